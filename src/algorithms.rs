@@ -1,12 +1,29 @@
 //! Contains the implementation of the algorithms used in the simulation.
 //! 
-//! Currently, the simulation supports two algorithms:
+//! Currently, the simulation supports nine algorithms:
 //! - [Jarvis March](https://en.wikipedia.org/wiki/Gift_wrapping_algorithm)
 //! - [Kirkpatrick Seidel](https://graphics.stanford.edu/courses/cs268-16-fall/Notes/KirkSeidel.pdf)
+//! - [Andrew's Monotone Chain](https://en.wikibooks.org/wiki/Algorithm_Implementation/Geometry/Convex_hull/Monotone_chain)
+//! - [Quickhull](https://en.wikipedia.org/wiki/Quickhull)
+//! - [Chan's algorithm](https://en.wikipedia.org/wiki/Chan%27s_algorithm)
+//! - [Divide and Conquer](https://doi.org/10.1145/359423.359430) by Preparata and Hong
+//! - [Randomized Incremental](https://en.wikipedia.org/wiki/Convex_hull_algorithms#Incremental_convex_hull_algorithms) construction
+//! - [Melkman's algorithm](https://maxgoldste.in/melkman/) for simple polylines
+//! - [Bentley-Faust-Preparata](https://doi.org/10.1145/358396.358402) approximate hull
 //! 
 //! Furthermore contains algorithm relevant functions.
+//!
+//! The hull algorithms are generic over the [Point] type, so they work with `f32` as well as `f64`
+//! coordinates. The primitives of the drawing history are always converted to [Vec2] for rendering.
 
 use bevy::prelude::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "operation-counts")]
+use std::cell::Cell;
+use std::collections::{HashSet, VecDeque};
+
+use crate::point::{Point, Scalar};
 
 /// Bevy resource that contains all the point history, so that they can be animated later.
 /// Support all primitives under [LineType].
@@ -24,17 +41,72 @@ pub struct Gizmo;
 pub struct ConvexHull;
 
 /// Enum representing the implemented algorithms
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum AlgorithmType {
     JarvisMarch,
     KirkPatrickSeidel,
+    MonotoneChain,
+    Quickhull,
+    Chan,
+    DivideAndConquer,
+    RandomizedIncremental,
+    Melkman,
+    Approximate,
+}
+
+impl AlgorithmType {
+    /// Returns the name of the algorithm on the command line and in the query parameters of the web build
+    pub fn name(self) -> &'static str {
+        match self {
+            AlgorithmType::JarvisMarch => "jarvis",
+            AlgorithmType::KirkPatrickSeidel => "kps",
+            AlgorithmType::MonotoneChain => "monotone",
+            AlgorithmType::Quickhull => "quickhull",
+            AlgorithmType::Chan => "chan",
+            AlgorithmType::DivideAndConquer => "divide",
+            AlgorithmType::RandomizedIncremental => "incremental",
+            AlgorithmType::Melkman => "melkman",
+            AlgorithmType::Approximate => "approximate",
+        }
+    }
+}
+
+impl std::str::FromStr for AlgorithmType {
+    type Err = String;
+
+    /// Parses the [name](AlgorithmType::name) of an algorithm
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "jarvis" => Ok(AlgorithmType::JarvisMarch),
+            "kps" => Ok(AlgorithmType::KirkPatrickSeidel),
+            "monotone" => Ok(AlgorithmType::MonotoneChain),
+            "quickhull" => Ok(AlgorithmType::Quickhull),
+            "chan" => Ok(AlgorithmType::Chan),
+            "divide" => Ok(AlgorithmType::DivideAndConquer),
+            "incremental" => Ok(AlgorithmType::RandomizedIncremental),
+            "melkman" => Ok(AlgorithmType::Melkman),
+            "approximate" => Ok(AlgorithmType::Approximate),
+            other => Err(format!("Unknown algorithm `{}`", other)),
+        }
+    }
 }
 
 /// Bevy resource that contains the current algorithm being used
 #[derive(Resource)]
 pub struct Algorithm(pub AlgorithmType);
 
+/// Default tolerance used by [orientation] to treat nearly collinear points as collinear.
+///
+/// The tolerance bounds the sine of the turn angle between the points rather than the raw
+/// cross product, so it works the same for the small and the large spreads the distributions produce.
+pub const DEFAULT_ORIENTATION_EPSILON: f32 = 1e-5;
+
+/// Bevy resource that contains the tolerance used by [orientation], see [DEFAULT_ORIENTATION_EPSILON]
+#[derive(Resource)]
+pub struct OrientationEpsilon(pub f32);
+
 /// Enum representing the different types of draw calls the simulation can make
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum LineType {
     /// Represents a line that is part of the convex hull
     PartOfHull(Vec2, Vec2),
@@ -42,11 +114,162 @@ pub enum LineType {
     Temporary(Vec2, Vec2),
     /// Represents a text comment that explains the current step
     TextComment(String),
-    /// Represents a vertical line at a given x coordinate
-    VerticalLine(f32),
+    /// Represents a vertical line at a given x coordinate, found at the given depth of the recursion
+    VerticalLine(f32, usize),
+    /// Highlights an input point that lies on a hull edge without being a vertex
+    CollinearPoint(Vec2),
+    /// Represents a circle with the given center and radius
+    Circle(Vec2, f32),
+    /// Shades the wedge of the circle with the given center and radius, starting at the given angle and sweeping
+    /// counterclockwise by the signed angle after it, both in radians. The wedge of the best candidate so far,
+    /// e.g. while [jarvis_march] searches the next vertex, is marked with the flag.
+    Arc(Vec2, f32, f32, f32, bool),
+    /// Represents the diameter of the hull, the segment between its two farthest points
+    Diameter(Vec2, Vec2),
+    /// Highlights the edge that closes the hull, before it is added with [LineType::PartOfHull]
+    ClosingEdge(Vec2, Vec2),
+    /// Represents an edge of the convex layer with the given index, see [convex_layers].
+    /// [chan] draws the hulls of its groups with it as well, one color per group.
+    LayerEdge(Vec2, Vec2, usize),
+    /// Marks a vertex of the finished hull
+    HullVertex(Vec2),
+    /// Shades the triangle with the given corners, e.g. the region Quickhull discards
+    Region(Vec2, Vec2, Vec2),
+    /// Shades the convex polygon with the given corners in the translucent color of the layer with the given
+    /// index, see [LineType::LayerEdge]. [kirk_patrick_seidel] shades the subproblems left and right of its bridges.
+    Polygon(Vec<Vec2>, usize),
+    /// Fills the finished hull with the given vertices, which are in counterclockwise order like the hulls of the algorithms
+    FilledHull(Vec<Vec2>),
+    /// Highlights a candidate tangent between two hulls, e.g. while [divide_and_conquer] merges them
+    Tangent(Vec2, Vec2),
+    /// Grays out an input point that was discarded before running the algorithm, see [akl_toussaint]
+    DiscardedPoint(Vec2),
+    /// Puts a halo ring around a point, its color telling the role of the point in the current step
+    HighlightPoint(Vec2, HighlightKind),
     /// Clears the screen
-    #[allow(dead_code)]
     ClearScreen,
+    /// Tags the step as a milestone of the algorithm with the given name, e.g. a bridge or a hull edge being found.
+    ///
+    /// Like [LineType::ClearScreen] it draws nothing, the simulation lets the user jump between the milestones.
+    Milestone(String),
+}
+
+/// Role of a point marked with a [LineType::HighlightPoint]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HighlightKind {
+    /// The point the algorithm is working from, e.g. the last vertex found by [jarvis_march]
+    Current,
+    /// A point the algorithm is considering
+    Candidate,
+    /// A point the algorithm ruled out of the hull
+    Rejected,
+    /// A point the algorithm confirmed as a vertex of the hull
+    Confirmed,
+}
+
+/// An event of the algorithms, which can be recorded alongside the drawing history for tooling and tests.
+///
+/// Unlike [LineType] the events describe what the algorithms decided rather than what to draw,
+/// and they can be serialized, e.g. to JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HullEvent {
+    /// Jarvis March found the leftmost point, where it starts wrapping
+    LeftmostPoint(Vec2),
+    /// Jarvis March or Chan's algorithm tested whether `candidate` is the next vertex after `from`
+    CandidateTested { from: Vec2, candidate: Vec2 },
+    /// An edge was added to the hull
+    EdgeAccepted(Vec2, Vec2),
+    /// KPS split the points at the median `x` at the given depth of the recursion
+    MedianChosen { depth: usize, x: f32 },
+    /// KPS found the bridge over the median at the given depth of the recursion
+    BridgeFound { depth: usize, left: Vec2, right: Vec2 },
+    /// KPS solved the subproblem of connecting `min` to `max` over the given number of points, see [recursion_tree].
+    ///
+    /// The bridge is [None] when the subproblem was given up on at the recursion limit.
+    Subproblem {
+        depth: usize,
+        upper: bool,
+        min: Vec2,
+        max: Vec2,
+        points: usize,
+        bridge: Option<(Vec2, Vec2)>,
+    },
+    /// Quickhull found the point farthest outside the segment from `a` to `b` at the given depth of the recursion
+    FarthestPoint { depth: usize, a: Vec2, b: Vec2, farthest: Vec2 },
+    /// Chan's algorithm started wrapping, assuming the hull has at most `guess` vertices
+    HullSizeGuess { guess: usize },
+    /// Divide and conquer merged two hulls with the given tangents at the given depth of the recursion
+    HullsMerged { depth: usize, upper: (Vec2, Vec2), lower: (Vec2, Vec2) },
+    /// The randomized incremental construction inserted `point`, which could see the given number of hull edges
+    PointInserted { point: Vec2, visible_edges: usize },
+    /// Melkman's algorithm added `point` to both ends of its deque, after popping the given numbers of points
+    DequeUpdated { point: Vec2, popped_top: usize, popped_bottom: usize },
+    /// The approximate hull split the points into `strips` vertical strips and kept `candidates` of their extrema
+    StripExtrema { strips: usize, candidates: usize },
+}
+
+/// Records the event into the log, if there is one. The event is only built when it is recorded.
+fn record(events: &mut Option<&mut Vec<HullEvent>>, event: impl FnOnce() -> HullEvent) {
+    if let Some(events) = events {
+        events.push(event());
+    }
+}
+
+/// Numbers of the primitive operations the algorithms performed during [count_operations].
+///
+/// They show the complexity of the algorithms independently of the machine, which the timings can't.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct OperationCounts {
+    /// Calls of [orientation], the primitive of Jarvis March and of most other algorithms
+    pub orientation_tests: usize,
+    /// Computed medians, KPS takes the median `x` of every split and the median slope of every bridge round
+    pub medians: usize,
+    /// Comparisons against the pivots of the selection behind [median]
+    pub comparisons: usize,
+    /// Rounds of the prune and search of the bridges of KPS
+    pub bridge_rounds: usize,
+    /// Candidates the bridge rounds of KPS discarded
+    pub pruned_candidates: usize,
+}
+
+#[cfg(feature = "operation-counts")]
+thread_local! {
+    /// Operation counts of the [count_operations] running on this thread, if there is one
+    static OPERATION_COUNTS: Cell<Option<OperationCounts>> = const { Cell::new(None) };
+}
+
+/// Updates the operation counts of the running [count_operations], if there is one
+#[cfg(feature = "operation-counts")]
+fn count(update: impl FnOnce(&mut OperationCounts)) {
+    OPERATION_COUNTS.with(|counts| {
+        if let Some(mut current) = counts.get() {
+            update(&mut current);
+            counts.set(Some(current));
+        }
+    });
+}
+
+/// Counts nothing, so the algorithms don't pay for the counting when it isn't compiled in
+#[cfg(not(feature = "operation-counts"))]
+#[inline(always)]
+fn count(_update: impl FnOnce(&mut OperationCounts)) {}
+
+/// Runs `run`, counting the primitive operations of the algorithms it calls on this thread.
+///
+/// Runs nested into each other only count into the innermost one. The operations are only counted with the
+/// `operation-counts` feature, without it the counts are all zero.
+#[cfg(feature = "operation-counts")]
+pub fn count_operations<R>(run: impl FnOnce() -> R) -> (R, OperationCounts) {
+    let outer = OPERATION_COUNTS.with(|counts| counts.replace(Some(OperationCounts::default())));
+    let result = run();
+    let counts = OPERATION_COUNTS.with(|counts| counts.replace(outer));
+    (result, counts.unwrap_or_default())
+}
+
+/// Runs `run` and returns all zero counts, as the `operation-counts` feature that counts the operations is disabled
+#[cfg(not(feature = "operation-counts"))]
+pub fn count_operations<R>(run: impl FnOnce() -> R) -> (R, OperationCounts) {
+    (run(), OperationCounts::default())
 }
 
 /// # Implementation of the [Jarvis March](https://en.wikipedia.org/wiki/Gift_wrapping_algorithm) algorithm (Gift-Wrapping algorithm)
@@ -79,7 +302,19 @@ pub enum LineType {
 ///
 /// Thus this algorithm yield the hull in $O(nh)$ time, wher $n$ is total number of points and $h$
 /// is number of point on the hull.
-pub fn jarvis_march(points: Vec<Vec2>, drawing_history: &mut Vec<Vec<LineType>>) -> Vec<Vec2> {
+///
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+/// When `events` is given the [HullEvent]s of the algorithm are recorded into it.
+pub fn jarvis_march<P: Point>(
+    points: Vec<P>,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    mut events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if let Some(hull) = single_distinct_point(&points, drawing_history) {
+        return hull;
+    }
+
     let n = points.len();
     if n < 3 {
         return Vec::new();
@@ -87,18 +322,23 @@ pub fn jarvis_march(points: Vec<Vec2>, drawing_history: &mut Vec<Vec<LineType>>)
 
     let mut hull = Vec::new();
 
-    // Find the leftmost point
+    // Find the leftmost point, the lowest one of them so that it is a vertex rather than
+    // a point on the left edge, which the walk could pass without stopping
     let mut l = 0;
     for i in 1..n {
-        if points[i].x < points[l].x {
+        if (points[i].x(), points[i].y()) < (points[l].x(), points[l].y()) {
             l = i;
         }
     }
+    record(&mut events, || HullEvent::LeftmostPoint(points[l].to_vec2()));
 
     // Start from leftmost point, keep moving counterclockwise
     // until reach the start point again
     let mut p = l;
     let mut q;
+    // Direction of the last hull edge, which the angles of the candidates are measured from.
+    // Nothing lies left of the leftmost point, so the wrapping starts by looking straight down
+    let mut direction = Vec2::NEG_Y;
     loop {
         let mut temp = vec![];
 
@@ -106,32 +346,72 @@ pub fn jarvis_march(points: Vec<Vec2>, drawing_history: &mut Vec<Vec<LineType>>)
         hull.push(points[p]);
 
         // Search for a point 'q' such that orientation(p, x, q) is
-        // counterclockwise for all points 'x'
-        q = (p + 1) % n;
+        // counterclockwise for all points 'x'. Duplicates of p give no direction, so they are skipped
+        q = (1..n).map(|offset| (p + offset) % n).find(|&r| points[r] != points[p]).unwrap();
+        let mut best_so_far = vec![q];
         for r in 0..n {
-            // If r is more counterclockwise than current q, then update q
-            if let Orientation::Counterclockwise = orientation(&points[p], &points[r], &points[q]) {
+            record(&mut events, || HullEvent::CandidateTested {
+                from: points[p].to_vec2(),
+                candidate: points[r].to_vec2(),
+            });
+
+            // If r is more counterclockwise than current q, then update q. Of collinear candidates
+            // ahead of p the farthest one is taken, so the walk can't go back and forth between them
+            let (to_r, to_q) = (points[r].minus(&points[p]), points[q].minus(&points[p]));
+            let better = match orientation(&points[p], &points[r], &points[q], epsilon) {
+                Orientation::Counterclockwise => true,
+                Orientation::Colinear => to_r.dot(&to_q) > P::Scalar::ZERO && to_r.dot(&to_r) > to_q.dot(&to_q),
+                Orientation::Clockwise => false,
+            };
+            if better {
                 q = r;
+                best_so_far.push(r);
             }
 
             // Add line from points[p] to points[q] to drawing history
             // if it's not already part of the hull
             if !hull.contains(&points[r]) {
-                temp.push(LineType::Temporary(points[p], points[r]));
+                temp.push(LineType::Temporary(points[p].to_vec2(), points[r].to_vec2()));
+            }
+        }
+
+        // Only the candidates that were the best so far get a wedge, the last one of them is the next vertex
+        for &candidate in &best_so_far {
+            temp.extend(angle_arc(points[p].to_vec2(), direction, points[candidate].to_vec2(), candidate == q));
+            if candidate != q {
+                temp.push(LineType::HighlightPoint(points[candidate].to_vec2(), HighlightKind::Candidate));
             }
         }
+        temp.push(LineType::HighlightPoint(points[p].to_vec2(), HighlightKind::Current));
+        temp.push(LineType::HighlightPoint(points[q].to_vec2(), HighlightKind::Confirmed));
+        direction = points[q].to_vec2() - points[p].to_vec2();
+
+        // While we don't come back to the first point or a duplicate of it. Within the tolerance the
+        // first point can lie on the edge to q, which then passes it, so the walk also stops at any
+        // vertex it already accepted. A hull has at most n vertices, more mean the walk is cycling.
+        // The closing edge is highlighted first, it becomes part of the hull in the last step
+        let (to_l, to_q) = (points[l].minus(&points[p]), points[q].minus(&points[p]));
+        let passes_l = matches!(orientation(&points[p], &points[l], &points[q], epsilon), Orientation::Colinear)
+            && to_l.dot(&to_q) > P::Scalar::ZERO
+            && to_l.dot(&to_l) <= to_q.dot(&to_q);
+        if points[q] == points[l] || passes_l || hull.contains(&points[q]) || hull.len() >= n {
+            temp.push(LineType::ClosingEdge(points[p].to_vec2(), points[l].to_vec2()));
+            temp.push(LineType::TextComment(format!(
+                "Closing the hull from {} back to {}",
+                points[p], points[l]
+            )));
+            drawing_history.push(temp);
+            break;
+        }
 
-        temp.push(LineType::PartOfHull(points[p], points[q]));
+        temp.push(LineType::PartOfHull(points[p].to_vec2(), points[q].to_vec2()));
+        temp.push(LineType::Milestone(format!("Hull edge from {} to {}", points[p], points[q])));
+        record(&mut events, || HullEvent::EdgeAccepted(points[p].to_vec2(), points[q].to_vec2()));
 
         // Now q is the most counterclockwise with respect to p
         // Set p as q for next iteration, so that q is added to result 'hull'
         p = q;
 
-        // While we don't come to first point
-        if p == l {
-            break;
-        }
-
         temp.push(LineType::TextComment(format!(
             "Checking all points starting from {} that are least counter clockwise",
             points[p]
@@ -139,331 +419,2033 @@ pub fn jarvis_march(points: Vec<Vec2>, drawing_history: &mut Vec<Vec<LineType>>)
         drawing_history.push(temp);
     }
 
-    drawing_history.push(vec![
-        LineType::PartOfHull(hull[hull.len() - 1], hull[0]),
+    record(&mut events, || HullEvent::EdgeAccepted(hull[hull.len() - 1].to_vec2(), hull[0].to_vec2()));
+    let mut step = vec![
+        LineType::PartOfHull(hull[hull.len() - 1].to_vec2(), hull[0].to_vec2()),
         LineType::TextComment("Found all points of the Hull".to_string()),
-    ]);
+    ];
+    step.extend(hull_vertices(&hull));
+    drawing_history.push(step);
 
     hull
 }
 
-/// Represent the orientation between three points (consecutive)
-enum Orientation {
-    /// Has $\lt 0$ angle between the lines made by the points
-    Clockwise,
-    /// Has $\gt 0$ angle between the lines made by the points
-    Counterclockwise,
-    /// Has $\pi$ radian angle between the lines made by the points
-    Colinear,
-}
-
-/// Finds the orientation of three points and returns [Orientation]
-/// 
-/// Calculates the angle between $p, q, r$ using $(q_y - p_y) \cdot (r_x - q_x) - (q_x - p_x) \cdot (r_y - q_y)$
-fn orientation(p: &Vec2, q: &Vec2, r: &Vec2) -> Orientation {
-    let val = (q.y - p.y) * (r.x - q.x) - (q.x - p.x) * (r.y - q.y);
-
-    if val == 0.0 {
-        return Orientation::Colinear;
-    }
-    if val > 0.0 {
-        return Orientation::Clockwise;
+/// Returns the wedge at `from` between the `direction` of the last hull edge and the edge to the candidate.
+///
+/// Its radius is a quarter of the candidate edge, so the wedges of several candidates stay apart.
+/// There is none for a candidate at `from` itself, which has no direction.
+fn angle_arc(from: Vec2, direction: Vec2, candidate: Vec2, best: bool) -> Option<LineType> {
+    let edge = candidate - from;
+    if edge == Vec2::ZERO || direction == Vec2::ZERO {
+        return None;
     }
-
-    Orientation::Counterclockwise
-}
-
-/// Represents the type of hull being calculated. Used for drawing purposes only in [kirk_patrick_seidel].
-enum HullType {
-    UpperHull,
-    LowerHull,
+    let start = direction.y.atan2(direction.x);
+    let sweep = direction.perp_dot(edge).atan2(direction.dot(edge));
+    Some(LineType::Arc(from, edge.length() * 0.25, start, sweep, best))
 }
 
-/// Implementation of the [Kirkpatrick Seidel](https://graphics.stanford.edu/courses/cs268-16-fall/Notes/KirkSeidel.pdf) Algorithm
+/// # Implementation of [Andrew's Monotone Chain](https://en.wikibooks.org/wiki/Algorithm_Implementation/Geometry/Convex_hull/Monotone_chain) algorithm
+/// This algorithm is used to calculate the convex hull of given set of points.
+/// It has a `O(n log n)` time complexity, where `n` is the number of points.
 ///
-/// ![](https://d3i71xaburhd42.cloudfront.net/9565745ce8b6c2114072e9620981fb97ed38e471/2-Figure1-1.png)
+/// Like [kirk_patrick_seidel] it splits the hull into a lower and an upper chain between the leftmost and
+/// the rightmost point, but builds each chain with a single sweep over the sorted points.
 ///
-/// ## Pseudocode [ref](http://www.cse.yorku.ca/~andy/courses/6114/lecture-notes/KirkSeidel.pdf)
+/// ## Pseudocode
 /// ```pseudocode
-/// Algorithm UpperHull(P)
-/// 0. if |P| ≤ 2 then return the obvious answer
-/// 1. else begin
-/// 2. Compute the median of x-coordinates of points in P.
-/// 3. Partition P into two sets L and R each of size about n/2 around the median.
-/// 4. Find the upper bridge pq of L and R, p∈L, and q∈R
-/// 5. L′ ← { r ∈L x(r) ≤ x(p) }
-/// 6. R′ ← { r ∈R x(r) ≥ x(q) }
-/// 7. LUH ← UpperHull(L′)
-/// 8. RUH ← UpperHull(R′)
-/// 9. return the concatenated list LUH, pq, RUH as the upper hull of P.
-/// 10. end
+/// algorithm monotone_chain(S) is
+///     sort S by x coordinate, ties broken by y coordinate
+///     L := empty list
+///     for p in S do
+///         while |L| >= 2 and L[-2], L[-1], p do not make a counterclockwise turn do
+///             remove the last point of L
+///         append p to L
+///     U := the same sweep over S in reverse order
+///     return L and U without their last points, as both end at the start of the other
 /// ```
-///
 /// ## Analysis
-/// This is a divide-&-conquer algorithm. The key step is the computation of the upper
-/// bridge which is based on the prune-&-search technique. This step can be done in O(n) time. We also know that step
-/// 2 can be done in $O(n)$ time by the linear time median finding algorithm. Hence, steps 3-6
-/// can be done in $O(n)$ time. For the purposes of analyzing algorithm `UpperHall(P)`, let us
-/// assume the upper hull of $P$ consists of $h$ edges. Our analysis will use both parameters n
-/// (input size) and $h$ (output size). Let $T(n, h)$ denote the worst-case time complexity of the
-/// algorithm. Suppose $\text{LUH}$ and $\text{RUH}$ in steps 7 and 8 consist of $h1$ and $h2$ edges, respectively. Since $|L'| ≤ |L|$ and $|R'| ≤ |R|$, the two recursive calls in steps 7 and 8 take time
-/// $T(\frac{n}2, h_1)$ and $T(\frac{n}2, h_2)$ time. (Note that $h = 1 + h1 + h2$. Hence, $h2 = h − 1 − h1$.)
-/// Therefore, the recurrence that describes the worst-case time complexity of the algorithm is
-/// $$
-/// T(n, h) = O(n) + max_{h1} \{ T(\frac{n}{2}, h_1) + T(\frac{n}{2}, h-1-h_1)\}
-/// $$
-/// if $h > 2$, otherwise $T(n, h) = O(n)$.
-///
-/// Suppose the two occurences of $O(n)$ in the above recurrence are at most $cn$,
-/// where $c$ is a suitably large constant. We will show by induction on $h$ that
-/// $T(n, h) \leq cn \log(h)$ for all $n$ and $h \leq 2$. For the base case where $h = 2$,
-/// $T(n, h) \leq cn \leq cn\log(2) = cn \log(h)$. For the inductive case,
-///
-/// $T(n, h)$
-///
-/// $\leq cn + max_{h1} \{ c\frac{n}{2}\log(h_1) + c\frac{n}{2}\log(h-1-h_1)\}$
-///
-/// $= cn + c\frac{n}{2}max_{h1} \log(h_1 (h - 1 - h_1))$
+/// Sorting the points takes $O(n\log(n))$ time. Every point is added to each chain once and removed at
+/// most once, so both sweeps take $O(n)$ time.
 ///
-/// $\leq cn + c\frac{n}{2}\log(\frac{h}{2} \cdot \frac{h}{2})$
-///
-/// $= cn + c\frac{n}{2}2\log(\frac{h}2)$
-///
-/// $= cn\log(h)$
+/// Thus this algorithm yields the hull in $O(n\log(n))$ time.
 ///
-/// Thus we can claim runtime of kirpatrick seidel algorithm to be $O(n\log(h))$.
-pub fn kirk_patrick_seidel(
-    points: Vec<Vec2>,
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+/// When `events` is given the [HullEvent]s of the algorithm are recorded into it.
+pub fn monotone_chain<P: Point>(
+    points: Vec<P>,
     drawing_history: &mut Vec<Vec<LineType>>,
-) -> Vec<Vec2> {
-    let mut upper_hull_vec = upper_hull(&points, drawing_history, &HullType::UpperHull);
-    drawing_history.push(vec![LineType::TextComment("Added upper hull".to_string())]);
+    epsilon: f32,
+    mut events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if let Some(hull) = single_distinct_point(&points, drawing_history) {
+        return hull;
+    }
 
-    let mut lower_hull_vec = upper_hull(
-        &points
-            .iter()
-            .map(|point| Vec2 {
-                x: point.x,
-                y: -point.y,
-            })
-            .collect::<Vec<_>>(),
-        drawing_history,
-        &HullType::LowerHull,
-    );
-    drawing_history.push(vec![LineType::TextComment("Added lower hull".to_string())]);
-    lower_hull_vec = lower_hull_vec
-        .iter()
-        .map(|point| Vec2 {
-            x: point.x,
-            y: -point.y,
-        })
-        .collect();
+    if points.len() < 3 {
+        return Vec::new();
+    }
 
-    // Time to merge lower and upper hull
-    let upper_hull_max = *upper_hull_vec
-        .iter()
-        .max_by(|a, b| a.x.partial_cmp(&b.x).unwrap())
-        .unwrap();
-    let upper_hull_min = *upper_hull_vec
-        .iter()
-        .min_by(|a, b| a.x.partial_cmp(&b.x).unwrap())
-        .unwrap();
-    let lower_hull_max = *lower_hull_vec
-        .iter()
-        .max_by(|a, b| a.x.partial_cmp(&b.x).unwrap())
-        .unwrap();
-    let lower_hull_min = *lower_hull_vec
-        .iter()
-        .min_by(|a, b| a.x.partial_cmp(&b.x).unwrap())
-        .unwrap();
+    let mut sorted_points = points;
+    sorted_points.sort_by(|a, b| (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap());
+    drawing_history.push(vec![LineType::TextComment(format!(
+        "Sorted the points from left to right, starting the lower chain at {}",
+        sorted_points[0]
+    ))]);
 
-    if upper_hull_max.x == lower_hull_max.x && upper_hull_min.y != lower_hull_min.y {
-        upper_hull_vec.push(lower_hull_max);
-        drawing_history.push(vec![
-            LineType::PartOfHull(upper_hull_max, lower_hull_max),
-            LineType::TextComment(format!(
-                "Adding right vertical edge between {} and {}",
-                upper_hull_max, lower_hull_max
-            )),
-        ]);
-    }
+    let mut lower = build_chain(sorted_points.iter(), "lower", drawing_history, epsilon);
+    let mut upper = build_chain(sorted_points.iter().rev(), "upper", drawing_history, epsilon);
 
-    if upper_hull_min.x == lower_hull_min.x && upper_hull_min.y != lower_hull_min.y {
-        upper_hull_vec.push(lower_hull_min);
-        drawing_history.push(vec![
-            LineType::PartOfHull(upper_hull_min, lower_hull_min),
-            LineType::TextComment(format!(
-                "Adding left vertical edge between {} and {}",
-                upper_hull_min, lower_hull_min
-            )),
-        ]);
+    // Each chain ends where the other one starts
+    lower.pop();
+    upper.pop();
+    let mut hull = lower;
+    hull.extend(upper);
+
+    for i in 0..hull.len() {
+        let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+        record(&mut events, || HullEvent::EdgeAccepted(a.to_vec2(), b.to_vec2()));
     }
 
-    drawing_history.push(vec![LineType::TextComment(
-        "Kirkseidel algorithm is complete".to_string(),
-    )]);
+    let mut step = vec![LineType::TextComment(
+        "Joined the lower and upper chains, the hull is complete".to_string(),
+    )];
+    step.extend(hull_vertices(&hull));
+    drawing_history.push(step);
 
-    upper_hull_vec.extend(lower_hull_vec);
-    upper_hull_vec
+    hull
 }
 
-/// Returns the upper hull of input points
-/// # Pseudocode
-/// ```text
-/// Procedure UPPER-HULL(S)
-/// 1. Initialization
-///     Let min and max be the indices of two points in S that form the left and right
-///     endpoint of the upper hull of S respectively, i.e.
-///         x(p_min) ≤ x(p_i) ≤ x(p_max) and
-///         y(p_min) ≥ y(p_i) if x(p_min) = x(p_i),
-///         y(p_max) ≥ y(p_i) if x(p_max) = x(p_i) for i = 1,..., n
-///     If min = max then print min and stop.
-///     Let T := {p_min, p_max} ∪ {p ∈ S | x(p_min) < x(p) < x(p_max)}.
-/// 2. return CONNECT(min, max, T)
-/// ```
-fn upper_hull(
-    points: &[Vec2],
+/// Sweeps over the points in the given order and returns the chain of [monotone_chain] they make.
+///
+/// Every step shows the chain so far along with the point being added. The finished chain is added to the hull.
+fn build_chain<'a, P: Point + 'a>(
+    points: impl Iterator<Item = &'a P>,
+    name: &str,
     drawing_history: &mut Vec<Vec<LineType>>,
-    hull_type: &HullType,
-) -> Vec<Vec2> {
-    let mut min_point = Vec2 {
-        x: f32::MAX,
-        y: f32::MIN,
-    };
-    for i in points.iter() {
-        if i.x < min_point.x || (i.x == min_point.x && i.y > min_point.y) {
-            min_point = *i;
-        }
-    }
+    epsilon: f32,
+) -> Vec<P> {
+    let mut chain: Vec<P> = Vec::new();
 
-    let mut max_point = Vec2 {
-        x: f32::MIN,
-        y: f32::MAX,
-    };
-    for i in points.iter() {
-        if i.x > max_point.x || (i.x == max_point.x && i.y > max_point.y) {
-            max_point = *i;
+    for &point in points {
+        let mut temp = vec![];
+        let mut comments = vec![];
+
+        // The last point of the chain is removed as long as it doesn't make a left turn
+        while chain.len() >= 2 {
+            let (a, b) = (chain[chain.len() - 2], chain[chain.len() - 1]);
+            if let Orientation::Counterclockwise = orientation(&a, &b, &point, epsilon) {
+                break;
+            }
+            temp.push(LineType::Temporary(b.to_vec2(), point.to_vec2()));
+            temp.push(LineType::HighlightPoint(b.to_vec2(), HighlightKind::Rejected));
+            comments.push(format!("Removing {} from the {} chain, it doesn't make a left turn", b, name));
+            chain.pop();
         }
-    }
 
-    if min_point == max_point {
-        drawing_history.push(vec![LineType::TextComment(
-            "Single point convex hull found, returning the point".to_string(),
-        )]);
-        return vec![min_point];
+        chain.push(point);
+        temp.push(LineType::HighlightPoint(point.to_vec2(), HighlightKind::Current));
+        temp.extend(chain.windows(2).map(|w| LineType::Temporary(w[0].to_vec2(), w[1].to_vec2())));
+        comments.push(format!("Adding {} to the {} chain", point, name));
+        temp.push(LineType::TextComment(comments.join("\n")));
+        drawing_history.push(temp);
     }
 
-    let mut temporary = vec![min_point, max_point];
-    temporary.extend(
-        points
-            .iter()
-            .filter(|p| p.x > min_point.x && p.x < max_point.x),
-    );
+    let mut step: Vec<_> = chain
+        .windows(2)
+        .map(|w| LineType::PartOfHull(w[0].to_vec2(), w[1].to_vec2()))
+        .collect();
+    step.push(LineType::TextComment(format!(
+        "Added the {} chain with {} points",
+        name,
+        chain.len()
+    )));
+    drawing_history.push(step);
 
-    connect(min_point, max_point, &temporary, drawing_history, hull_type)
+    chain
 }
 
-/// Returns the points that form the convex hull
-/// # Pseudocode
-/// ```text
-/// Procedure CONNECT(k, m, S)
-/// 1. Find a real number a such that
-///     x(p_i) ≤ a for ⌊|S|/2⌋ points in S and
-///     x(p_i) > a for ⌈|S|/2⌉ points in S.
-/// 2. Find the "bridge" over the vertical line L {(x, y) | x = a}, i.e.
-///     (i, j) := BRIDGE (S, a).
-/// 3. Let S_left := {p_i} ∪ {p ∈ S | x(p) < x(p_i)}.
-///     Let S_right := {p_j} ∪ {p ∈ S | x(p) > x(p_j)}.
-/// 4. If i = k
-///     then add_to_answer(print (i))
-///     else add_to_answer(CONNECT (k, i, S_left).)
-///    If j = m 
-///     then add_to_answer(print (j))
-///     else add_to_answer(CONNECT (j, m, S_right).)
-/// 5. return answer
+/// # Implementation of the [Quickhull](https://en.wikipedia.org/wiki/Quickhull) algorithm
+/// This algorithm is used to calculate the convex hull of given set of points.
+/// It has a `O(n log n)` expected time complexity and a `O(n^2)` worst case, where `n` is the number of points.
+///
+/// ## Pseudocode
+/// ```pseudocode
+/// algorithm quickhull(S) is
+///     A, B := leftmost and rightmost point of S
+///     return [A] + find_hull(A, B, points of S right of AB) + [B] + find_hull(B, A, points of S right of BA)
+///
+/// algorithm find_hull(A, B, S) is
+///     if S is empty then return []
+///     C := point of S farthest from the line AB
+///     # The points inside the triangle ABC can't be part of the hull
+///     return find_hull(A, C, points of S right of AC) + [C] + find_hull(C, B, points of S right of CB)
 /// ```
-/// Note: hear print means add to the output answer
-fn connect(
-    min: Vec2,
-    max: Vec2,
-    points: &[Vec2],
+/// ## Analysis
+/// Every level of the recursion partitions the points in $O(n)$ time. When the farthest points split
+/// the remaining points evenly there are $O(\log(n))$ levels, which yields the hull in $O(n\log(n))$ time.
+/// When every split only removes a single point, e.g. for points on a parabola, there are $O(n)$ levels instead,
+/// which takes $O(n^2)$ time.
+///
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+/// When `events` is given the [HullEvent]s of the algorithm are recorded into it.
+pub fn quickhull<P: Point>(
+    points: Vec<P>,
     drawing_history: &mut Vec<Vec<LineType>>,
-    hull_type: &HullType,
-) -> Vec<Vec2> {
-    let median = median_of_medians(&points.iter().map(|point| point.x).collect::<Vec<_>>());
-    drawing_history.push(vec![
-        LineType::VerticalLine(median),
-        LineType::TextComment(format!("Found the median at {}", median)),
-    ]);
+    epsilon: f32,
+    mut events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if let Some(hull) = single_distinct_point(&points, drawing_history) {
+        return hull;
+    }
 
-    let (left, right) = bridge(points, median);
-    let (drawing_left, drawing_right) = match hull_type {
-        HullType::LowerHull => (
-            Vec2 {
-                x: left.x,
-                y: -left.y,
-            },
-            Vec2 {
-                x: right.x,
-                y: -right.y,
-            },
-        ),
-        _ => (left, right),
-    };
-    let (drawing_min, drawing_max) = match hull_type {
-        HullType::LowerHull => (
-            Vec2 {
-                x: min.x,
-                y: -min.y,
-            },
-            Vec2 {
-                x: max.x,
-                y: -max.y,
-            },
-        ),
-        _ => (min, max),
-    };
-    
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let lexicographic = |a: &&P, b: &&P| (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap();
+    let left = *points.iter().min_by(lexicographic).unwrap();
+    let right = *points.iter().max_by(lexicographic).unwrap();
     drawing_history.push(vec![
-        LineType::PartOfHull(drawing_left, drawing_right),
+        LineType::Temporary(left.to_vec2(), right.to_vec2()),
         LineType::TextComment(format!(
-            "Found the bridge points {} and {}",
-            drawing_left, drawing_right
+            "Splitting the points by the segment between the leftmost point {} and the rightmost point {}",
+            left, right
         )),
-        LineType::Temporary(drawing_min, drawing_left),
-        LineType::Temporary(drawing_left, drawing_right),
-        LineType::Temporary(drawing_right, drawing_max),
-        LineType::Temporary(drawing_max, drawing_min),
     ]);
 
-    let mut left_points = vec![left];
-    left_points.extend(points.iter().filter(|p| p.x < left.x));
-
-    let mut right_points = vec![right];
-    right_points.extend(points.iter().filter(|p| p.x > right.x));
+    // Walking below the segment first yields the hull in counterclockwise order
+    let mut hull = vec![left];
+    let below = outside_points(&points, left, right, epsilon);
+    find_hull(left, right, below, 0, &mut hull, drawing_history, epsilon, &mut events);
+    hull.push(right);
+    let above = outside_points(&points, right, left, epsilon);
+    find_hull(right, left, above, 0, &mut hull, drawing_history, epsilon, &mut events);
 
-    let mut output = vec![];
-    if left == min {
-        output.extend(vec![left]);
-    } else {
-        output.extend(connect(min, left, &left_points, drawing_history, hull_type));
+    for i in 0..hull.len() {
+        let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+        record(&mut events, || HullEvent::EdgeAccepted(a.to_vec2(), b.to_vec2()));
     }
 
-    if right == max {
-        output.extend(vec![right]);
-    } else {
-        output.extend(connect(
-            right,
-            max,
-            &right_points,
-            drawing_history,
-            hull_type,
+    let mut step = vec![LineType::TextComment("Quickhull is complete".to_string())];
+    step.extend(hull_vertices(&hull));
+    drawing_history.push(step);
+
+    hull
+}
+
+/// Returns the points strictly right of the segment from `a` to `b`, i.e. outside of the hull when walking it counterclockwise
+fn outside_points<P: Point>(points: &[P], a: P, b: P, epsilon: f32) -> Vec<P> {
+    points
+        .iter()
+        .filter(|p| matches!(orientation(&a, &b, p, epsilon), Orientation::Clockwise))
+        .copied()
+        .collect()
+}
+
+/// Adds the vertices of the hull strictly between `a` and `b` to `hull`, see [quickhull].
+///
+/// `points` are the points outside the segment from `a` to `b`. The farthest one is a vertex of the hull,
+/// the points in the triangle it makes with the segment are discarded and the rest are searched recursively.
+#[allow(clippy::too_many_arguments)]
+fn find_hull<P: Point>(
+    a: P,
+    b: P,
+    points: Vec<P>,
+    depth: usize,
+    hull: &mut Vec<P>,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    events: &mut Option<&mut Vec<HullEvent>>,
+) {
+    // The distance from the line through a and b is proportional to this cross product
+    let distance = |p: &P| ((b.x() - a.x()) * (p.y() - a.y()) - (b.y() - a.y()) * (p.x() - a.x())).abs();
+    let Some(farthest) = points
+        .iter()
+        .max_by(|p, q| distance(p).partial_cmp(&distance(q)).unwrap())
+        .copied()
+    else {
+        drawing_history.push(vec![
+            LineType::PartOfHull(a.to_vec2(), b.to_vec2()),
+            LineType::TextComment(format!(
+                "Depth {}: no points outside the segment between {} and {}, it is an edge of the hull",
+                depth, a, b
+            )),
+            LineType::Milestone(format!("Hull edge from {} to {}", a, b)),
+        ]);
+        return;
+    };
+    record(events, || HullEvent::FarthestPoint {
+        depth,
+        a: a.to_vec2(),
+        b: b.to_vec2(),
+        farthest: farthest.to_vec2(),
+    });
+
+    let left = outside_points(&points, a, farthest, epsilon);
+    let right = outside_points(&points, farthest, b, epsilon);
+    let discarded = points.len() - left.len() - right.len() - 1;
+
+    drawing_history.push(vec![
+        LineType::Region(a.to_vec2(), b.to_vec2(), farthest.to_vec2()),
+        LineType::Temporary(a.to_vec2(), b.to_vec2()),
+        LineType::Temporary(a.to_vec2(), farthest.to_vec2()),
+        LineType::Temporary(farthest.to_vec2(), b.to_vec2()),
+        LineType::TextComment(format!(
+            "Depth {}: {} is the farthest point from the segment between {} and {}, discarding the {} points inside the triangle",
+            depth, farthest, a, b, discarded
+        )),
+    ]);
+
+    find_hull(a, farthest, left, depth + 1, hull, drawing_history, epsilon, events);
+    hull.push(farthest);
+    find_hull(farthest, b, right, depth + 1, hull, drawing_history, epsilon, events);
+}
+
+/// # Implementation of [Chan's algorithm](https://en.wikipedia.org/wiki/Chan%27s_algorithm)
+/// This algorithm is used to calculate the convex hull of given set of points.
+/// It has a `O(n log h)` time complexity, where `n` is the number of points and `h` is the number of points on the convex hull.
+///
+/// ## Pseudocode
+/// ```pseudocode
+/// algorithm chan(S) is
+///     for t := 1, 2, ... do
+///         m := min(2^(2^t), |S|)     # the guess of h
+///         split S into groups of at most m points, compute the hull of each with Graham scan
+///         p := leftmost point in S
+///         repeat m times
+///             q := the tangent from p to each group hull, found with a binary search
+///             p := the most clockwise of these tangents, the next point of the hull
+///             if p is the leftmost point then return the hull
+/// ```
+/// ## Analysis
+/// The Graham scans of the groups take $O(n\log(m))$ time. Each of the at most $m$ wrapping steps queries the
+/// tangents of $\lceil n/m \rceil$ groups in $O(\log(m))$ time, which also adds up to $O(n\log(m))$.
+///
+/// The guess is squared after every failed round, so the round with $m \geq h$ is reached with $m \leq h^2$.
+/// The rounds take $O(n 2^t)$ time, which sums to $O(n\log(h))$ over the $\log(\log(h))$ rounds.
+///
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+/// When `events` is given the [HullEvent]s of the algorithm are recorded into it.
+pub fn chan<P: Point>(
+    points: Vec<P>,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    mut events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if let Some(hull) = single_distinct_point(&points, drawing_history) {
+        return hull;
+    }
+
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    // Duplicates would give tangents of zero length, which can't be compared with the others
+    let mut seen = HashSet::new();
+    let points: Vec<P> = points.into_iter().filter(|p| seen.insert(point_key(p))).collect();
+    let n = points.len();
+    let start = *points
+        .iter()
+        .min_by(|a, b| (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap())
+        .unwrap();
+
+    let mut exponent = 2;
+    loop {
+        let guess = 2usize.checked_pow(exponent).map_or(n, |guess| guess.min(n));
+        record(&mut events, || HullEvent::HullSizeGuess { guess });
+
+        let groups: Vec<Vec<P>> = points.chunks(guess).map(|group| graham_scan(group, epsilon)).collect();
+        let mut step = vec![LineType::ClearScreen];
+        for (index, group) in groups.iter().enumerate() {
+            step.extend((0..group.len()).map(|i| {
+                LineType::LayerEdge(group[i].to_vec2(), group[(i + 1) % group.len()].to_vec2(), index)
+            }));
+        }
+        step.push(LineType::TextComment(format!(
+            "Guessing the hull has at most {} vertices, found the hulls of {} groups with Graham scan",
+            guess,
+            groups.len()
+        )));
+        step.push(LineType::Milestone(format!("Guess of {} vertices", guess)));
+        drawing_history.push(step);
+
+        if let Some(hull) = wrap_groups(&groups, start, guess, drawing_history, epsilon, &mut events) {
+            for i in 0..hull.len() {
+                let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+                record(&mut events, || HullEvent::EdgeAccepted(a.to_vec2(), b.to_vec2()));
+            }
+
+            let mut step = vec![LineType::ClearScreen];
+            step.extend((0..hull.len()).map(|i| {
+                LineType::PartOfHull(hull[i].to_vec2(), hull[(i + 1) % hull.len()].to_vec2())
+            }));
+            step.push(LineType::TextComment(format!(
+                "Chan's algorithm is complete, the hull has {} vertices",
+                hull.len()
+            )));
+            step.extend(hull_vertices(&hull));
+            drawing_history.push(step);
+
+            return hull;
+        }
+
+        // A guess of every point always closes the hull, unless the tolerance lets the wrapping go astray
+        if guess == n {
+            drawing_history.push(vec![LineType::TextComment(
+                "Failed to close the hull with every point as a group".to_string(),
+            )]);
+            return Vec::new();
+        }
+
+        drawing_history.push(vec![LineType::TextComment(format!(
+            "The hull has more than {} vertices, squaring the guess",
+            guess
+        ))]);
+        exponent *= 2;
+    }
+}
+
+/// Wraps the hulls of the groups of [chan] starting from `start`, for at most `guess` steps.
+///
+/// Returns the hull if it closed in time, [None] otherwise.
+fn wrap_groups<P: Point>(
+    groups: &[Vec<P>],
+    start: P,
+    guess: usize,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    events: &mut Option<&mut Vec<HullEvent>>,
+) -> Option<Vec<P>> {
+    let mut hull = vec![start];
+
+    for _ in 0..guess {
+        let current = hull[hull.len() - 1];
+        let candidates: Vec<P> = groups
+            .iter()
+            .map(|group| tangent(group, current, epsilon))
+            .filter(|candidate| *candidate != current)
+            .collect();
+
+        // Like in Jarvis March the candidate with no other candidate to its right is the next vertex,
+        // of collinear candidates the farthest one is taken so the hull has no collinear vertices
+        let mut next = candidates[0];
+        let mut temp = vec![];
+        for &candidate in &candidates {
+            record(events, || HullEvent::CandidateTested {
+                from: current.to_vec2(),
+                candidate: candidate.to_vec2(),
+            });
+            temp.push(LineType::Temporary(current.to_vec2(), candidate.to_vec2()));
+
+            let (to_candidate, to_next) = (candidate.minus(&current), next.minus(&current));
+            match orientation(&current, &candidate, &next, epsilon) {
+                Orientation::Counterclockwise => next = candidate,
+                Orientation::Colinear
+                    if to_candidate.dot(&to_next) > P::Scalar::ZERO
+                        && to_candidate.dot(&to_candidate) > to_next.dot(&to_next) =>
+                {
+                    next = candidate
+                }
+                _ => {}
+            }
+        }
+
+        temp.push(LineType::PartOfHull(current.to_vec2(), next.to_vec2()));
+        temp.push(LineType::TextComment(format!(
+            "Found the tangents from {} to the {} groups, {} is the next point of the hull",
+            current,
+            groups.len(),
+            next
+        )));
+        drawing_history.push(temp);
+
+        if next == start {
+            return Some(hull);
+        }
+        hull.push(next);
+    }
+
+    None
+}
+
+/// Returns the hull of the points in counterclockwise order, found with [Graham scan](https://en.wikipedia.org/wiki/Graham_scan).
+///
+/// The points are sorted by their angle around the lowest leftmost point, then every point that doesn't make
+/// a left turn is removed.
+fn graham_scan<P: Point>(points: &[P], epsilon: f32) -> Vec<P> {
+    let mut sorted_points = points.to_vec();
+    let pivot_index = (0..sorted_points.len())
+        .min_by(|&a, &b| {
+            let (a, b) = (sorted_points[a], sorted_points[b]);
+            (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap()
+        })
+        .unwrap();
+    let pivot = sorted_points.swap_remove(pivot_index);
+
+    // The pivot is the leftmost point, so the cross product orders the others by angle
+    sorted_points.sort_by(|a, b| {
+        let (a, b) = (a.minus(&pivot), b.minus(&pivot));
+        let cross = a.x() * b.y() - a.y() * b.x();
+        (P::Scalar::ZERO)
+            .partial_cmp(&cross)
+            .unwrap()
+            .then_with(|| a.dot(&a).partial_cmp(&b.dot(&b)).unwrap())
+    });
+
+    // The points on the last ray lead back to the pivot, so they are visited from the farthest one.
+    // When all points are on that ray they are left in order, which only keeps the farthest.
+    if let Some(&last) = sorted_points.last() {
+        let last = last.minus(&pivot);
+        let on_last_ray = |p: &P| {
+            let p = p.minus(&pivot);
+            p.x() * last.y() - p.y() * last.x() == P::Scalar::ZERO
+        };
+        let run = sorted_points.iter().rev().take_while(|p| on_last_ray(p)).count();
+        if run < sorted_points.len() {
+            let len = sorted_points.len();
+            sorted_points[len - run..].reverse();
+        }
+    }
+
+    let mut hull = vec![pivot];
+    for point in sorted_points {
+        while hull.len() >= 2
+            && orientation(&hull[hull.len() - 2], &hull[hull.len() - 1], &point, epsilon) != Orientation::Counterclockwise
+        {
+            hull.pop();
+        }
+        hull.push(point);
+    }
+
+    hull
+}
+
+/// Returns the vertex of the convex `hull` for which the whole hull lies left of the line from `point` to it.
+///
+/// When `point` is a vertex of the hull, or lies on one of its edges, this is the vertex after it. Otherwise the
+/// vertex is found with a binary search, which falls back to testing every vertex if the tolerance of
+/// [orientation] misleads it.
+fn tangent<P: Point>(hull: &[P], point: P, epsilon: f32) -> P {
+    let n = hull.len();
+    if let Some(index) = hull.iter().position(|vertex| *vertex == point) {
+        return hull[(index + 1) % n];
+    }
+    // The binary search can't tell the two ends of the edge apart, as the point is collinear with both
+    let on_edge = |i: usize| {
+        let (a, b) = (hull[i], hull[(i + 1) % n]);
+        let (to_a, to_b) = (a.minus(&point), b.minus(&point));
+        orientation(&a, &point, &b, epsilon) == Orientation::Colinear && to_a.dot(&to_b) < P::Scalar::ZERO
+    };
+    if let Some(index) = (0..n).find(|&i| on_edge(i)) {
+        return hull[(index + 1) % n];
+    }
+
+    let turn = |a: usize, b: usize| orientation(&point, &hull[a % n], &hull[b % n], epsilon);
+    let is_tangent = |i: usize| {
+        turn(i, i + n - 1) != Orientation::Clockwise && turn(i, i + 1) != Orientation::Clockwise
+    };
+    let flip = |turn: Orientation| match turn {
+        Orientation::Clockwise => Orientation::Counterclockwise,
+        Orientation::Counterclockwise => Orientation::Clockwise,
+        Orientation::Colinear => Orientation::Colinear,
+    };
+
+    let (mut low, mut high) = (0, n);
+    let mut low_previous = turn(0, n - 1);
+    let mut low_next = turn(0, 1);
+    while low < high {
+        let middle = (low + high) / 2;
+        if is_tangent(middle) {
+            return hull[middle];
+        }
+
+        let middle_previous = turn(middle, middle + n - 1);
+        let middle_next = turn(middle, middle + 1);
+        let middle_side = turn(low, middle);
+        let left_chain = (middle_side == Orientation::Counterclockwise
+            && (low_next == Orientation::Clockwise || low_previous == low_next))
+            || (middle_side == Orientation::Clockwise && middle_previous == Orientation::Clockwise);
+
+        if left_chain {
+            high = middle;
+        } else {
+            low = middle + 1;
+            low_previous = flip(middle_next);
+            low_next = turn(low, low + 1);
+        }
+    }
+
+    let index = if is_tangent(low % n) {
+        low % n
+    } else {
+        (0..n).find(|&i| is_tangent(i)).unwrap_or(0)
+    };
+    hull[index]
+}
+
+/// # Implementation of the [Divide and Conquer](https://doi.org/10.1145/359423.359430) algorithm by Preparata and Hong
+/// This algorithm is used to calculate the convex hull of given set of points.
+/// It has a `O(n log n)` time complexity, where `n` is the number of points.
+///
+/// ## Pseudocode
+/// ```pseudocode
+/// algorithm divide_and_conquer(S) is
+///     # S is sorted by x coordinate
+///     if |S| <= 3 then return the hull of S
+///     L := divide_and_conquer(left half of S)
+///     R := divide_and_conquer(right half of S)
+///     (a, b) := upper tangent of L and R, walked from the rightmost point of L and the leftmost point of R
+///     (c, d) := lower tangent of L and R, walked the same way
+///     return L from a to c, followed by R from d to b
+/// ```
+/// ## Analysis
+/// Sorting the points takes $O(n\log(n))$ time. Walking the tangents visits every vertex of both hulls at most once,
+/// so merging takes $O(n)$ time. This gives $T(n) = 2T(\frac{n}{2}) + O(n)$.
+///
+/// Thus this algorithm yields the hull in $O(n\log(n))$ time.
+///
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+/// When `events` is given the [HullEvent]s of the algorithm are recorded into it.
+pub fn divide_and_conquer<P: Point>(
+    points: Vec<P>,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    mut events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if let Some(hull) = single_distinct_point(&points, drawing_history) {
+        return hull;
+    }
+
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    // Duplicates would end up in both halves, where neither tangent could pass them
+    let mut seen = HashSet::new();
+    let mut sorted_points: Vec<P> = points.into_iter().filter(|p| seen.insert(point_key(p))).collect();
+    sorted_points.sort_by(|a, b| (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap());
+
+    let hull = split_and_merge(&sorted_points, 0, drawing_history, epsilon, &mut events);
+
+    for i in 0..hull.len() {
+        let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+        record(&mut events, || HullEvent::EdgeAccepted(a.to_vec2(), b.to_vec2()));
+    }
+
+    let mut step: Vec<_> = outline(&hull, LineType::PartOfHull).collect();
+    step.push(LineType::TextComment(format!(
+        "Divide and conquer is complete, the hull has {} vertices",
+        hull.len()
+    )));
+    step.extend(hull_vertices(&hull));
+    drawing_history.push(step);
+
+    hull
+}
+
+/// Returns the hull of the sorted points in counterclockwise order, see [divide_and_conquer]
+fn split_and_merge<P: Point>(
+    points: &[P],
+    depth: usize,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    events: &mut Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if points.len() <= 3 {
+        return small_hull(points, epsilon);
+    }
+
+    let middle = points.len() / 2;
+    let x = (points[middle - 1].x().to_f32() + points[middle].x().to_f32()) / 2.0;
+    drawing_history.push(vec![
+        LineType::VerticalLine(x, depth),
+        LineType::TextComment(format!("Depth {}: splitting {} points at x={}", depth, points.len(), x)),
+    ]);
+
+    let left = split_and_merge(&points[..middle], depth + 1, drawing_history, epsilon, events);
+    let right = split_and_merge(&points[middle..], depth + 1, drawing_history, epsilon, events);
+
+    merge_hulls(&left, &right, depth, drawing_history, epsilon, events)
+}
+
+/// Returns the hull of at most 3 sorted points in counterclockwise order
+fn small_hull<P: Point>(points: &[P], epsilon: f32) -> Vec<P> {
+    match points {
+        [a, b, c] => match orientation(a, b, c, epsilon) {
+            Orientation::Counterclockwise => vec![*a, *b, *c],
+            Orientation::Clockwise => vec![*a, *c, *b],
+            // The points are sorted, so the outer ones are the ends of the segment
+            Orientation::Colinear => vec![*a, *c],
+        },
+        _ => points.to_vec(),
+    }
+}
+
+/// Merges the hulls of two halves of the points, `left` lying entirely left of `right`.
+///
+/// Both tangents are walked from the rightmost point of `left` and the leftmost point of `right`,
+/// every step of the walk being shown along with the two hulls.
+fn merge_hulls<P: Point>(
+    left: &[P],
+    right: &[P],
+    depth: usize,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    events: &mut Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    let lexicographic = |a: &P, b: &P| (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap();
+    let rightmost = (0..left.len()).max_by(|&a, &b| lexicographic(&left[a], &left[b])).unwrap();
+    let leftmost = (0..right.len()).min_by(|&a, &b| lexicographic(&right[a], &right[b])).unwrap();
+
+    let (upper_left, upper_right) =
+        walk_tangent(left, right, (rightmost, leftmost), true, depth, drawing_history, epsilon);
+    let (lower_left, lower_right) =
+        walk_tangent(left, right, (rightmost, leftmost), false, depth, drawing_history, epsilon);
+
+    // Walking counterclockwise the hull follows the left hull from the upper to the lower tangent,
+    // and then the right hull from the lower to the upper tangent
+    let mut hull = Vec::new();
+    let mut i = upper_left;
+    loop {
+        hull.push(left[i]);
+        if i == lower_left {
+            break;
+        }
+        i = (i + 1) % left.len();
+    }
+    let mut j = lower_right;
+    loop {
+        hull.push(right[j]);
+        if j == upper_right {
+            break;
+        }
+        j = (j + 1) % right.len();
+    }
+
+    let upper = (left[upper_left].to_vec2(), right[upper_right].to_vec2());
+    let lower = (left[lower_left].to_vec2(), right[lower_right].to_vec2());
+    record(events, || HullEvent::HullsMerged { depth, upper, lower });
+
+    let mut step: Vec<_> = outline(&hull, LineType::Temporary).collect();
+    step.push(LineType::Tangent(upper.0, upper.1));
+    step.push(LineType::Tangent(lower.0, lower.1));
+    step.push(LineType::TextComment(format!(
+        "Depth {}: merged the hulls into one of {} vertices",
+        depth,
+        hull.len()
+    )));
+    step.push(LineType::Milestone(format!("Depth {} merged", depth)));
+    drawing_history.push(step);
+
+    hull
+}
+
+/// Walks the upper or the lower tangent of the hulls of [merge_hulls] from the given pair of vertices,
+/// returning the indices of its endpoints.
+///
+/// The endpoint on one hull is moved as long as its neighbour lies beyond the tangent, then the one on the other hull,
+/// until neither moves. Of collinear neighbours the farther one is taken, so the tangent has no collinear vertices.
+fn walk_tangent<P: Point>(
+    left: &[P],
+    right: &[P],
+    (mut i, mut j): (usize, usize),
+    upper: bool,
+    depth: usize,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+) -> (usize, usize) {
+    let (n, m) = (left.len(), right.len());
+    let name = if upper { "upper" } else { "lower" };
+
+    // The upper tangent goes counterclockwise around the left hull and clockwise around the right one,
+    // the lower tangent the other way around
+    let (left_step, right_step) = if upper { (1, m - 1) } else { (n - 1, 1) };
+    let (left_outside, right_outside) = if upper {
+        (Orientation::Clockwise, Orientation::Counterclockwise)
+    } else {
+        (Orientation::Counterclockwise, Orientation::Clockwise)
+    };
+    let beyond = |from: &P, current: &P, next: &P, outside: Orientation| {
+        let turn = orientation(from, current, next, epsilon);
+        let (to_current, to_next) = (current.minus(from), next.minus(from));
+        turn == outside || (turn == Orientation::Colinear && to_next.dot(&to_next) > to_current.dot(&to_current))
+    };
+
+    // Every move makes progress around one of the hulls, so neither endpoint can go all the way around.
+    // The bounds only guard against the tolerance of [orientation] misleading the walk.
+    for _ in 0..n + m {
+        let mut moved = false;
+        for _ in 0..n {
+            if !beyond(&right[j], &left[i], &left[(i + left_step) % n], left_outside) {
+                break;
+            }
+            i = (i + left_step) % n;
+            moved = true;
+        }
+        for _ in 0..m {
+            if !beyond(&left[i], &right[j], &right[(j + right_step) % m], right_outside) {
+                break;
+            }
+            j = (j + right_step) % m;
+            moved = true;
+        }
+
+        let mut step: Vec<_> = outline(left, LineType::Temporary).chain(outline(right, LineType::Temporary)).collect();
+        step.push(LineType::Tangent(left[i].to_vec2(), right[j].to_vec2()));
+        step.push(LineType::TextComment(if moved {
+            format!("Depth {}: walking the {} tangent to {} and {}", depth, name, left[i], right[j])
+        } else {
+            format!("Depth {}: found the {} tangent between {} and {}", depth, name, left[i], right[j])
+        }));
+        drawing_history.push(step);
+
+        if !moved {
+            break;
+        }
+    }
+
+    (i, j)
+}
+
+/// # Implementation of the [Randomized Incremental](https://en.wikipedia.org/wiki/Convex_hull_algorithms#Incremental_convex_hull_algorithms) construction
+/// This algorithm is used to calculate the convex hull of given set of points.
+/// It has a `O(n log n)` expected time complexity with the conflict lists of the textbook version, which this
+/// implementation leaves out in favour of a `O(h)` visibility test per point.
+///
+/// ## Pseudocode
+/// ```pseudocode
+/// algorithm randomized_incremental(S) is
+///     shuffle S
+///     H := triangle of the first three points of S that aren't collinear
+///     for p in S do
+///         if p is outside of H then
+///             E := edges of H visible from p   # a contiguous chain
+///             replace E by the edges from its endpoints to p
+///     return H
+/// ```
+/// ## Analysis
+/// Every insertion tests all $O(h)$ edges of the current hull, which takes $O(nh)$ time in total. Shuffling the
+/// points first makes the inputs that build up a large hull early, e.g. points sorted around a circle, unlikely.
+///
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+/// The points are shuffled with `rng`, so seeding it makes the run reproducible.
+/// When `events` is given the [HullEvent]s of the algorithm are recorded into it.
+pub fn randomized_incremental<P: Point>(
+    points: Vec<P>,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    rng: &mut impl Rng,
+    mut events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if let Some(hull) = single_distinct_point(&points, drawing_history) {
+        return hull;
+    }
+
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut points = points;
+    points.shuffle(rng);
+
+    // The first point and the first one different from it span the line the triangle is built on
+    let first = points[0];
+    let second = *points.iter().find(|p| **p != first).unwrap();
+    let Some(third) = points
+        .iter()
+        .find(|p| !matches!(orientation(&first, &second, p, epsilon), Orientation::Colinear))
+        .copied()
+    else {
+        let lexicographic = |a: &&P, b: &&P| (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap();
+        let hull = vec![
+            *points.iter().min_by(lexicographic).unwrap(),
+            *points.iter().max_by(lexicographic).unwrap(),
+        ];
+        drawing_history.push(vec![
+            LineType::PartOfHull(hull[0].to_vec2(), hull[1].to_vec2()),
+            LineType::TextComment(
+                "All points are collinear, the hull is the segment between the outermost ones".to_string(),
+            ),
+        ]);
+        return hull;
+    };
+
+    let mut hull = match orientation(&first, &second, &third, epsilon) {
+        Orientation::Counterclockwise => vec![first, second, third],
+        _ => vec![first, third, second],
+    };
+    let mut step: Vec<_> = outline(&hull, LineType::Temporary).collect();
+    step.push(LineType::TextComment(format!(
+        "Starting with the triangle of {}, {} and {}",
+        hull[0], hull[1], hull[2]
+    )));
+    drawing_history.push(step);
+
+    for point in points {
+        if hull.contains(&point) {
+            continue;
+        }
+
+        let mut step: Vec<_> = outline(&hull, LineType::Temporary).collect();
+        if point_in_hull(&hull, point, epsilon) {
+            step.push(LineType::CollinearPoint(point.to_vec2()));
+            step.push(LineType::TextComment(format!("{} lies inside the hull, it is skipped", point)));
+            drawing_history.push(step);
+            continue;
+        }
+
+        // The hull is counterclockwise, so the point sees the edges it lies right of. Like in [insert_point]
+        // collinear edges count as visible, so that no collinear vertices are left on the hull.
+        let n = hull.len();
+        let visible = |i: usize| {
+            !matches!(
+                orientation(&hull[i], &hull[(i + 1) % n], &point, epsilon),
+                Orientation::Counterclockwise
+            )
+        };
+        let Some(first_visible) = (0..n).find(|&i| visible(i) && !visible((i + n - 1) % n)) else {
+            continue;
+        };
+        let mut last_visible = first_visible;
+        while visible((last_visible + 1) % n) {
+            last_visible = (last_visible + 1) % n;
+        }
+
+        let visible_edges = (last_visible + n - first_visible) % n + 1;
+        record(&mut events, || HullEvent::PointInserted {
+            point: point.to_vec2(),
+            visible_edges,
+        });
+
+        for k in 0..visible_edges {
+            let i = (first_visible + k) % n;
+            step.push(LineType::Region(point.to_vec2(), hull[i].to_vec2(), hull[(i + 1) % n].to_vec2()));
+        }
+        let (start, end) = (hull[first_visible], hull[(last_visible + 1) % n]);
+        step.push(LineType::Tangent(start.to_vec2(), point.to_vec2()));
+        step.push(LineType::Tangent(point.to_vec2(), end.to_vec2()));
+        step.push(LineType::TextComment(format!(
+            "{} sees {} edges of the hull, replacing them with the edges from {} and to {}",
+            point, visible_edges, start, end
+        )));
+        drawing_history.push(step);
+
+        let mut updated = Vec::with_capacity(n + 1);
+        let mut i = (last_visible + 1) % n;
+        loop {
+            updated.push(hull[i]);
+            if i == first_visible {
+                break;
+            }
+            i = (i + 1) % n;
+        }
+        updated.push(point);
+        hull = updated;
+    }
+
+    // The shuffle leaves the hull starting anywhere, it is rotated to start from its leftmost vertex like the others
+    let leftmost = (0..hull.len())
+        .min_by(|&a, &b| (hull[a].x(), hull[a].y()).partial_cmp(&(hull[b].x(), hull[b].y())).unwrap())
+        .unwrap();
+    hull.rotate_left(leftmost);
+
+    for i in 0..hull.len() {
+        let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+        record(&mut events, || HullEvent::EdgeAccepted(a.to_vec2(), b.to_vec2()));
+    }
+
+    let mut step: Vec<_> = outline(&hull, LineType::PartOfHull).collect();
+    step.push(LineType::TextComment(format!(
+        "Inserted all points, the hull has {} vertices",
+        hull.len()
+    )));
+    step.extend(hull_vertices(&hull));
+    drawing_history.push(step);
+
+    hull
+}
+
+/// # Implementation of [Melkman's algorithm](https://maxgoldste.in/melkman/)
+/// This algorithm is used to calculate the convex hull of a simple polyline, i.e. of points whose order forms
+/// a path that doesn't cross itself, such as the vertices of a simple polygon.
+/// It has a `O(n)` time complexity, where `n` is the number of points. For other inputs the result can be wrong.
+///
+/// ## Pseudocode
+/// ```pseudocode
+/// algorithm melkman(S) is
+///     # the deque D holds the hull of the points so far, with the last added point at both ends
+///     D := triangle of the first three points of S, counterclockwise
+///     for v in the rest of S do
+///         if v is left of the edges at both ends of D then continue   # v is inside the hull
+///         pop the top of D until the top edge turns left towards v, push v to the top
+///         pop the bottom of D until v turns left towards the bottom edge, push v to the bottom
+///     return D without its top
+/// ```
+/// ## Analysis
+/// As the polyline doesn't cross itself, a point outside of the hull of the previous points can only be
+/// outside of the edges next to the last added point, which are the edges at the ends of the deque.
+/// Every point is pushed and popped at most twice, so this algorithm yields the hull in $O(n)$ time.
+///
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+/// When `events` is given the [HullEvent]s of the algorithm are recorded into it.
+pub fn melkman<P: Point>(
+    points: Vec<P>,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    mut events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if let Some(hull) = single_distinct_point(&points, drawing_history) {
+        return hull;
+    }
+
+    if points.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut points = points;
+    points.dedup();
+
+    // The first points can be collinear, the polyline may only move away from the first one along their line
+    let Some(third) = (2..points.len())
+        .find(|&i| !matches!(orientation(&points[0], &points[i - 1], &points[i], epsilon), Orientation::Colinear))
+    else {
+        let lexicographic = |a: &&P, b: &&P| (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap();
+        let hull = vec![
+            *points.iter().min_by(lexicographic).unwrap(),
+            *points.iter().max_by(lexicographic).unwrap(),
+        ];
+        drawing_history.push(vec![
+            LineType::PartOfHull(hull[0].to_vec2(), hull[1].to_vec2()),
+            LineType::TextComment(
+                "All points are collinear, the hull is the segment between the outermost ones".to_string(),
+            ),
+        ]);
+        return hull;
+    };
+
+    let (a, b, c) = (points[0], points[third - 1], points[third]);
+    let mut deque: VecDeque<P> = match orientation(&a, &b, &c, epsilon) {
+        Orientation::Counterclockwise => VecDeque::from([c, a, b, c]),
+        _ => VecDeque::from([c, b, a, c]),
+    };
+    drawing_history.push(deque_step(
+        &deque,
+        format!("Starting the deque with the triangle of {}, {} and {}", a, b, c),
+    ));
+
+    // `b` lies strictly between `a` and `c` on their line
+    let between = |a: &P, b: &P, c: &P| {
+        b.minus(a).dot(&c.minus(a)) > P::Scalar::ZERO && b.minus(c).dot(&a.minus(c)) > P::Scalar::ZERO
+    };
+    // `point` is inside the half plane left of the edge from `a` to `b`, or on the edge itself
+    let inside = |a: &P, b: &P, point: &P| match orientation(a, b, point, epsilon) {
+        Orientation::Counterclockwise => true,
+        Orientation::Colinear => !between(point, a, b) && !between(point, b, a),
+        Orientation::Clockwise => false,
+    };
+
+    for &point in &points[third + 1..] {
+        let (top, bottom) = (deque.len() - 1, 0);
+        if inside(&deque[top - 1], &deque[top], &point) && inside(&deque[bottom], &deque[bottom + 1], &point) {
+            drawing_history.push(deque_step(&deque, format!("{} lies inside the hull of the deque, it is skipped", point)));
+            continue;
+        }
+
+        // Collinear ends are only popped when they lie between their neighbour and the point
+        let mut popped_top = 0;
+        while deque.len() >= 3 {
+            let (previous, top) = (deque[deque.len() - 2], deque[deque.len() - 1]);
+            match orientation(&previous, &top, &point, epsilon) {
+                Orientation::Counterclockwise => break,
+                Orientation::Colinear if !between(&previous, &top, &point) => break,
+                _ => {}
+            }
+            deque.pop_back();
+            popped_top += 1;
+        }
+        deque.push_back(point);
+
+        let mut popped_bottom = 0;
+        while deque.len() >= 3 {
+            let (bottom, next) = (deque[0], deque[1]);
+            match orientation(&point, &bottom, &next, epsilon) {
+                Orientation::Counterclockwise => break,
+                Orientation::Colinear if !between(&point, &bottom, &next) => break,
+                _ => {}
+            }
+            deque.pop_front();
+            popped_bottom += 1;
+        }
+        deque.push_front(point);
+
+        record(&mut events, || HullEvent::DequeUpdated {
+            point: point.to_vec2(),
+            popped_top,
+            popped_bottom,
+        });
+        drawing_history.push(deque_step(
+            &deque,
+            format!(
+                "Pushed {} to both ends of the deque after popping {} points from the top and {} from the bottom",
+                point, popped_top, popped_bottom
+            ),
+        ));
+    }
+
+    deque.pop_back();
+    let mut hull: Vec<P> = deque.into();
+    let leftmost = (0..hull.len())
+        .min_by(|&a, &b| (hull[a].x(), hull[a].y()).partial_cmp(&(hull[b].x(), hull[b].y())).unwrap())
+        .unwrap();
+    hull.rotate_left(leftmost);
+
+    for i in 0..hull.len() {
+        let (a, b) = (hull[i], hull[(i + 1) % hull.len()]);
+        record(&mut events, || HullEvent::EdgeAccepted(a.to_vec2(), b.to_vec2()));
+    }
+
+    let mut step: Vec<_> = outline(&hull, LineType::PartOfHull).collect();
+    step.push(LineType::TextComment(format!(
+        "Melkman's algorithm is complete, the hull has {} vertices",
+        hull.len()
+    )));
+    step.extend(hull_vertices(&hull));
+    drawing_history.push(step);
+
+    hull
+}
+
+/// Returns the step of [melkman] showing the contents of the deque, from its bottom to its top,
+/// with the ends of the deque highlighted
+fn deque_step<P: Point>(deque: &VecDeque<P>, comment: String) -> Vec<LineType> {
+    let mut step: Vec<_> = deque
+        .iter()
+        .zip(deque.iter().skip(1))
+        .map(|(a, b)| LineType::Temporary(a.to_vec2(), b.to_vec2()))
+        .collect();
+    let (bottom, top) = (deque[0], deque[deque.len() - 1]);
+    step.push(LineType::Tangent(deque[1].to_vec2(), bottom.to_vec2()));
+    step.push(LineType::Tangent(deque[deque.len() - 2].to_vec2(), top.to_vec2()));
+    step.push(LineType::TextComment(comment));
+    step
+}
+
+/// Default number of vertical strips of [approximate_hull], used when it runs without the simulation
+pub const DEFAULT_APPROXIMATION_STRIPS: usize = 32;
+
+/// # Implementation of the [Bentley-Faust-Preparata](https://doi.org/10.1145/358396.358402) approximate hull
+/// This algorithm is used to approximate the convex hull of a large set of points.
+/// It has a `O(n + k)` time complexity, where `n` is the number of points and `k` is the number of strips.
+///
+/// ## Pseudocode
+/// ```pseudocode
+/// algorithm approximate(S, k) is
+///     split the x range of S into k vertical strips of equal width
+///     C := the lowest and the highest point of every strip
+///     add the lowest and the highest of the leftmost and of the rightmost points to C
+///     return monotone_chain(C)
+/// ```
+/// ## Analysis
+/// Finding the strip of every point and its extrema takes $O(n)$ time. The candidates are at most $2k + 4$
+/// points, which the strips already order by $x$, so their hull takes $O(k)$ time to find, or $O(k \log(k))$
+/// with the sort of [monotone_chain].
+///
+/// Every vertex of the approximate hull is a vertex of the input, so the approximation lies inside the exact
+/// hull. No point is farther outside of it than the width of a strip, see [approximation_error].
+///
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+/// When `events` is given the [HullEvent]s of the algorithm are recorded into it.
+pub fn approximate_hull<P: Point>(
+    points: Vec<P>,
+    strips: usize,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    mut events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+
+    let strips = strips.max(1);
+    let (min_x, max_x) = points.iter().fold((f32::MAX, f32::MIN), |(min, max), p| {
+        (min.min(p.x().to_f32()), max.max(p.x().to_f32()))
+    });
+    let width = (max_x - min_x) / strips as f32;
+    let strip = |point: &P| {
+        if width > 0.0 {
+            (((point.x().to_f32() - min_x) / width) as usize).min(strips - 1)
+        } else {
+            0
+        }
+    };
+
+    // The lowest and the highest point of every strip
+    let mut extrema: Vec<Option<(P, P)>> = vec![None; strips];
+    for point in &points {
+        match &mut extrema[strip(point)] {
+            Some((lowest, highest)) => {
+                if point.y() < lowest.y() {
+                    *lowest = *point;
+                }
+                if point.y() > highest.y() {
+                    *highest = *point;
+                }
+            }
+            empty => *empty = Some((*point, *point)),
+        }
+    }
+
+    let mut step: Vec<_> = (0..=strips)
+        .filter(|_| width > 0.0)
+        .map(|i| LineType::VerticalLine(min_x + width * i as f32, 0))
+        .collect();
+    step.push(LineType::TextComment(format!(
+        "Split the points into {} vertical strips of width {:.2}",
+        strips, width
+    )));
+    drawing_history.push(step);
+
+    let extreme = |key: fn(&P) -> (P::Scalar, P::Scalar)| {
+        *points
+            .iter()
+            .min_by(|a, b| key(a).partial_cmp(&key(b)).unwrap())
+            .unwrap()
+    };
+    let mut candidates = Vec::new();
+    let mut seen = HashSet::new();
+    for (lowest, highest) in extrema.iter().flatten() {
+        push_candidate(&mut candidates, &mut seen, *lowest);
+        push_candidate(&mut candidates, &mut seen, *highest);
+    }
+    // The extrema of the outermost strips can miss the leftmost and rightmost points, which are on the hull
+    push_candidate(&mut candidates, &mut seen, extreme(|p| (p.x(), p.y())));
+    push_candidate(&mut candidates, &mut seen, extreme(|p| (p.x(), -p.y())));
+    push_candidate(&mut candidates, &mut seen, extreme(|p| (-p.x(), p.y())));
+    push_candidate(&mut candidates, &mut seen, extreme(|p| (-p.x(), -p.y())));
+    record(&mut events, || HullEvent::StripExtrema { strips, candidates: candidates.len() });
+
+    let mut step: Vec<_> = extrema
+        .iter()
+        .flatten()
+        .map(|(lowest, highest)| LineType::Tangent(lowest.to_vec2(), highest.to_vec2()))
+        .collect();
+    step.extend(hull_vertices(&candidates));
+    step.push(LineType::TextComment(format!(
+        "Kept the lowest and highest points of the strips, {} of {} points",
+        candidates.len(),
+        points.len()
+    )));
+    drawing_history.push(step);
+
+    monotone_chain(candidates, drawing_history, epsilon, events)
+}
+
+/// Returns how far the exact hull reaches outside of the approximate one, the largest distance of a vertex
+/// of `exact` to the boundary of `approximate`. It is zero when the approximation is exact.
+pub fn approximation_error<P: Point>(approximate: &[P], exact: &[P], epsilon: f32) -> f32 {
+    let distance = |point: Vec2| match approximate.len() {
+        0 => 0.0,
+        1 => point.distance(approximate[0].to_vec2()),
+        len => (0..len)
+            .map(|i| {
+                let (a, b) = (approximate[i].to_vec2(), approximate[(i + 1) % len].to_vec2());
+                let t = ((point - a).dot(b - a) / (b - a).length_squared().max(f32::MIN_POSITIVE)).clamp(0.0, 1.0);
+                point.distance(a + (b - a) * t)
+            })
+            .fold(f32::MAX, f32::min),
+    };
+
+    exact
+        .iter()
+        .filter(|vertex| !point_in_hull(approximate, **vertex, epsilon))
+        .map(|vertex| distance(vertex.to_vec2()))
+        .fold(0.0, f32::max)
+}
+
+/// Returns the edges of the hull as the given kind of line, a hull of a single point has none
+fn outline<P: Point>(hull: &[P], line: fn(Vec2, Vec2) -> LineType) -> impl Iterator<Item = LineType> + '_ {
+    let edges = match hull.len() {
+        0 | 1 => 0,
+        2 => 1,
+        len => len,
+    };
+    (0..edges).map(move |i| line(hull[i].to_vec2(), hull[(i + 1) % hull.len()].to_vec2()))
+}
+
+/// Returns a [LineType::HullVertex] for every vertex of the hull, which the algorithms add to their last step
+pub fn hull_vertices<P: Point>(hull: &[P]) -> impl Iterator<Item = LineType> + '_ {
+    hull.iter().map(|vertex| LineType::HullVertex(vertex.to_vec2()))
+}
+
+/// Represent the orientation between three points (consecutive)
+#[derive(PartialEq, Clone, Copy)]
+enum Orientation {
+    /// Has $\lt 0$ angle between the lines made by the points
+    Clockwise,
+    /// Has $\gt 0$ angle between the lines made by the points
+    Counterclockwise,
+    /// Has $\pi$ radian angle between the lines made by the points
+    Colinear,
+}
+
+/// Discards the points that can't be vertices of the hull, using the [Akl–Toussaint heuristic](https://en.wikipedia.org/wiki/Convex_hull_algorithms#Akl%E2%80%93Toussaint_heuristic).
+///
+/// The leftmost, lowest, rightmost and highest points are vertices of the hull, so the points strictly inside
+/// the quadrilateral they form are not. The step showing the quadrilateral and the discarded points is added to
+/// the drawing history, and the remaining points are returned to be passed on to any of the algorithms.
+pub fn akl_toussaint<P: Point>(points: &[P], drawing_history: &mut Vec<Vec<LineType>>, epsilon: f32) -> Vec<P> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+
+    let extreme = |key: fn(&P) -> (P::Scalar, P::Scalar)| {
+        *points
+            .iter()
+            .min_by(|a, b| key(a).partial_cmp(&key(b)).unwrap())
+            .unwrap()
+    };
+    // Counterclockwise, like the hulls the algorithms return
+    let mut quadrilateral = vec![
+        extreme(|p| (p.x(), p.y())),
+        extreme(|p| (p.y(), -p.x())),
+        extreme(|p| (-p.x(), -p.y())),
+        extreme(|p| (-p.y(), p.x())),
+    ];
+    quadrilateral.dedup();
+    if quadrilateral.len() > 1 && quadrilateral[0] == quadrilateral[quadrilateral.len() - 1] {
+        quadrilateral.pop();
+    }
+
+    // With fewer than three extreme points the quadrilateral has no inside
+    let (kept, discarded): (Vec<P>, Vec<P>) = if quadrilateral.len() < 3 {
+        (points.to_vec(), Vec::new())
+    } else {
+        points
+            .iter()
+            .copied()
+            .partition(|p| quadrilateral.contains(p) || !point_in_hull(&quadrilateral, *p, epsilon))
+    };
+
+    let mut step: Vec<_> = (1..quadrilateral.len().saturating_sub(1))
+        .map(|i| {
+            let (a, b, c) = (quadrilateral[0], quadrilateral[i], quadrilateral[i + 1]);
+            LineType::Region(a.to_vec2(), b.to_vec2(), c.to_vec2())
+        })
+        .collect();
+    step.extend(outline(&quadrilateral, LineType::Temporary));
+    step.extend(discarded.iter().map(|p| LineType::DiscardedPoint(p.to_vec2())));
+    step.push(LineType::TextComment(format!(
+        "Akl-Toussaint discarded {} of {} points inside the quadrilateral of the extreme points",
+        discarded.len(),
+        points.len()
+    )));
+    drawing_history.push(step);
+
+    kept
+}
+
+/// Computes the convex hull of the points with the given algorithm, without recording the drawing history.
+///
+/// This is the entry point for using the algorithms outside of the simulation, e.g. by the command line interface.
+/// The randomized algorithms are seeded with [HEADLESS_SEED], so the same points always give the same hull.
+/// All algorithms return the vertices in counterclockwise order.
+///
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+pub fn compute_hull<P: Point>(points: Vec<P>, algorithm: AlgorithmType, epsilon: f32) -> Vec<P> {
+    run_headless(points, algorithm, epsilon, None)
+}
+
+/// Computes the convex hull like [compute_hull], along with the [HullEvent]s of the algorithm.
+pub fn trace_hull<P: Point>(points: Vec<P>, algorithm: AlgorithmType, epsilon: f32) -> (Vec<P>, Vec<HullEvent>) {
+    let mut events = Vec::new();
+    let hull = run_headless(points, algorithm, epsilon, Some(&mut events));
+    (hull, events)
+}
+
+/// Seed of the shuffle of [randomized_incremental] when it runs through [compute_hull] or [trace_hull]
+pub const HEADLESS_SEED: u64 = 0;
+
+/// Runs the algorithm with the given tolerance, discarding the drawing history
+fn run_headless<P: Point>(
+    points: Vec<P>,
+    algorithm: AlgorithmType,
+    epsilon: f32,
+    events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    let mut drawing_history = Vec::new();
+    match algorithm {
+        AlgorithmType::JarvisMarch => jarvis_march(points, &mut drawing_history, epsilon, events),
+        AlgorithmType::KirkPatrickSeidel => kirk_patrick_seidel(points, &mut drawing_history, epsilon, events),
+        AlgorithmType::MonotoneChain => monotone_chain(points, &mut drawing_history, epsilon, events),
+        AlgorithmType::Quickhull => quickhull(points, &mut drawing_history, epsilon, events),
+        AlgorithmType::Chan => chan(points, &mut drawing_history, epsilon, events),
+        AlgorithmType::DivideAndConquer => divide_and_conquer(points, &mut drawing_history, epsilon, events),
+        AlgorithmType::RandomizedIncremental => {
+            let mut rng = StdRng::seed_from_u64(HEADLESS_SEED);
+            randomized_incremental(points, &mut drawing_history, epsilon, &mut rng, events)
+        }
+        AlgorithmType::Melkman => melkman(points, &mut drawing_history, epsilon, events),
+        AlgorithmType::Approximate => approximate_hull(
+            points,
+            DEFAULT_APPROXIMATION_STRIPS,
+            &mut drawing_history,
+            epsilon,
+            events,
+        ),
+    }
+}
+
+/// Computes the [convex layers](https://en.wikipedia.org/wiki/Convex_layers) of the points, from the outermost inwards.
+///
+/// Each layer is the hull of the points left after removing the vertices of the previous layers, found with
+/// [kirk_patrick_seidel]. The peeling stops once fewer than 3 points remain, or no vertices could be removed.
+pub fn convex_layers<P: Point>(points: &[P], epsilon: f32) -> Vec<Vec<P>> {
+    let mut layers = Vec::new();
+    let mut remaining = points.to_vec();
+
+    while remaining.len() >= 3 {
+        let layer = compute_hull(remaining.clone(), AlgorithmType::KirkPatrickSeidel, epsilon);
+        let vertices: HashSet<(u64, u64)> = layer.iter().map(point_key).collect();
+
+        let count = remaining.len();
+        remaining.retain(|p| !vertices.contains(&point_key(p)));
+        if remaining.len() == count {
+            break;
+        }
+
+        layers.push(layer);
+    }
+
+    layers
+}
+
+/// Finds the orientation of three points and returns [Orientation]
+/// 
+/// Calculates the angle between $p, q, r$ using $(q_y - p_y) \cdot (r_x - q_x) - (q_x - p_x) \cdot (r_y - q_y)$
+///
+/// The points are [Orientation::Colinear] when this value is within $\epsilon \cdot |q - p| \cdot |r - q|$
+/// of zero, i.e. when the sine of the turn angle is at most $\epsilon$.
+fn orientation<P: Point>(p: &P, q: &P, r: &P, epsilon: f32) -> Orientation {
+    count(|counts| counts.orientation_tests += 1);
+    let val = (q.y() - p.y()) * (r.x() - q.x()) - (q.x() - p.x()) * (r.y() - q.y());
+    let tolerance = P::Scalar::from_f32(epsilon) * q.distance_to(p) * r.distance_to(q);
+
+    if val.abs() <= tolerance {
+        return Orientation::Colinear;
+    }
+    if val > P::Scalar::ZERO {
+        return Orientation::Clockwise;
+    }
+
+    Orientation::Counterclockwise
+}
+
+/// Returns the input points that lie on an edge of the hull without being one of its vertices.
+///
+/// A point $p$ lies on the edge $ab$ when $a, p, b$ are [Orientation::Colinear] and $p$ is
+/// strictly between $a$ and $b$. Jarvis March and Kirk Patrick Seidel do not always agree on
+/// whether such points are vertices, which is why their hulls can differ on degenerate inputs.
+pub fn collinear_hull_points<P: Point>(points: &[P], hull: &[P], epsilon: f32) -> Vec<P> {
+    if hull.len() < 2 {
+        return Vec::new();
+    }
+
+    let vertices: HashSet<(u64, u64)> = hull.iter().map(point_key).collect();
+    points
+        .iter()
+        .filter(|p| !vertices.contains(&point_key(*p)))
+        .filter(|p| {
+            (0..hull.len()).any(|i| {
+                let a = hull[i];
+                let b = hull[(i + 1) % hull.len()];
+                matches!(orientation(&a, *p, &b, epsilon), Orientation::Colinear)
+                    && p.minus(&a).dot(&b.minus(&a)) > P::Scalar::ZERO
+                    && p.minus(&b).dot(&a.minus(&b)) > P::Scalar::ZERO
+            })
+        })
+        .copied()
+        .collect()
+}
+
+/// Checks whether the point lies inside the convex hull, points on its boundary count as inside.
+///
+/// The point is inside when its [orientation] with respect to every edge of the hull is the same, the
+/// [Orientation::Colinear] edges being ignored. This works for either vertex order of the hull.
+pub fn point_in_hull<P: Point>(hull: &[P], point: P, epsilon: f32) -> bool {
+    match hull.len() {
+        0 => return false,
+        1 => return hull[0] == point,
+        _ => {}
+    }
+
+    let mut side = None;
+    for i in 0..hull.len() {
+        let a = hull[i];
+        let b = hull[(i + 1) % hull.len()];
+        let clockwise = match orientation(&a, &b, &point, epsilon) {
+            Orientation::Clockwise => true,
+            Orientation::Counterclockwise => false,
+            Orientation::Colinear => {
+                // A point collinear with an edge is only on the boundary between its endpoints
+                if point.minus(&a).dot(&b.minus(&a)) < P::Scalar::ZERO
+                    || point.minus(&b).dot(&a.minus(&b)) < P::Scalar::ZERO
+                {
+                    return false;
+                }
+                continue;
+            }
+        };
+
+        if *side.get_or_insert(clockwise) != clockwise {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Inserts a point into an existing convex hull in $O(h)$ time, keeping the vertex order of the hull.
+///
+/// The edges the point can see, those it lies to the outer side of, form a contiguous chain. Its endpoints
+/// are the tangent vertices from the point, which are found with [orientation]. The vertices strictly
+/// between them are removed and the point is spliced in. The hull is then rotated to start from its
+/// leftmost vertex, like the hulls returned by [kirk_patrick_seidel]. Points inside the hull leave it unchanged.
+///
+/// Hulls with fewer than three vertices are recomputed from scratch, as they have no inside to speak of.
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+pub fn insert_point<P: Point>(hull: &mut Vec<P>, point: P, epsilon: f32) {
+    if hull.len() < 3 {
+        let mut points = hull.clone();
+        points.push(point);
+        *hull = kirk_patrick_seidel(points, &mut Vec::new(), epsilon, None);
+        return;
+    }
+
+    if point_in_hull(hull, point, epsilon) {
+        return;
+    }
+
+    let n = hull.len();
+    let twice_area = (0..n).fold(P::Scalar::ZERO, |area, i| {
+        let (a, b) = (hull[i], hull[(i + 1) % n]);
+        area + a.x() * b.y() - b.x() * a.y()
+    });
+    let counterclockwise = twice_area > P::Scalar::ZERO;
+
+    // Collinear edges count as visible, so that no collinear vertices are left on the hull
+    let visible = |i: usize| match orientation(&hull[i], &hull[(i + 1) % n], &point, epsilon) {
+        Orientation::Clockwise => counterclockwise,
+        Orientation::Counterclockwise => !counterclockwise,
+        Orientation::Colinear => true,
+    };
+
+    let Some(first) = (0..n).find(|&i| visible(i) && !visible((i + n - 1) % n)) else {
+        return;
+    };
+    let mut last = first;
+    while visible((last + 1) % n) {
+        last = (last + 1) % n;
+    }
+
+    let mut updated = Vec::with_capacity(n + 1);
+    updated.push(point);
+    let mut i = (last + 1) % n;
+    loop {
+        updated.push(hull[i]);
+        if i == first {
+            break;
+        }
+        i = (i + 1) % n;
+    }
+
+    let leftmost = (0..updated.len())
+        .min_by(|&a, &b| {
+            (updated[a].x(), updated[a].y())
+                .partial_cmp(&(updated[b].x(), updated[b].y()))
+                .unwrap()
+        })
+        .unwrap();
+    updated.rotate_left(leftmost);
+    *hull = updated;
+}
+
+/// Returns the hull of points that are all equal, which is just the first of them.
+///
+/// The algorithms can't find a hull of a single distinct point, Jarvis March never gets back to its
+/// starting point and the hulls of KPS have no edges to merge, so they return early with this instead.
+/// The points are compared exactly, the orientation epsilon is the sine of an angle and not a distance,
+/// so a tiny but proper input, like latitudes and longitudes of a small area, still gets its whole hull.
+fn single_distinct_point<P: Point>(points: &[P], drawing_history: &mut Vec<Vec<LineType>>) -> Option<Vec<P>> {
+    let first = *points.first()?;
+    if points.iter().any(|p| *p != first) {
+        return None;
+    }
+
+    drawing_history.push(vec![LineType::TextComment(
+        "Degenerate input: single distinct point".to_string(),
+    )]);
+    Some(vec![first])
+}
+
+/// Returns the hull in a canonical form, so that the outputs of the algorithms can be compared.
+///
+/// Duplicated vertices and vertices lying on the edge between their neighbours are removed, as the
+/// algorithms don't agree on whether collinear points are vertices. The remaining vertices are sorted
+/// counterclockwise by their angle around the centroid, starting from the lowest leftmost one. When all
+/// vertices are collinear only the two endpoints of the segment are kept.
+pub fn canonical_hull<P: Point>(hull: &[P], epsilon: f32) -> Vec<P> {
+    let mut seen = HashSet::new();
+    let mut vertices: Vec<P> = hull.iter().copied().filter(|p| seen.insert(point_key(p))).collect();
+    let lexicographic = |a: &P, b: &P| (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap();
+
+    if vertices.len() < 3 {
+        vertices.sort_by(lexicographic);
+        return vertices;
+    }
+
+    let first = *vertices.iter().min_by(|a, b| lexicographic(a, b)).unwrap();
+    let last = *vertices.iter().max_by(|a, b| lexicographic(a, b)).unwrap();
+    if vertices
+        .iter()
+        .all(|p| *p == first || *p == last || matches!(orientation(&first, p, &last, epsilon), Orientation::Colinear))
+    {
+        return vec![first, last];
+    }
+
+    let centroid = vertices.iter().fold(Vec2::ZERO, |sum, p| sum + p.to_vec2()) / vertices.len() as f32;
+    let angle = |p: &P| {
+        let offset = p.to_vec2() - centroid;
+        offset.y.atan2(offset.x)
+    };
+    vertices.sort_by(|a, b| angle(a).partial_cmp(&angle(b)).unwrap());
+
+    // Removing a collinear vertex can make its neighbours collinear, so this repeats until nothing changes
+    loop {
+        let n = vertices.len();
+        let Some(i) = (0..n).find(|&i| {
+            let (previous, next) = (vertices[(i + n - 1) % n], vertices[(i + 1) % n]);
+            matches!(orientation(&previous, &vertices[i], &next, epsilon), Orientation::Colinear)
+        }) else {
+            break;
+        };
+        vertices.remove(i);
+    }
+
+    let start = vertices.iter().position(|p| *p == first).unwrap_or(0);
+    vertices.rotate_left(start);
+    vertices
+}
+
+/// Generates the random point set the algorithms are cross-checked on for the given seed, see [canonical_hull].
+///
+/// Between 3 and 199 points are drawn with integer coordinates within `extent` of the origin, so that small
+/// extents give mostly duplicated and collinear points.
+pub fn cross_check_points(seed: u64, extent: i32) -> Vec<Vec2> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let count = rng.gen_range(3..200);
+    (0..count)
+        .map(|_| Vec2::new(rng.gen_range(-extent..=extent) as f32, rng.gen_range(-extent..=extent) as f32))
+        .collect()
+}
+
+/// Represents the type of hull being calculated in [kirk_patrick_seidel].
+///
+/// Decides which side of the points [connect] and [bridge] wrap around.
+enum HullType {
+    UpperHull,
+    LowerHull,
+}
+
+/// Implementation of the [Kirkpatrick Seidel](https://graphics.stanford.edu/courses/cs268-16-fall/Notes/KirkSeidel.pdf) Algorithm
+///
+/// ![](https://d3i71xaburhd42.cloudfront.net/9565745ce8b6c2114072e9620981fb97ed38e471/2-Figure1-1.png)
+///
+/// ## Pseudocode [ref](http://www.cse.yorku.ca/~andy/courses/6114/lecture-notes/KirkSeidel.pdf)
+/// ```pseudocode
+/// Algorithm UpperHull(P)
+/// 0. if |P| ≤ 2 then return the obvious answer
+/// 1. else begin
+/// 2. Compute the median of x-coordinates of points in P.
+/// 3. Partition P into two sets L and R each of size about n/2 around the median.
+/// 4. Find the upper bridge pq of L and R, p∈L, and q∈R
+/// 5. L′ ← { r ∈L x(r) ≤ x(p) }
+/// 6. R′ ← { r ∈R x(r) ≥ x(q) }
+/// 7. LUH ← UpperHull(L′)
+/// 8. RUH ← UpperHull(R′)
+/// 9. return the concatenated list LUH, pq, RUH as the upper hull of P.
+/// 10. end
+/// ```
+///
+/// ## Analysis
+/// This is a divide-&-conquer algorithm. The key step is the computation of the upper
+/// bridge which is based on the prune-&-search technique. This step can be done in O(n) time. We also know that step
+/// 2 can be done in $O(n)$ time by the linear time median finding algorithm. Hence, steps 3-6
+/// can be done in $O(n)$ time. For the purposes of analyzing algorithm `UpperHall(P)`, let us
+/// assume the upper hull of $P$ consists of $h$ edges. Our analysis will use both parameters n
+/// (input size) and $h$ (output size). Let $T(n, h)$ denote the worst-case time complexity of the
+/// algorithm. Suppose $\text{LUH}$ and $\text{RUH}$ in steps 7 and 8 consist of $h1$ and $h2$ edges, respectively. Since $|L'| ≤ |L|$ and $|R'| ≤ |R|$, the two recursive calls in steps 7 and 8 take time
+/// $T(\frac{n}2, h_1)$ and $T(\frac{n}2, h_2)$ time. (Note that $h = 1 + h1 + h2$. Hence, $h2 = h − 1 − h1$.)
+/// Therefore, the recurrence that describes the worst-case time complexity of the algorithm is
+/// $$
+/// T(n, h) = O(n) + max_{h1} \{ T(\frac{n}{2}, h_1) + T(\frac{n}{2}, h-1-h_1)\}
+/// $$
+/// if $h > 2$, otherwise $T(n, h) = O(n)$.
+///
+/// Suppose the two occurences of $O(n)$ in the above recurrence are at most $cn$,
+/// where $c$ is a suitably large constant. We will show by induction on $h$ that
+/// $T(n, h) \leq cn \log(h)$ for all $n$ and $h \leq 2$. For the base case where $h = 2$,
+/// $T(n, h) \leq cn \leq cn\log(2) = cn \log(h)$. For the inductive case,
+///
+/// $T(n, h)$
+///
+/// $\leq cn + max_{h1} \{ c\frac{n}{2}\log(h_1) + c\frac{n}{2}\log(h-1-h_1)\}$
+///
+/// $= cn + c\frac{n}{2}max_{h1} \log(h_1 (h - 1 - h_1))$
+///
+/// $\leq cn + c\frac{n}{2}\log(\frac{h}{2} \cdot \frac{h}{2})$
+///
+/// $= cn + c\frac{n}{2}2\log(\frac{h}2)$
+///
+/// $= cn\log(h)$
+///
+/// Thus we can claim runtime of kirpatrick seidel algorithm to be $O(n\log(h))$.
+///
+/// Points whose turn is within `epsilon` of a straight line are treated as collinear, see [orientation].
+/// When `events` is given the [HullEvent]s of the algorithm are recorded into it.
+pub fn kirk_patrick_seidel<P: Point>(
+    points: Vec<P>,
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    mut events: Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    if let Some(hull) = single_distinct_point(&points, drawing_history) {
+        return hull;
+    }
+
+    let upper_hull_vec = upper_hull(&points, drawing_history, epsilon, &mut events);
+    drawing_history.push(vec![LineType::TextComment("Added upper hull".to_string())]);
+
+    let lower_hull_vec = lower_hull(&points, drawing_history, epsilon, &mut events);
+    drawing_history.push(vec![LineType::TextComment("Added lower hull".to_string())]);
+
+    // Both hulls run from left to right. Where their endpoints differ the hull has a
+    // vertical edge, otherwise the two hulls share that endpoint.
+    let (upper_left, upper_right) = (upper_hull_vec[0], upper_hull_vec[upper_hull_vec.len() - 1]);
+    let (lower_left, lower_right) = (lower_hull_vec[0], lower_hull_vec[lower_hull_vec.len() - 1]);
+
+    if upper_right != lower_right {
+        record(&mut events, || HullEvent::EdgeAccepted(lower_right.to_vec2(), upper_right.to_vec2()));
+        drawing_history.push(vec![
+            LineType::PartOfHull(lower_right.to_vec2(), upper_right.to_vec2()),
+            LineType::TextComment(format!(
+                "Adding right vertical edge between {} and {}",
+                lower_right, upper_right
+            )),
+        ]);
+    }
+
+    // When all points share one x coordinate both vertical edges are the same edge
+    if upper_left != lower_left && (upper_left, lower_left) != (upper_right, lower_right) {
+        record(&mut events, || HullEvent::EdgeAccepted(upper_left.to_vec2(), lower_left.to_vec2()));
+        drawing_history.push(vec![
+            LineType::PartOfHull(upper_left.to_vec2(), lower_left.to_vec2()),
+            LineType::TextComment(format!(
+                "Adding left vertical edge between {} and {}",
+                upper_left, lower_left
+            )),
+        ]);
+    }
+
+    // Walk the lower hull left to right and then the upper hull back, which yields the
+    // hull in counterclockwise order starting from the leftmost point.
+    let mut hull = lower_hull_vec;
+    let mut upper_hull_vec: Vec<P> = upper_hull_vec.into_iter().rev().collect();
+    if upper_hull_vec.first() == hull.last() {
+        upper_hull_vec.remove(0);
+    }
+    if upper_hull_vec.last() == hull.first() {
+        upper_hull_vec.pop();
+    }
+    hull.extend(upper_hull_vec);
+
+    let mut step = vec![LineType::TextComment(
+        "Kirkseidel algorithm is complete".to_string(),
+    )];
+    step.extend(hull_vertices(&hull));
+    drawing_history.push(step);
+
+    hull
+}
+
+/// Returns the upper hull of input points
+/// # Pseudocode
+/// ```text
+/// Procedure UPPER-HULL(S)
+/// 1. Initialization
+///     Let min and max be the indices of two points in S that form the left and right
+///     endpoint of the upper hull of S respectively, i.e.
+///         x(p_min) ≤ x(p_i) ≤ x(p_max) and
+///         y(p_min) ≥ y(p_i) if x(p_min) = x(p_i),
+///         y(p_max) ≥ y(p_i) if x(p_max) = x(p_i) for i = 1,..., n
+///     If min = max then print min and stop.
+///     Let T := {p_min, p_max} ∪ {p ∈ S | x(p_min) < x(p) < x(p_max)}.
+/// 2. return CONNECT(min, max, T)
+/// ```
+fn upper_hull<P: Point>(
+    points: &[P],
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    events: &mut Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    let mut min_point = P::new(P::Scalar::MAX, P::Scalar::MIN);
+    for i in points.iter() {
+        if i.x() < min_point.x() || (i.x() == min_point.x() && i.y() > min_point.y()) {
+            min_point = *i;
+        }
+    }
+
+    let mut max_point = P::new(P::Scalar::MIN, P::Scalar::MIN);
+    for i in points.iter() {
+        if i.x() > max_point.x() || (i.x() == max_point.x() && i.y() > max_point.y()) {
+            max_point = *i;
+        }
+    }
+
+    if min_point == max_point {
+        drawing_history.push(vec![LineType::TextComment(
+            "Single point convex hull found, returning the point".to_string(),
+        )]);
+        return vec![min_point];
+    }
+
+    let mut temporary = vec![min_point, max_point];
+    temporary.extend(
+        points
+            .iter()
+            .filter(|p| p.x() > min_point.x() && p.x() < max_point.x()),
+    );
+
+    connect(
+        min_point,
+        max_point,
+        &temporary,
+        drawing_history,
+        &HullType::UpperHull,
+        epsilon,
+        events,
+        Recursion::new(temporary.len()),
+    )
+}
+
+/// Returns the lower hull of input points
+///
+/// Mirrors [upper_hull], the endpoints are the lowest points with the least and
+/// greatest $x$ coordinate and the [connect] step wraps around the points from below.
+/// # Pseudocode
+/// ```text
+/// Procedure LOWER-HULL(S)
+/// 1. Initialization
+///     Let min and max be the indices of two points in S that form the left and right
+///     endpoint of the lower hull of S respectively, i.e.
+///         x(p_min) ≤ x(p_i) ≤ x(p_max) and
+///         y(p_min) ≤ y(p_i) if x(p_min) = x(p_i),
+///         y(p_max) ≤ y(p_i) if x(p_max) = x(p_i) for i = 1,..., n
+///     If min = max then print min and stop.
+///     Let T := {p_min, p_max} ∪ {p ∈ S | x(p_min) < x(p) < x(p_max)}.
+/// 2. return CONNECT(min, max, T)
+/// ```
+fn lower_hull<P: Point>(
+    points: &[P],
+    drawing_history: &mut Vec<Vec<LineType>>,
+    epsilon: f32,
+    events: &mut Option<&mut Vec<HullEvent>>,
+) -> Vec<P> {
+    let mut min_point = P::new(P::Scalar::MAX, P::Scalar::MAX);
+    for i in points.iter() {
+        if i.x() < min_point.x() || (i.x() == min_point.x() && i.y() < min_point.y()) {
+            min_point = *i;
+        }
+    }
+
+    let mut max_point = P::new(P::Scalar::MIN, P::Scalar::MAX);
+    for i in points.iter() {
+        if i.x() > max_point.x() || (i.x() == max_point.x() && i.y() < max_point.y()) {
+            max_point = *i;
+        }
+    }
+
+    if min_point == max_point {
+        drawing_history.push(vec![LineType::TextComment(
+            "Single point convex hull found, returning the point".to_string(),
+        )]);
+        return vec![min_point];
+    }
+
+    let mut temporary = vec![min_point, max_point];
+    temporary.extend(
+        points
+            .iter()
+            .filter(|p| p.x() > min_point.x() && p.x() < max_point.x()),
+    );
+
+    connect(
+        min_point,
+        max_point,
+        &temporary,
+        drawing_history,
+        &HullType::LowerHull,
+        epsilon,
+        events,
+        Recursion::new(temporary.len()),
+    )
+}
+
+/// Returns the points that form the convex hull
+/// # Pseudocode
+/// ```text
+/// Procedure CONNECT(k, m, S)
+/// 1. Find a real number a such that
+///     x(p_i) ≤ a for ⌊|S|/2⌋ points in S and
+///     x(p_i) > a for ⌈|S|/2⌉ points in S.
+/// 2. Find the "bridge" over the vertical line L {(x, y) | x = a}, i.e.
+///     (i, j) := BRIDGE (S, a).
+/// 3. Let S_left := {p_i} ∪ {p ∈ S | x(p) < x(p_i)}.
+///     Let S_right := {p_j} ∪ {p ∈ S | x(p) > x(p_j)}.
+/// 4. If i = k
+///     then add_to_answer(print (i))
+///     else add_to_answer(CONNECT (k, i, S_left).)
+///    If j = m 
+///     then add_to_answer(print (j))
+///     else add_to_answer(CONNECT (j, m, S_right).)
+/// 5. return answer
+/// ```
+/// Note: hear print means add to the output answer
+///
+/// The depth of the `recursion` starts at 0 for the call made by [upper_hull] or [lower_hull]. It is shown
+/// in the comments of the steps and fades the vertical lines of the medians. Once it exceeds its limit, or
+/// the [bridge] exceeds its own, the points between `min` and `max` are given up on with a warning so that
+/// degenerate inputs can't overflow the stack.
+#[allow(clippy::too_many_arguments)]
+fn connect<P: Point>(
+    min: P,
+    max: P,
+    points: &[P],
+    drawing_history: &mut Vec<Vec<LineType>>,
+    hull_type: &HullType,
+    epsilon: f32,
+    events: &mut Option<&mut Vec<HullEvent>>,
+    recursion: Recursion,
+) -> Vec<P> {
+    let depth = recursion.depth;
+    let subproblem = |bridge: Option<(P, P)>| HullEvent::Subproblem {
+        depth,
+        upper: matches!(hull_type, HullType::UpperHull),
+        min: min.to_vec2(),
+        max: max.to_vec2(),
+        points: points.len(),
+        bridge: bridge.map(|(left, right)| (left.to_vec2(), right.to_vec2())),
+    };
+    let give_up = |drawing_history: &mut Vec<Vec<LineType>>, events: &mut Option<&mut Vec<HullEvent>>| {
+        record(events, || subproblem(None));
+        drawing_history.push(vec![LineType::TextComment(format!(
+            "Depth {}: recursion limit reached, skipping the points between {} and {}",
+            depth, min, max
+        ))]);
+        vec![min, max]
+    };
+    if recursion.exceeded() {
+        return give_up(drawing_history, events);
+    }
+
+    let median = median(&points.iter().map(|point| point.x()).collect::<Vec<_>>());
+    record(events, || HullEvent::MedianChosen { depth, x: median.to_f32() });
+    drawing_history.push(vec![
+        LineType::VerticalLine(median.to_f32(), depth),
+        LineType::TextComment(format!("Depth {}: median at x={}", depth, median)),
+    ]);
+
+    let Some((left, right)) = bridge(points, median, hull_type, epsilon, Recursion::new(points.len())) else {
+        return give_up(drawing_history, events);
+    };
+    record(events, || HullEvent::BridgeFound {
+        depth,
+        left: left.to_vec2(),
+        right: right.to_vec2(),
+    });
+    // Recorded before recursing, so the subproblems are listed in preorder
+    record(events, || subproblem(Some((left, right))));
+    drawing_history.push(vec![
+        LineType::PartOfHull(left.to_vec2(), right.to_vec2()),
+        LineType::TextComment(format!(
+            "Depth {}: found the bridge points {} and {}",
+            depth, left, right
+        )),
+        LineType::Temporary(min.to_vec2(), left.to_vec2()),
+        LineType::Temporary(left.to_vec2(), right.to_vec2()),
+        LineType::Temporary(right.to_vec2(), max.to_vec2()),
+        LineType::Temporary(max.to_vec2(), min.to_vec2()),
+        LineType::Milestone(format!("Depth {}: bridge from {} to {}", depth, left, right)),
+    ]);
+
+    let mut left_points = vec![left];
+    left_points.extend(points.iter().filter(|p| p.x() < left.x()));
+
+    let mut right_points = vec![right];
+    right_points.extend(points.iter().filter(|p| p.x() > right.x()));
+
+    // The subproblems that are recursed into are shaded, the left one in the first color and the right one in the second
+    let mut step: Vec<_> = [(left != min, &left_points, 0), (right != max, &right_points, 1)]
+        .into_iter()
+        .filter(|(recursed, _, _)| *recursed)
+        .map(|(_, subproblem, color)| LineType::Polygon(bounding_box(subproblem), color))
+        .collect();
+    if !step.is_empty() {
+        step.push(LineType::TextComment(format!(
+            "Depth {}: splitting into {} points left and {} points right of the bridge",
+            depth,
+            left_points.len(),
+            right_points.len()
+        )));
+        drawing_history.push(step);
+    }
+
+    let mut output = vec![];
+    if left == min {
+        output.extend(vec![left]);
+    } else {
+        output.extend(connect(
+            min,
+            left,
+            &left_points,
+            drawing_history,
+            hull_type,
+            epsilon,
+            events,
+            recursion.deeper(),
+        ));
+    }
+
+    if right == max {
+        output.extend(vec![right]);
+    } else {
+        output.extend(connect(
+            right,
+            max,
+            &right_points,
+            drawing_history,
+            hull_type,
+            epsilon,
+            events,
+            recursion.deeper(),
         ));
     }
 
     let mut temp = vec![];
     for i in 0..output.len() - 1 {
-        temp.push(LineType::Temporary(output[i], output[i + 1]))
+        temp.push(LineType::Temporary(output[i].to_vec2(), output[i + 1].to_vec2()))
     }
     temp.push(LineType::TextComment(
         "Found the connecting hull".to_string(),
@@ -472,6 +2454,14 @@ fn connect(
     output
 }
 
+/// Returns the corners of the bounding box of the points in counterclockwise order
+fn bounding_box<P: Point>(points: &[P]) -> Vec<Vec2> {
+    let (min, max) = points.iter().fold((Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)), |(min, max), point| {
+        (min.min(point.to_vec2()), max.max(point.to_vec2()))
+    });
+    vec![min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+}
+
 /// Returns the two points that forms the bridge of the points
 /// # Pseudocode
 /// ```text
@@ -509,125 +2499,364 @@ fn connect(
 ///         for all (p_i, p_j) ∈ LARGE insert p_i and p_j into CANDIDATES.
 /// 10. return(BRIDGE (CANDIDATES, a)).
 /// ```
-fn bridge(points: &[Vec2], median: f32) -> (Vec2, Vec2) {
-    let mut candidates: Vec<Vec2> = Vec::new();
+/// For the lower hull the function is mirrored: vertical pairs keep their lower point,
+/// the supporting line minimizes $y(p) - K \cdot x(p)$ and the roles of SMALL and LARGE are swapped.
+///
+/// Returns [None] once the `recursion` exceeds its limit, which only happens on degenerate inputs.
+fn bridge<P: Point>(
+    points: &[P],
+    median: P::Scalar,
+    hull_type: &HullType,
+    epsilon: f32,
+    recursion: Recursion,
+) -> Option<(P, P)> {
+    if recursion.exceeded() {
+        return None;
+    }
+
+    let mut candidates: Vec<P> = Vec::new();
+    let mut seen: HashSet<(u64, u64)> = HashSet::new();
     if points.len() == 2 {
-        return if points[0].x < points[1].x {
-            (points[0], points[1])
+        return if points[0].x() < points[1].x() {
+            Some((points[0], points[1]))
         } else {
-            (points[1], points[0])
+            Some((points[1], points[0]))
         };
     }
+    count(|counts| counts.bridge_rounds += 1);
 
     let mut sorted_points = points.to_owned();
-    sorted_points.sort_unstable_by(|a, b| a.x.partial_cmp(&b.x).unwrap());
+    sorted_points.sort_unstable_by(|a, b| a.x().partial_cmp(&b.x()).unwrap());
 
-    let mut pairs: Vec<(Vec2, Vec2)> = Vec::new();
+    let mut pairs: Vec<(P, P)> = Vec::new();
 
     for chunk in sorted_points.chunks(2) {
         if chunk.len() == 2 {
             pairs.push((chunk[0], chunk[1]));
         } else {
-            candidates.push(chunk[0]);
+            push_candidate(&mut candidates, &mut seen, chunk[0]);
         }
     }
 
     let mut slopes = vec![];
 
     for (point_i, point_j) in pairs.iter() {
-        if point_i.x == point_j.x {
-            if point_i.y > point_j.y {
-                if !candidates.contains(point_i) {
-                    candidates.push(*point_i);
-                }
-            } else if !candidates.contains(point_j) {
-                candidates.push(*point_j);
+        if point_i.x() == point_j.x() {
+            let keep_i = match hull_type {
+                HullType::UpperHull => point_i.y() > point_j.y(),
+                HullType::LowerHull => point_i.y() < point_j.y(),
+            };
+            if keep_i {
+                push_candidate(&mut candidates, &mut seen, *point_i);
+            } else {
+                push_candidate(&mut candidates, &mut seen, *point_j);
             }
         } else {
             slopes.push((
                 point_i,
                 point_j,
-                (point_i.y - point_j.y) / (point_i.x - point_j.x),
+                (point_i.y() - point_j.y()) / (point_i.x() - point_j.x()),
             ));
         }
     }
 
-    let median_slope =
-        median_of_medians(&slopes.iter().map(|(_, _, slope)| slope).collect::<Vec<_>>());
-    let small = slopes.iter().filter(|(_, _, slope)| slope < median_slope);
-    let equal = slopes.iter().filter(|(_, _, slope)| slope == median_slope);
-    let large = slopes.iter().filter(|(_, _, slope)| slope > median_slope);
+    // Every pair was vertical, the candidates already hold one point of each of them
+    if slopes.is_empty() {
+        count(|counts| counts.pruned_candidates += points.len() - candidates.len());
+        return bridge(&candidates, median, hull_type, epsilon, recursion.deeper());
+    }
+
+    let median_slope = self::median(&slopes.iter().map(|(_, _, slope)| *slope).collect::<Vec<_>>());
+    let small: Vec<_> = slopes.iter().filter(|(_, _, slope)| *slope < median_slope).collect();
+    let equal: Vec<_> = slopes.iter().filter(|(_, _, slope)| *slope == median_slope).collect();
+    let large: Vec<_> = slopes.iter().filter(|(_, _, slope)| *slope > median_slope).collect();
+
+    // Negating the intercept turns the lower hull into the upper hull case
+    let (upper, small, large) = match hull_type {
+        HullType::UpperHull => (true, small, large),
+        HullType::LowerHull => (false, large, small),
+    };
+    let intercept = |p: &P| {
+        let value = p.y() - median_slope * p.x();
+        if upper {
+            value
+        } else {
+            -value
+        }
+    };
 
-    // set of points with maximum value of p.y - median_slope * p.x
+    // set of points with maximum value of the intercept p.y - median_slope * p.x. The tolerance follows the
+    // magnitude of the terms, so that nearly collinear vertices aren't merged into the supporting line.
     let max_value = points
         .iter()
-        .map(|p| p.y - median_slope * p.x)
-        .fold(f32::MIN, f32::max);
+        .map(intercept)
+        .fold(P::Scalar::MIN, |max, value| if value > max { value } else { max });
+    let magnitude = points
+        .iter()
+        .map(|p| p.y().abs() + (median_slope * p.x()).abs())
+        .fold(P::Scalar::ZERO, |max, value| if value > max { value } else { max });
+    let tolerance = P::Scalar::from_f32(epsilon) * magnitude;
     let max_points: Vec<_> = points
         .iter()
-        .filter(|p| ((p.y - median_slope * p.x) - max_value).abs() < 0.01)
+        .filter(|p| (intercept(p) - max_value).abs() <= tolerance)
         .collect();
     let min_point = max_points
         .iter()
-        .min_by(|a, b| a.x.partial_cmp(&b.x).unwrap())
+        .min_by(|a, b| a.x().partial_cmp(&b.x()).unwrap())
         .unwrap();
     let max_point = max_points
         .iter()
-        .max_by(|a, b| a.x.partial_cmp(&b.x).unwrap())
+        .max_by(|a, b| a.x().partial_cmp(&b.x()).unwrap())
         .unwrap();
 
-    if min_point.x <= median && max_point.x > median {
-        return (**min_point, **max_point);
-    } else if max_point.x <= median {
+    if min_point.x() <= median && max_point.x() > median {
+        return Some((**min_point, **max_point));
+    } else if max_point.x() <= median {
         for (_, point2, _) in large {
-            if !candidates.contains(point2) {
-                candidates.push(**point2);
-            }
+            push_candidate(&mut candidates, &mut seen, **point2);
         }
 
         for (_, point2, _) in equal {
-            if !candidates.contains(point2) {
-                candidates.push(**point2);
-            }
+            push_candidate(&mut candidates, &mut seen, **point2);
         }
 
         for (point1, point2, _) in small {
-            if !candidates.contains(point2) {
-                candidates.push(**point2);
-            }
-
-            if !candidates.contains(point1) {
-                candidates.push(**point1);
-            }
+            push_candidate(&mut candidates, &mut seen, **point2);
+            push_candidate(&mut candidates, &mut seen, **point1);
         }
-    } else if min_point.x > median {
+    } else if min_point.x() > median {
         for (point1, _, _) in small {
-            if !candidates.contains(point1) {
-                candidates.push(**point1);
-            }
+            push_candidate(&mut candidates, &mut seen, **point1);
         }
 
         for (point1, _, _) in equal {
-            if !candidates.contains(point1) {
-                candidates.push(**point1);
-            }
+            push_candidate(&mut candidates, &mut seen, **point1);
         }
 
         for (point1, point2, _) in large {
-            if !candidates.contains(point2) {
-                candidates.push(**point2);
-            }
+            push_candidate(&mut candidates, &mut seen, **point2);
+            push_candidate(&mut candidates, &mut seen, **point1);
+        }
+    }
 
-            if !candidates.contains(point1) {
-                candidates.push(**point1);
-            }
+    count(|counts| counts.pruned_candidates += points.len() - candidates.len());
+    bridge(&candidates, median, hull_type, epsilon, recursion.deeper())
+}
+
+/// Depth of the recursion of [connect] or [bridge], along with the limit it may not exceed.
+///
+/// Each level of both recursions discards a constant fraction of the points, so on valid inputs their depth
+/// stays within a multiple of $\log_2(n)$. The limit adds some slack on top of that.
+#[derive(Clone, Copy)]
+struct Recursion {
+    depth: usize,
+    limit: usize,
+}
+
+impl Recursion {
+    /// Multiple of $\log_2(n)$ the depth of the recursion may reach
+    const LOG_FACTOR: usize = 4;
+    /// Depth the recursion may reach on top of the multiple of $\log_2(n)$
+    const SLACK: usize = 16;
+
+    /// Starts a recursion over `n` points
+    fn new(n: usize) -> Self {
+        let log2 = (usize::BITS - n.leading_zeros()) as usize;
+        Self {
+            depth: 0,
+            limit: Self::LOG_FACTOR * log2 + Self::SLACK,
+        }
+    }
+
+    /// Returns the recursion one level deeper
+    fn deeper(self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..self
+        }
+    }
+
+    /// Whether the depth is beyond the limit
+    fn exceeded(&self) -> bool {
+        self.depth > self.limit
+    }
+}
+
+/// A subproblem of the [connect] recursion of [kirk_patrick_seidel], along with the subproblems it split into.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecursionNode {
+    /// Depth of the recursion, 0 for the whole upper or lower hull
+    pub depth: usize,
+    /// Whether the subproblem belongs to the upper hull
+    pub upper: bool,
+    /// Left endpoint the subproblem connects from
+    pub min: Vec2,
+    /// Right endpoint the subproblem connects to
+    pub max: Vec2,
+    /// Number of points of the subproblem
+    pub points: usize,
+    /// Bridge found over the median, [None] when the subproblem was given up on
+    pub bridge: Option<(Vec2, Vec2)>,
+    /// Subproblems left and right of the bridge, in this order
+    pub children: Vec<RecursionNode>,
+}
+
+/// Rebuilds the recursion of [kirk_patrick_seidel] from its [HullEvent::Subproblem]s, one tree per hull.
+///
+/// The subproblems are recorded in preorder, so each one is a child of the last one recorded a level above it.
+pub fn recursion_tree(events: &[HullEvent]) -> Vec<RecursionNode> {
+    fn attach(stack: &mut [RecursionNode], roots: &mut Vec<RecursionNode>, node: RecursionNode) {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+
+    let mut roots = Vec::new();
+    let mut stack: Vec<RecursionNode> = Vec::new();
+    for event in events {
+        let HullEvent::Subproblem { depth, upper, min, max, points, bridge } = event else {
+            continue;
+        };
+        while stack.len() > *depth {
+            let node = stack.pop().unwrap();
+            attach(&mut stack, &mut roots, node);
+        }
+        stack.push(RecursionNode {
+            depth: *depth,
+            upper: *upper,
+            min: *min,
+            max: *max,
+            points: *points,
+            bridge: *bridge,
+            children: Vec::new(),
+        });
+    }
+    while let Some(node) = stack.pop() {
+        attach(&mut stack, &mut roots, node);
+    }
+
+    roots
+}
+
+/// Returns the bit representation of a point, so that it can be used as a hash key.
+///
+/// See [Scalar::key], which keeps the key consistent with `==` on the points.
+fn point_key<P: Point>(point: &P) -> (u64, u64) {
+    (point.x().key(), point.y().key())
+}
+
+/// Pushes `point` into the bridge candidates unless it is already one of them.
+///
+/// `seen` holds the [point_key] of every candidate, which makes the check constant time
+/// instead of a linear scan over `candidates`.
+fn push_candidate<P: Point>(candidates: &mut Vec<P>, seen: &mut HashSet<(u64, u64)>, point: P) {
+    if seen.insert(point_key(&point)) {
+        candidates.push(point);
+    }
+}
+
+/// Returns the points without their duplicates, in the order they first occur.
+///
+/// This is how the bridges of [kirk_patrick_seidel] collect their candidates with [push_candidate].
+pub fn deduplicate_points<P: Point>(points: &[P]) -> Vec<P> {
+    let mut candidates = Vec::new();
+    let mut seen = HashSet::new();
+    for point in points {
+        push_candidate(&mut candidates, &mut seen, *point);
+    }
+    candidates
+}
+
+/// Returns the exact median of the input list, the lower one of the two middle elements if its length is even
+///
+/// Uses the [Median of medians](https://en.wikipedia.org/wiki/Median_of_medians) selection algorithm by
+/// Blum, Floyd, Pratt, Rivest and Tarjan, which finds the median in $O(n)$ time in the worst case.
+/// # Pseudocode
+/// ```text
+/// Function SELECT(S, k)
+/// 1. If |S| ≤ 5 then sort S and return its k-th element.
+/// 2. Partition S into ⌈|S|/5⌉ disjoint sets of size 5 and let M be the set of their medians.
+/// 3. Let p := SELECT(M, ⌊|M|/2⌋).
+/// 4. Partition S into L := {s ∈ S | s < p}, E := {s ∈ S | s = p} and G := {s ∈ S | s > p}.
+/// 5. If k < |L| then return SELECT(L, k).
+/// 6. If k < |L| + |E| then return p.
+/// 7. return SELECT(G, k - |L| - |E|).
+/// ```
+/// The median is SELECT(S, ⌊(|S| - 1)/2⌋). Ties with the pivot are all resolved at once in step 6.
+/// # Analysis
+/// The pivot p is the exact median of M, so at least half of the ⌈|S|/5⌉ sets have a median that is at
+/// most p, and each of them contributes three elements that are at most p. Thus $|L| \leq \frac{7n}{10} + 6$,
+/// and likewise for G, so every step discards a constant fraction of S. With the $O(n)$ partitioning the
+/// time complexity is $T(n) \leq T(\frac{n}{5}) + T(\frac{7n}{10} + 6) + cn$. As $\frac{1}{5} + \frac{7}{10} < 1$
+/// the sizes of the subproblems shrink geometrically, which gives $T(n) \leq 10cn = O(n)$.
+pub fn median<T>(nums: &[T]) -> T
+where
+    T: Clone + Copy + PartialOrd,
+{
+    if nums.is_empty() {
+        panic!("No median of an empty list");
+    }
+    count(|counts| counts.medians += 1);
+
+    select(nums.to_vec(), (nums.len() - 1) / 2)
+}
+
+/// Returns the `k`-th smallest element of `nums`, see [median]
+fn select<T>(mut nums: Vec<T>, k: usize) -> T
+where
+    T: Clone + Copy + PartialOrd,
+{
+    if nums.len() <= 5 {
+        nums.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+        return nums[k];
+    }
+
+    let medians = group_medians(&nums);
+    let middle = medians.len() / 2;
+    let pivot = select(medians, middle);
+    count(|counts| counts.comparisons += nums.len());
+    let mut equal = 0;
+    let mut less = Vec::new();
+    let mut greater = Vec::new();
+    for num in nums {
+        if num < pivot {
+            less.push(num);
+        } else if num > pivot {
+            greater.push(num);
+        } else {
+            equal += 1;
         }
     }
 
-    bridge(&candidates, median)
+    if k < less.len() {
+        select(less, k)
+    } else if k < less.len() + equal {
+        pivot
+    } else {
+        let skipped = less.len() + equal;
+        select(greater, k - skipped)
+    }
+}
+
+/// Returns the medians of the consecutive groups of 5 elements, the last group may be smaller
+fn group_medians<T>(nums: &[T]) -> Vec<T>
+where
+    T: Clone + Copy + PartialOrd,
+{
+    nums.chunks(5)
+        .map(|chunk| {
+            let mut chunk = chunk.to_vec();
+            chunk.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+            chunk[chunk.len() / 2]
+        })
+        .collect()
 }
 
-/// Returns the [Median of medians](https://en.wikipedia.org/wiki/Median_of_medians) of the input list
+/// Returns the repeated median of medians of the input list
+///
+/// Unlike the pivot of [median], which selects the exact median of the group medians, the groups are
+/// reduced again and again, so the result is only an approximation of the median.
 /// # Pseudocode
 /// ```text
 /// Function MEDIAN-OF-MEDIANS(S)
@@ -637,40 +2866,620 @@ fn bridge(points: &[Vec2], median: f32) -> (Vec2, Vec2) {
 /// 4. return MEDIAN-OF-MEDIANS(M).
 /// ```
 /// # Analysis
-/// The algorithm is a divide-&-conquer algorithm. The key step is the computation of the median
-/// of medians which is based on the partitioning of the input set into disjoint sets of size 5.
-/// This step can be done in O(n) time. We also know that step 4 can be done in O(n) time by the
-/// linear time median finding algorithm. Hence, steps 2-4 can be done in O(n) time. For the purposes
-/// of analyzing algorithm `MEDIAN-OF-MEDIANS(S)`, let us assume the input set consists of n elements.
-/// Let $T(n)$ denote the worst-case time complexity of the algorithm. Suppose the two occurrences of
-/// $O(n)$ in the above recurrence are at most $cn$, where $c$ is a suitably large constant. We will show
-/// by induction on $n$ that $T(n) \leq cn \log(n)$ for all $n \geq 5$. For the base case where $n = 5$,
-/// $T(n) \leq cn \leq cn\log(5) = cn \log(n)$. For the inductive case,
-/// $T(n) \leq cn + T(\frac{n}{5})$.
-/// By the inductive hypothesis, $T(\frac{n}{5}) \leq c \frac{n}{5} \log(\frac{n}{5})$.
-/// Therefore, $T(n) \leq cn + c \frac{n}{5} \log(\frac{n}{5}) = cn \log(n)$, completing the induction.
+/// Step 2 and 3 take $O(n)$ time and step 4 recurses on a fifth of the input, thus
+/// $T(n) \leq cn + T(\frac{n}{5}) \leq \frac{5}{4}cn = O(n)$.
 pub fn median_of_medians<T>(nums: &[T]) -> T
 where
     T: Clone + Copy + PartialOrd,
 {
     match nums.len() {
         0 => panic!("No median of an empty list"),
-        1 => nums[0],
-        2..=5 => {
-            let mut nums = nums.to_owned();
-            nums.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
-            nums[nums.len() / 2]
-        }
-        _ => median_of_medians(
-            &nums
-                .to_vec()
-                .chunks(5)
-                .map(|chunk| {
-                    let mut chunk = chunk.to_vec();
-                    chunk.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
-                    chunk[chunk.len() / 2]
-                })
-                .collect::<Vec<T>>(),
-        ),
+        1..=5 => group_medians(nums)[0],
+        _ => median_of_medians(&group_medians(nums)),
+    }
+}
+
+/// Computes the smallest circle containing all the points using [Welzl's algorithm](https://en.wikipedia.org/wiki/Smallest-circle_problem#Welzl's_algorithm)
+///
+/// Returns the center and the radius of the circle. The circle is always determined by at most three
+/// points of the support set, all of which are vertices of the convex hull.
+///
+/// The points are shuffled with `rng` and the recursion is unrolled into the equivalent iterative form, which
+/// has an expected $O(n)$ time complexity and doesn't overflow the stack on large inputs.
+/// ```pseudocode
+/// algorithm welzl(P) is
+///     shuffle P
+///     D := circle of radius 0 around p_1
+///     for i := 2 to n do
+///         if p_i is not in D then
+///             D := circle of radius 0 around p_i
+///             for j := 1 to i - 1 do
+///                 if p_j is not in D then
+///                     D := circle with diameter p_i p_j
+///                     for k := 1 to j - 1 do
+///                         if p_k is not in D then
+///                             D := circle through p_i, p_j, p_k
+///     return D
+/// ```
+pub fn min_enclosing_circle(points: &[Vec2], rng: &mut impl Rng) -> (Vec2, f32) {
+    if points.is_empty() {
+        return (Vec2::ZERO, 0.0);
+    }
+
+    let mut points = points.to_vec();
+    points.shuffle(rng);
+
+    let mut circle = (points[0], 0.0);
+    for i in 1..points.len() {
+        if circle_contains(circle, points[i]) {
+            continue;
+        }
+
+        circle = (points[i], 0.0);
+        for j in 0..i {
+            if circle_contains(circle, points[j]) {
+                continue;
+            }
+
+            circle = circle_from_diameter(points[i], points[j]);
+            for &point in &points[..j] {
+                if !circle_contains(circle, point) {
+                    circle = circumcircle(points[i], points[j], point);
+                }
+            }
+        }
+    }
+
+    circle
+}
+
+/// Checks whether the point lies in the circle, allowing for a small relative rounding error
+fn circle_contains((center, radius): (Vec2, f32), point: Vec2) -> bool {
+    center.distance(point) <= radius * (1.0 + 1e-5) + 1e-5
+}
+
+/// Returns the circle having the segment $ab$ as its diameter
+fn circle_from_diameter(a: Vec2, b: Vec2) -> (Vec2, f32) {
+    ((a + b) / 2.0, a.distance(b) / 2.0)
+}
+
+/// Returns the circle passing through the three points.
+///
+/// When the points are collinear no such circle exists, so the circle with the two farthest points as
+/// its diameter is returned instead.
+fn circumcircle(a: Vec2, b: Vec2, c: Vec2) -> (Vec2, f32) {
+    let ab = b - a;
+    let ac = c - a;
+    let d = 2.0 * ab.perp_dot(ac);
+
+    if d.abs() <= f32::EPSILON * ab.length() * ac.length() {
+        return [
+            circle_from_diameter(a, b),
+            circle_from_diameter(a, c),
+            circle_from_diameter(b, c),
+        ]
+        .into_iter()
+        .fold((a, 0.0), |largest, circle| if circle.1 > largest.1 { circle } else { largest });
+    }
+
+    let center = a + Vec2::new(
+        ac.y * ab.length_squared() - ab.y * ac.length_squared(),
+        ab.x * ac.length_squared() - ac.x * ab.length_squared(),
+    ) / d;
+
+    (center, center.distance(a))
+}
+
+/// Finds the farthest pair of points of the hull using [rotating calipers](https://en.wikipedia.org/wiki/Rotating_calipers)
+///
+/// Returns the two antipodal points and the distance between them. The hull vertices have to be ordered
+/// along the hull, either clockwise or counterclockwise, as returned by the algorithms.
+///
+/// For every edge of the hull the vertex farthest from it is found by advancing a second pointer while
+/// the area of the triangle it forms with the edge grows. As both pointers only go around the hull once
+/// this takes $O(h)$ time. When all the points are collinear the areas are all zero, in which case the
+/// diameter is found by taking the farthest point from any vertex and then the farthest point from that one.
+pub fn hull_diameter(hull: &[Vec2]) -> (Vec2, Vec2, f32) {
+    match hull.len() {
+        0 => return (Vec2::ZERO, Vec2::ZERO, 0.0),
+        1 => return (hull[0], hull[0], 0.0),
+        2 => return (hull[0], hull[1], hull[0].distance(hull[1])),
+        _ => {}
+    }
+
+    let n = hull.len();
+    let area = |a: Vec2, b: Vec2, c: Vec2| (b - a).perp_dot(c - a).abs();
+
+    let mut diameter = (hull[0], hull[1], hull[0].distance(hull[1]));
+    let mut update = |a: Vec2, b: Vec2| {
+        let distance = a.distance(b);
+        if distance > diameter.2 {
+            diameter = (a, b, distance);
+        }
+    };
+
+    let mut j = 1;
+    let mut degenerate = true;
+    for i in 0..n {
+        let a = hull[i];
+        let b = hull[(i + 1) % n];
+        while area(a, b, hull[(j + 1) % n]) > area(a, b, hull[j]) {
+            j = (j + 1) % n;
+            degenerate = false;
+        }
+        update(a, hull[j]);
+        update(b, hull[j]);
+    }
+
+    if degenerate {
+        let farthest = |from: Vec2| {
+            hull.iter()
+                .copied()
+                .fold(from, |far, p| if from.distance(p) > from.distance(far) { p } else { far })
+        };
+        let start = farthest(hull[0]);
+        update(start, farthest(start));
+    }
+
+    diameter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::DVec2;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// Algorithms that find the exact hull of any point set, Melkman's algorithm only does for simple polylines
+    const EXACT_ALGORITHMS: [AlgorithmType; 7] = [
+        AlgorithmType::JarvisMarch,
+        AlgorithmType::KirkPatrickSeidel,
+        AlgorithmType::MonotoneChain,
+        AlgorithmType::Quickhull,
+        AlgorithmType::Chan,
+        AlgorithmType::DivideAndConquer,
+        AlgorithmType::RandomizedIncremental,
+    ];
+
+    /// Coordinate ranges of the random points, the smaller one gives mostly duplicated and collinear points
+    const EXTENTS: [i32; 2] = [3, 100];
+
+    /// Orders the points by their angle around the centroid, which makes them a simple polyline for [melkman]
+    fn star_polyline(points: &[Vec2]) -> Vec<Vec2> {
+        let centroid = points.iter().copied().sum::<Vec2>() / points.len() as f32;
+        let angle = |p: &Vec2| (p.y - centroid.y).atan2(p.x - centroid.x);
+        let mut polyline = points.to_vec();
+        polyline.sort_by(|a, b| angle(a).partial_cmp(&angle(b)).unwrap());
+        polyline
+    }
+
+    fn canonical(hull: &[Vec2]) -> Vec<Vec2> {
+        canonical_hull(hull, DEFAULT_ORIENTATION_EPSILON)
+    }
+
+    #[test]
+    fn exact_algorithms_agree_on_random_points() {
+        for (seed, extent) in (0..200).flat_map(|seed| EXTENTS.map(|extent| (seed, extent))) {
+            let points = cross_check_points(seed, extent);
+            let reference = canonical(&compute_hull(
+                points.clone(),
+                AlgorithmType::JarvisMarch,
+                DEFAULT_ORIENTATION_EPSILON,
+            ));
+
+            for algorithm in EXACT_ALGORITHMS {
+                let hull = compute_hull(points.clone(), algorithm, DEFAULT_ORIENTATION_EPSILON);
+                assert_eq!(canonical(&hull), reference, "seed {} within {}", seed, extent);
+            }
+            // The duplicated points give the star polyline ties in the angle, so it isn't always simple
+            if extent == EXTENTS[1] {
+                let hull = compute_hull(star_polyline(&points), AlgorithmType::Melkman, DEFAULT_ORIENTATION_EPSILON);
+                assert_eq!(canonical(&hull), reference, "seed {} within {}", seed, extent);
+            }
+        }
+    }
+
+    #[test]
+    fn hulls_are_counterclockwise() {
+        for (seed, extent) in (0..200).flat_map(|seed| EXTENTS.map(|extent| (seed, extent))) {
+            let points = cross_check_points(seed, extent);
+            let hulls = EXACT_ALGORITHMS
+                .into_iter()
+                .chain([AlgorithmType::Approximate])
+                .map(|algorithm| compute_hull(points.clone(), algorithm, DEFAULT_ORIENTATION_EPSILON))
+                .chain([compute_hull(star_polyline(&points), AlgorithmType::Melkman, DEFAULT_ORIENTATION_EPSILON)]);
+
+            for hull in hulls.filter(|hull| hull.len() >= 3) {
+                let n = hull.len();
+                for i in 0..n {
+                    let (a, b, c) = (hull[i], hull[(i + 1) % n], hull[(i + 2) % n]);
+                    assert!((b - a).perp_dot(c - b) >= 0.0, "seed {} within {}: {:?}", seed, extent, hull);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tall_thin_rectangle_has_each_corner_once() {
+        let corners = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1000.0),
+            Vec2::new(0.0, 1000.0),
+        ];
+        let mut points = corners.to_vec();
+        points.extend((1..100).flat_map(|i| [Vec2::new(0.0, i as f32 * 10.0), Vec2::new(1.0, i as f32 * 10.0)]));
+        points.push(Vec2::new(0.5, 500.0));
+
+        for algorithm in [AlgorithmType::JarvisMarch, AlgorithmType::KirkPatrickSeidel] {
+            let hull = compute_hull(points.clone(), algorithm, DEFAULT_ORIENTATION_EPSILON);
+            for corner in corners {
+                assert_eq!(hull.iter().filter(|p| **p == corner).count(), 1, "{} in {:?}", corner, hull);
+            }
+        }
+    }
+
+    #[test]
+    fn jarvis_march_stops_when_passing_the_start_within_epsilon() {
+        // The leftmost point lies within the tolerance on the edge from (0, 100) to (0.00005, -10)
+        let points = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 100.0),
+            Vec2::new(0.00005, -10.0),
+            Vec2::new(10.0, 0.0),
+        ];
+        let hull = compute_hull(points.clone(), AlgorithmType::JarvisMarch, DEFAULT_ORIENTATION_EPSILON);
+
+        assert!(hull.len() <= points.len(), "{:?}", hull);
+        for vertex in &hull {
+            assert_eq!(hull.iter().filter(|p| *p == vertex).count(), 1, "{:?}", hull);
+        }
+
+        // Fractional points within the tolerance of a line, some of them with a point off the line
+        let mut rng = StdRng::seed_from_u64(0);
+        for seed in 0..200 {
+            let mut points: Vec<_> = (0..rng.gen_range(3..50))
+                .map(|_| Vec2::new(rng.gen_range(-100.0..100.0), rng.gen_range(-1e-4..1e-4)))
+                .collect();
+            if seed % 2 == 0 {
+                points.push(Vec2::new(rng.gen_range(-100.0..100.0), 0.01));
+            }
+            let hull = compute_hull(points.clone(), AlgorithmType::JarvisMarch, DEFAULT_ORIENTATION_EPSILON);
+            for vertex in &hull {
+                assert_eq!(hull.iter().filter(|p| *p == vertex).count(), 1, "seed {}: {:?}", seed, points);
+            }
+        }
+    }
+
+    #[test]
+    fn orientation_tolerates_turns_within_epsilon() {
+        // The sine of the turn at q is about the offset of r divided by the length of the second segment
+        let (p, q) = (Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0));
+        let turn = |factor: f32| {
+            let r = Vec2::new(200.0, DEFAULT_ORIENTATION_EPSILON * 100.0 * factor);
+            orientation(&p, &q, &r, DEFAULT_ORIENTATION_EPSILON)
+        };
+
+        assert!(matches!(turn(0.9), Orientation::Colinear));
+        assert!(matches!(turn(-0.9), Orientation::Colinear));
+        assert!(matches!(turn(1.1), Orientation::Counterclockwise));
+        assert!(matches!(turn(-1.1), Orientation::Clockwise));
+    }
+
+    #[test]
+    fn f64_points_resolve_turns_that_f32_rounds_away() {
+        let epsilon = 1e-12;
+        let (p, q, r) = (DVec2::new(0.0, 0.0), DVec2::new(1.0, 1.0), DVec2::new(2.0, 2.0 + 1e-9));
+        assert!(matches!(orientation(&p, &q, &r, epsilon), Orientation::Counterclockwise));
+        assert!(matches!(
+            orientation(&p.as_vec2(), &q.as_vec2(), &r.as_vec2(), epsilon),
+            Orientation::Colinear
+        ));
+
+        // The apex lies just above the top edge of the square, in f32 it lies on the edge
+        let apex = DVec2::new(0.5, 1.0 + 1e-9);
+        let points = vec![
+            DVec2::new(0.0, 0.0),
+            DVec2::new(1.0, 0.0),
+            DVec2::new(1.0, 1.0),
+            DVec2::new(0.0, 1.0),
+            apex,
+        ];
+        for algorithm in EXACT_ALGORITHMS {
+            let hull = compute_hull(points.clone(), algorithm, epsilon);
+            assert_eq!(hull.len(), 5, "{}: {:?}", algorithm.name(), hull);
+            assert!(hull.contains(&apex), "{}: {:?}", algorithm.name(), hull);
+
+            let rounded = compute_hull(points.iter().map(DVec2::as_vec2).collect(), algorithm, epsilon);
+            assert_eq!(rounded.len(), 4, "{}: {:?}", algorithm.name(), rounded);
+        }
+    }
+
+    #[test]
+    fn f32_and_f64_hulls_agree() {
+        for (seed, extent) in (0..50).flat_map(|seed| EXTENTS.map(|extent| (seed, extent))) {
+            let points = cross_check_points(seed, extent);
+            let wide: Vec<DVec2> = points.iter().map(|p| p.as_dvec2()).collect();
+
+            for algorithm in EXACT_ALGORITHMS.into_iter().chain([AlgorithmType::Approximate]) {
+                let hull = compute_hull(points.clone(), algorithm, DEFAULT_ORIENTATION_EPSILON);
+                let wide_hull = compute_hull(wide.clone(), algorithm, DEFAULT_ORIENTATION_EPSILON);
+                let narrowed: Vec<Vec2> = wide_hull.iter().map(|p| p.as_vec2()).collect();
+                assert_eq!(canonical(&narrowed), canonical(&hull), "{} on seed {}", algorithm.name(), seed);
+            }
+        }
+    }
+
+    #[test]
+    fn hull_diameter_of_degenerate_hulls() {
+        let (a, b) = (Vec2::new(1.0, 2.0), Vec2::new(4.0, 6.0));
+        assert_eq!(hull_diameter(&[]), (Vec2::ZERO, Vec2::ZERO, 0.0));
+        assert_eq!(hull_diameter(&[a]), (a, a, 0.0));
+        assert_eq!(hull_diameter(&[a, b]), (a, b, 5.0));
+
+        // All the areas of the calipers are zero, the diameter is found from the farthest points instead
+        let run: Vec<Vec2> = (0..6).map(|i| Vec2::new(i as f32, 2.0 * i as f32)).collect();
+        let (start, end, distance) = hull_diameter(&run);
+        let pair = [start, end];
+        assert!(pair.contains(&run[0]) && pair.contains(&run[5]), "{:?}", pair);
+        assert_eq!(distance, run[0].distance(run[5]));
+    }
+
+    #[test]
+    fn hull_diameter_of_rectangle_with_vertex_on_an_edge() {
+        let rectangle = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(4.0, 2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ];
+        let (start, end, distance) = hull_diameter(&rectangle);
+
+        let diagonals = [(rectangle[0], rectangle[2]), (rectangle[1], rectangle[4])];
+        assert!(
+            diagonals.iter().any(|&(a, b)| (start, end) == (a, b) || (start, end) == (b, a)),
+            "{} to {}",
+            start,
+            end
+        );
+        assert_eq!(distance, 20.0f32.sqrt());
+    }
+
+    #[test]
+    fn insert_point_matches_the_hull_of_all_points() {
+        let hexagon = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(4.0, -2.0),
+            Vec2::new(8.0, 0.0),
+            Vec2::new(8.0, 4.0),
+            Vec2::new(4.0, 6.0),
+            Vec2::new(0.0, 4.0),
+            Vec2::new(3.0, 2.0),
+        ];
+        let inserted = [
+            // Inside, the hull stays the same
+            Vec2::new(2.0, 2.0),
+            // Hides the three vertices on the right
+            Vec2::new(20.0, 2.0),
+            // Extends the edge from (0, 0) to (4, -2), which leaves (4, -2) collinear
+            Vec2::new(8.0, -4.0),
+            // On the left edge
+            Vec2::new(0.0, 1.0),
+        ];
+
+        for point in inserted {
+            let mut hull = compute_hull(hexagon.clone(), AlgorithmType::KirkPatrickSeidel, DEFAULT_ORIENTATION_EPSILON);
+            let before = hull.clone();
+            insert_point(&mut hull, point, DEFAULT_ORIENTATION_EPSILON);
+
+            let mut points = hexagon.clone();
+            points.push(point);
+            let expected = compute_hull(points, AlgorithmType::KirkPatrickSeidel, DEFAULT_ORIENTATION_EPSILON);
+            assert_eq!(canonical(&hull), canonical(&expected), "{} into {:?}", point, before);
+            if point_in_hull(&before, point, DEFAULT_ORIENTATION_EPSILON) {
+                assert_eq!(hull, before, "{}", point);
+            }
+        }
+    }
+
+    #[test]
+    fn insert_point_into_small_hulls() {
+        let (a, b, c) = (Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0), Vec2::new(2.0, 3.0));
+        let cases = [(vec![], a), (vec![a], b), (vec![a, b], c), (vec![a, b], Vec2::new(8.0, 0.0))];
+
+        for (small, point) in cases {
+            let mut hull = small.clone();
+            insert_point(&mut hull, point, DEFAULT_ORIENTATION_EPSILON);
+
+            let mut points = small.clone();
+            points.push(point);
+            let expected = compute_hull(points, AlgorithmType::KirkPatrickSeidel, DEFAULT_ORIENTATION_EPSILON);
+            assert_eq!(canonical(&hull), canonical(&expected), "{} into {:?}", point, small);
+        }
+    }
+
+    #[test]
+    fn min_enclosing_circle_contains_all_points() {
+        for (seed, extent) in (0..100).flat_map(|seed| EXTENTS.map(|extent| (seed, extent))) {
+            let points = cross_check_points(seed, extent);
+            let (center, radius) = min_enclosing_circle(&points, &mut StdRng::seed_from_u64(seed));
+            for point in &points {
+                assert!(center.distance(*point) <= radius + 1e-3, "seed {}: {} outside", seed, point);
+            }
+        }
+    }
+
+    #[test]
+    fn min_enclosing_circle_of_few_points() {
+        let (a, b) = (Vec2::new(1.0, 2.0), Vec2::new(7.0, 10.0));
+        let cases = [
+            (vec![a], (a, 0.0)),
+            (vec![a, b], (Vec2::new(4.0, 6.0), 5.0)),
+            // Right triangle, its hypotenuse is the diameter
+            (vec![Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0), Vec2::new(0.0, 3.0)], (Vec2::new(2.0, 1.5), 2.5)),
+            // Acute triangle, the circle passes through all three points
+            (vec![Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(1.0, 2.0)], (Vec2::new(1.0, 0.75), 1.25)),
+            // Obtuse triangle, the longest side is the diameter
+            (vec![Vec2::new(0.0, 0.0), Vec2::new(10.0, 0.0), Vec2::new(5.0, 1.0)], (Vec2::new(5.0, 0.0), 5.0)),
+            // Collinear points, the outermost two are the diameter
+            (
+                vec![Vec2::new(3.0, 0.0), Vec2::new(0.0, 0.0), Vec2::new(7.0, 0.0), Vec2::new(1.0, 0.0)],
+                (Vec2::new(3.5, 0.0), 3.5),
+            ),
+        ];
+
+        for (points, (center, radius)) in cases {
+            for seed in 0..10 {
+                let circle = min_enclosing_circle(&points, &mut StdRng::seed_from_u64(seed));
+                assert!(circle.0.distance(center) <= 1e-5, "{:?}: {:?}", points, circle);
+                assert!((circle.1 - radius).abs() <= 1e-5, "{:?}: {:?}", points, circle);
+            }
+        }
+    }
+
+    #[test]
+    fn deduplicate_points_matches_the_linear_scan() {
+        // The candidates of the bridges used to be deduplicated by scanning them with `Vec::contains`
+        let linear_scan = |points: &[Vec2]| {
+            let mut candidates = Vec::new();
+            for point in points {
+                if !candidates.contains(point) {
+                    candidates.push(*point);
+                }
+            }
+            candidates
+        };
+
+        for (seed, extent) in (0..200).flat_map(|seed| EXTENTS.map(|extent| (seed, extent))) {
+            let mut points = cross_check_points(seed, extent);
+            // Signed zeros are equal, so only the first of them is kept
+            points.extend([Vec2::new(-0.0, 0.0), Vec2::new(0.0, -0.0), Vec2::ZERO]);
+            assert_eq!(deduplicate_points(&points), linear_scan(&points), "seed {} within {}", seed, extent);
+        }
+    }
+
+    #[test]
+    fn median_matches_sorted_copy() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut inputs = vec![vec![1, 2], vec![4, 1, 3, 2], vec![5, 5, 1, 1, 3, 3], vec![2; 10]];
+        for len in 1..100 {
+            inputs.push((0..len).map(|_| rng.gen_range(-1000..1000)).collect());
+            // Duplicate heavy
+            inputs.push((0..len).map(|_| rng.gen_range(0..3)).collect());
+        }
+
+        for nums in &inputs {
+            let mut sorted = nums.clone();
+            sorted.sort();
+            assert_eq!(median(nums), sorted[(nums.len() - 1) / 2], "{:?}", nums);
+
+            // The median of medians is only an approximation of larger inputs, but one of their elements
+            let approximate = median_of_medians(nums);
+            if nums.len() <= 5 {
+                assert_eq!(approximate, sorted[nums.len() / 2], "{:?}", nums);
+            } else {
+                assert!(nums.contains(&approximate), "{:?}", nums);
+            }
+        }
+    }
+
+    #[test]
+    fn median_is_linear_on_adversarial_inputs() {
+        let n: i64 = 100_000;
+        let inputs: [(&str, Vec<i64>); 5] = [
+            ("sorted", (0..n).collect()),
+            ("reversed", (0..n).rev().collect()),
+            ("organ pipe", (0..n).map(|i| i.min(n - i)).collect()),
+            ("few distinct", (0..n).map(|i| i % 3).collect()),
+            ("equal", vec![7; n as usize]),
+        ];
+
+        for (name, nums) in inputs {
+            let mut sorted = nums.clone();
+            sorted.sort();
+            let (value, _counts) = count_operations(|| median(&nums));
+            assert_eq!(value, sorted[(nums.len() - 1) / 2], "{}", name);
+            // Every partition discards at least 3/10 of its input and the pivot recursion takes a fifth of it
+            #[cfg(feature = "operation-counts")]
+            assert!(_counts.comparisons <= 10 * nums.len(), "{}: {} comparisons", name, _counts.comparisons);
+        }
+    }
+
+    #[test]
+    fn identical_points_give_a_single_vertex() {
+        let points = vec![Vec2::new(3.0, -4.0); 100];
+        for algorithm in EXACT_ALGORITHMS.into_iter().chain([AlgorithmType::Melkman, AlgorithmType::Approximate]) {
+            assert_eq!(compute_hull(points.clone(), algorithm, DEFAULT_ORIENTATION_EPSILON), vec![points[0]]);
+        }
+    }
+
+    #[test]
+    fn small_extent_square_keeps_its_corners() {
+        // Longitudes and latitudes of a small area, closer together than the largest epsilon of the inspector
+        let corners = [
+            Vec2::new(2.350, 48.850),
+            Vec2::new(2.351, 48.850),
+            Vec2::new(2.351, 48.851),
+            Vec2::new(2.350, 48.851),
+        ];
+        let mut points = corners.to_vec();
+        points.push(Vec2::new(2.3505, 48.8505));
+
+        for algorithm in EXACT_ALGORITHMS {
+            let hull = compute_hull(points.clone(), algorithm, 1e-2);
+            assert_eq!(hull.len(), 4, "{}: {:?}", algorithm.name(), hull);
+            for corner in corners {
+                assert!(hull.contains(&corner), "{}: {} not in {:?}", algorithm.name(), corner, hull);
+            }
+        }
+    }
+
+    #[test]
+    fn kirk_patrick_seidel_returns_on_ties() {
+        // Many points share few x coordinates, so most medians and bridge slopes tie
+        let mut points: Vec<Vec2> = (0..1600)
+            .map(|i| Vec2::new((i % 3) as f32, (i / 40 % 2) as f32 * 1000.0))
+            .collect();
+        points.extend((0..50).map(|i| Vec2::new(0.0, i as f32)));
+
+        let hull = compute_hull(points.clone(), AlgorithmType::KirkPatrickSeidel, DEFAULT_ORIENTATION_EPSILON);
+        let reference = compute_hull(points, AlgorithmType::JarvisMarch, DEFAULT_ORIENTATION_EPSILON);
+        assert_eq!(canonical(&hull), canonical(&reference));
+    }
+
+    #[test]
+    fn trace_hull_records_the_events_in_order() {
+        let (a, b, c) = (Vec2::new(0.0, 0.0), Vec2::new(4.0, 0.0), Vec2::new(0.0, 4.0));
+        let points = vec![a, b, c, Vec2::new(1.0, 1.0)];
+        let (hull, events) = trace_hull(points, AlgorithmType::KirkPatrickSeidel, DEFAULT_ORIENTATION_EPSILON);
+
+        assert_eq!(hull, vec![a, b, c]);
+        assert_eq!(
+            events,
+            vec![
+                HullEvent::MedianChosen { depth: 0, x: 1.0 },
+                HullEvent::BridgeFound { depth: 0, left: c, right: b },
+                HullEvent::Subproblem {
+                    depth: 0,
+                    upper: true,
+                    min: c,
+                    max: b,
+                    points: 3,
+                    bridge: Some((c, b)),
+                },
+                HullEvent::MedianChosen { depth: 0, x: 1.0 },
+                HullEvent::BridgeFound { depth: 0, left: a, right: b },
+                HullEvent::Subproblem {
+                    depth: 0,
+                    upper: false,
+                    min: a,
+                    max: b,
+                    points: 3,
+                    bridge: Some((a, b)),
+                },
+                HullEvent::EdgeAccepted(c, a),
+            ]
+        );
     }
 }
@@ -0,0 +1,211 @@
+//! Contains the benchmark window, which times the algorithms on the current points.
+//!
+//! Every selected algorithm is run a number of times without a drawing history, and the distribution
+//! of its timings is plotted with [egui_plot], either as box plots or as overlapping histograms.
+//! The samples are taken a few per frame, so the simulation stays responsive while the benchmark runs.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use egui_plot::{Bar, BarChart, BoxElem, BoxPlot, BoxSpread, Legend, Plot};
+use web_time::Instant;
+
+use convex_hull_simulation::algorithms::{compute_hull, AlgorithmType, OrientationEpsilon};
+
+use super::PointData;
+
+/// Number of bins the histograms split the range of the timings into
+const HISTOGRAM_BINS: usize = 20;
+
+/// Time spent taking samples per frame, the last sample of a frame may run over it
+const FRAME_BUDGET: Duration = Duration::from_millis(12);
+
+/// Resource to store the settings and the results of the benchmark window.
+///
+/// It contains data in the following order: # of samples per algorithm | the algorithms and whether they are timed | the timings in milliseconds of the last benchmark, per algorithm | plot histograms instead of box plots | the benchmark is still taking samples
+#[derive(Resource)]
+pub struct Benchmark(
+    pub usize,
+    pub Vec<(&'static str, AlgorithmType, bool)>,
+    pub Vec<(&'static str, AlgorithmType, Vec<f64>)>,
+    pub bool,
+    pub bool,
+);
+
+impl Default for Benchmark {
+    fn default() -> Self {
+        Self(
+            20,
+            vec![
+                ("Jarvis March", AlgorithmType::JarvisMarch, true),
+                ("Kirk Patrick Seidel", AlgorithmType::KirkPatrickSeidel, true),
+                ("Monotone Chain", AlgorithmType::MonotoneChain, false),
+                ("Quickhull", AlgorithmType::Quickhull, false),
+                ("Chan", AlgorithmType::Chan, false),
+                ("Divide and Conquer", AlgorithmType::DivideAndConquer, false),
+                ("Randomized Incremental", AlgorithmType::RandomizedIncremental, false),
+            ],
+            Vec::new(),
+            false,
+            false,
+        )
+    }
+}
+
+/// Shows the benchmark window, taking the samples of the running benchmark for up to [FRAME_BUDGET] every frame.
+pub fn benchmark_ui(
+    mut contexts: EguiContexts,
+    point_data: Res<PointData>,
+    orientation_epsilon: Res<OrientationEpsilon>,
+    mut benchmark: ResMut<Benchmark>,
+) {
+    if benchmark.4 {
+        take_samples(&point_data.0, orientation_epsilon.0, &mut benchmark);
+    }
+
+    egui::Window::new("Benchmark")
+        .default_open(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!("Times the algorithms on the current {} points", point_data.0.len()));
+            let running = benchmark.4;
+            ui.add_enabled(!running, egui::Slider::new(&mut benchmark.0, 1..=200).text("Samples"));
+            ui.add_enabled_ui(!running, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for (name, _, selected) in &mut benchmark.1 {
+                        ui.checkbox(selected, *name);
+                    }
+                });
+            });
+
+            if running {
+                let taken: usize = benchmark.2.iter().map(|(_, _, timings)| timings.len()).sum();
+                let total = benchmark.0 * benchmark.2.len();
+                ui.horizontal(|ui| {
+                    let progress = egui::ProgressBar::new(taken as f32 / total as f32);
+                    ui.add(progress.text(format!("{} / {} samples", taken, total)));
+                    if ui.button("Cancel").clicked() {
+                        benchmark.2.clear();
+                        benchmark.4 = false;
+                    }
+                });
+                return;
+            }
+
+            if ui.button("Run benchmark").clicked() {
+                let results: Vec<_> = benchmark
+                    .1
+                    .iter()
+                    .filter(|(_, _, selected)| *selected)
+                    .map(|(name, algorithm, _)| (*name, *algorithm, Vec::new()))
+                    .collect();
+                benchmark.4 = !results.is_empty();
+                benchmark.2 = results;
+                return;
+            }
+
+            if benchmark.2.is_empty() {
+                return;
+            }
+
+            ui.checkbox(&mut benchmark.3, "Plot histograms");
+            for (name, _, timings) in &benchmark.2 {
+                let sorted = sorted(timings);
+                ui.label(format!(
+                    "{}: median {:.3} ms, min {:.3} ms, max {:.3} ms",
+                    name,
+                    quantile(&sorted, 0.5),
+                    sorted[0],
+                    sorted[sorted.len() - 1]
+                ));
+            }
+
+            Plot::new("benchmark_plot")
+                .legend(Legend::default())
+                .height(240.0)
+                .show(ui, |plot_ui| {
+                    if benchmark.3 {
+                        for chart in histograms(&benchmark.2) {
+                            plot_ui.bar_chart(chart);
+                        }
+                    } else {
+                        for (index, (name, _, timings)) in benchmark.2.iter().enumerate() {
+                            plot_ui.box_plot(box_plot(name, index as f64, timings));
+                        }
+                    }
+                });
+        });
+}
+
+/// Takes the next samples of the running benchmark until the [FRAME_BUDGET] is used up, one algorithm after the other.
+///
+/// Each sample runs the algorithm on a copy of the points, its timing in milliseconds is added to the results.
+fn take_samples(points: &[Vec2], epsilon: f32, benchmark: &mut Benchmark) {
+    let frame = Instant::now();
+    let samples = benchmark.0;
+    while frame.elapsed() < FRAME_BUDGET {
+        let next = benchmark.2.iter_mut().find(|(_, _, timings)| timings.len() < samples);
+        let Some((_, algorithm, timings)) = next else {
+            benchmark.4 = false;
+            return;
+        };
+
+        let points = points.to_vec();
+        let start = Instant::now();
+        std::hint::black_box(compute_hull(points, *algorithm, epsilon));
+        timings.push(start.elapsed().as_secs_f64() * 1000.0);
+    }
+}
+
+/// Returns a sorted copy of the timings
+fn sorted(timings: &[f64]) -> Vec<f64> {
+    let mut sorted = timings.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+/// Returns the `q` quantile of the sorted timings, interpolating linearly between the two closest ones
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// Returns the box plot of the timings at `x`, its whiskers spanning all of them
+fn box_plot(name: &str, x: f64, timings: &[f64]) -> BoxPlot {
+    let sorted = sorted(timings);
+    let spread = BoxSpread::new(
+        sorted[0],
+        quantile(&sorted, 0.25),
+        quantile(&sorted, 0.5),
+        quantile(&sorted, 0.75),
+        sorted[sorted.len() - 1],
+    );
+    BoxPlot::new(vec![BoxElem::new(x, spread).name(name)]).name(name)
+}
+
+/// Returns a histogram of the timings of every algorithm, all of them sharing the same bins
+fn histograms(results: &[(&'static str, AlgorithmType, Vec<f64>)]) -> Vec<BarChart> {
+    let (min, max) = results
+        .iter()
+        .flat_map(|(_, _, timings)| timings)
+        .fold((f64::MAX, f64::MIN), |(min, max), t| (min.min(*t), max.max(*t)));
+    // Equal timings would make the bins empty, so they get a width anyway
+    let width = ((max - min) / HISTOGRAM_BINS as f64).max(1e-6);
+
+    results
+        .iter()
+        .map(|(name, _, timings)| {
+            let mut counts = [0; HISTOGRAM_BINS];
+            for t in timings {
+                counts[(((t - min) / width) as usize).min(HISTOGRAM_BINS - 1)] += 1;
+            }
+            let bars = counts
+                .iter()
+                .enumerate()
+                .map(|(bin, count)| Bar::new(min + (bin as f64 + 0.5) * width, *count as f64).width(width))
+                .collect();
+            BarChart::new(bars).name(name)
+        })
+        .collect()
+}
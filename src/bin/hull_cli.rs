@@ -0,0 +1,271 @@
+//! # Hull CLI
+//! Computes the convex hull of the points in a file without launching the simulation.
+//!
+//! ```text
+//! hull_cli [<input>] [--algorithm <name>] [--output <path>] [--events <path>] [--counts]
+//! hull_cli --cross-check <count> [--seed <start>]
+//! ```
+//!
+//! The algorithm is one of `jarvis`, `kps` (the default), `monotone`, `quickhull`, `chan`, `divide`,
+//! `incremental`, `melkman`, expecting the points in the order of a simple polyline, or `approximate`,
+//! which approximates the hull with the default number of strips.
+//! The input has one `x, y` pair per line, is a GeoJSON document of points or WKT points. The hull vertices are
+//! written as `x, y` pairs, to the output file if one is given and to stdout otherwise, except for outputs ending
+//! in `.geojson` or `.wkt`, which get the hull as a polygon of that format. Statistics are printed to stderr.
+//! Without an input, or with `-` as the input, the points are read from stdin, so the hull can be computed
+//! in a pipe like `generate_points | hull_cli | plot`.
+//! With `--events` the [HullEvent]s of the algorithm are also written to the given path as JSON, and with
+//! `--counts` the primitive operations of the algorithm are counted, if the `operation-counts` feature is
+//! enabled. Both slow the algorithm down, which the printed time includes.
+//!
+//! With `--cross-check` no input is read, instead `count` random point sets are generated from the
+//! consecutive seeds starting at `start` and the hulls found by all algorithms are compared. The seeds
+//! of the sets they disagree on are printed, so they can be reproduced with `--seed <seed> --cross-check 1`.
+
+use std::panic;
+use std::process::ExitCode;
+
+use convex_hull_simulation::{
+    algorithms::{
+        canonical_hull, compute_hull, count_operations, cross_check_points, trace_hull, AlgorithmType, HullEvent,
+        OperationCounts, DEFAULT_ORIENTATION_EPSILON,
+    },
+    export::{hull_to_text, HullFormat},
+    parsing::parse_input,
+};
+use web_time::Instant;
+
+const USAGE: &str = "Usage: hull_cli [<input>] [--algorithm <name>] [--output <path>] [--events <path>] [--counts]
+       hull_cli --cross-check <count> [--seed <start>]
+Algorithms: jarvis, kps (default), monotone, quickhull, chan, divide, incremental, melkman, approximate";
+
+/// Largest absolute coordinate of the points generated by the cross-check
+const CROSS_CHECK_EXTENT: i32 = 100;
+
+/// Largest distance between two vertices that are considered the same by the cross-check
+const CROSS_CHECK_TOLERANCE: f32 = 1e-3;
+
+/// Options given on the command line
+struct Options {
+    input: Option<String>,
+    algorithm: AlgorithmType,
+    output: Option<String>,
+    /// Path the events of the algorithm are written to
+    events: Option<String>,
+    /// Whether the operations of the algorithm are counted and printed
+    counts: bool,
+    /// Number of random point sets to compare the algorithms on, if set no input is read
+    cross_check: Option<u64>,
+    /// Seed of the first random point set of the cross-check
+    seed: u64,
+}
+
+/// Parses the command line arguments, excluding the program name
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut input = None;
+    let mut algorithm = AlgorithmType::KirkPatrickSeidel;
+    let mut output = None;
+    let mut events = None;
+    let mut counts = false;
+    let mut cross_check = None;
+    let mut seed = 0;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--algorithm" | "-a" => {
+                algorithm = args.next().ok_or("Missing value for --algorithm")?.parse()?;
+            }
+            "--output" | "-o" => {
+                output = Some(args.next().ok_or("Missing value for --output")?);
+            }
+            "--events" => {
+                events = Some(args.next().ok_or("Missing value for --events")?);
+            }
+            "--counts" => counts = true,
+            "--cross-check" => {
+                let value = args.next().ok_or("Missing value for --cross-check")?;
+                cross_check = Some(value.parse().map_err(|_| format!("Invalid count `{}`", value))?);
+            }
+            "--seed" => {
+                let value = args.next().ok_or("Missing value for --seed")?;
+                seed = value.parse().map_err(|_| format!("Invalid seed `{}`", value))?;
+            }
+            _ if arg.starts_with('-') && arg != "-" => return Err(format!("Unknown option `{}`", arg)),
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(format!("Unexpected argument `{}`", arg)),
+        }
+    }
+
+    Ok(Options {
+        input,
+        algorithm,
+        output,
+        events,
+        counts,
+        cross_check,
+        seed,
+    })
+}
+
+/// Writes the events to `path` as a JSON array
+fn write_events(path: &str, events: &[HullEvent]) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(events).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
+
+/// Compares the hulls found by all algorithms on the point set of the given seed.
+///
+/// Returns the reason they disagree, if they do. An algorithm panicking counts as a disagreement.
+fn compare_algorithms(seed: u64) -> Result<(), String> {
+    let points = cross_check_points(seed, CROSS_CHECK_EXTENT);
+
+    let mut hulls = Vec::new();
+    for (name, algorithm) in [
+        ("Jarvis March", AlgorithmType::JarvisMarch),
+        ("Kirk Patrick Seidel", AlgorithmType::KirkPatrickSeidel),
+        ("Monotone Chain", AlgorithmType::MonotoneChain),
+        ("Quickhull", AlgorithmType::Quickhull),
+        ("Chan", AlgorithmType::Chan),
+        ("Divide and Conquer", AlgorithmType::DivideAndConquer),
+        ("Randomized Incremental", AlgorithmType::RandomizedIncremental),
+    ] {
+        let input = points.clone();
+        match panic::catch_unwind(move || compute_hull(input, algorithm, DEFAULT_ORIENTATION_EPSILON)) {
+            Ok(hull) => hulls.push((name, canonical_hull(&hull, DEFAULT_ORIENTATION_EPSILON))),
+            Err(_) => return Err(format!("{} panicked", name)),
+        }
+    }
+
+    // Every hull is compared against the one of Jarvis March
+    let (reference_name, reference) = &hulls[0];
+    for (name, hull) in &hulls[1..] {
+        if reference.len() != hull.len() {
+            return Err(format!(
+                "{} points: {} found {} vertices, {} {}",
+                points.len(),
+                reference_name,
+                reference.len(),
+                name,
+                hull.len()
+            ));
+        }
+        if let Some((a, b)) = reference
+            .iter()
+            .zip(hull)
+            .find(|(a, b)| a.distance(**b) > CROSS_CHECK_TOLERANCE)
+        {
+            return Err(format!("{} points: vertex {} of {} differs from {}", points.len(), b, name, a));
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the cross-check over `count` seeds starting at `seed`, printing the ones that fail
+fn cross_check(count: u64, seed: u64) -> ExitCode {
+    let failures: Vec<u64> = (seed..seed.saturating_add(count))
+        .filter(|&seed| match compare_algorithms(seed) {
+            Ok(()) => false,
+            Err(reason) => {
+                eprintln!("Seed {} failed: {}", seed, reason);
+                true
+            }
+        })
+        .collect();
+
+    eprintln!("Point sets checked: {}", count);
+    eprintln!("Disagreements: {}", failures.len());
+
+    if failures.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Some(count) = options.cross_check {
+        return cross_check(count, options.seed);
+    }
+
+    let input = options.input.unwrap_or_else(|| "-".to_string());
+    let text = if input == "-" {
+        std::io::read_to_string(std::io::stdin())
+    } else {
+        std::fs::read_to_string(&input)
+    };
+    let text = match text {
+        Ok(text) => text,
+        Err(error) => {
+            eprintln!("Failed to read {}: {}", if input == "-" { "stdin" } else { &input }, error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (points, errors) = parse_input(&text);
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+    let input_count = points.len();
+
+    // The events are only recorded and the operations only counted when they are asked for
+    let run = || {
+        if options.events.is_some() {
+            trace_hull(points, options.algorithm, DEFAULT_ORIENTATION_EPSILON)
+        } else {
+            (compute_hull(points, options.algorithm, DEFAULT_ORIENTATION_EPSILON), Vec::new())
+        }
+    };
+    let start = Instant::now();
+    let ((hull, events), operations) = if options.counts {
+        count_operations(run)
+    } else {
+        (run(), OperationCounts::default())
+    };
+    let elapsed = start.elapsed();
+
+    if let Some(path) = &options.events {
+        if let Err(error) = write_events(path, &events) {
+            eprintln!("Failed to write {}: {}", path, error);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let format = match &options.output {
+        Some(path) if path.ends_with(".geojson") => HullFormat::GeoJson,
+        Some(path) if path.ends_with(".wkt") => HullFormat::Wkt,
+        _ => HullFormat::Csv,
+    };
+    let text = hull_to_text(&hull, None, format);
+    match &options.output {
+        Some(path) => {
+            if let Err(error) = std::fs::write(path, text) {
+                eprintln!("Failed to write {}: {}", path, error);
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{}", text),
+    }
+
+    eprintln!("Input points: {}", input_count);
+    eprintln!("Rejected lines: {}", errors.len());
+    eprintln!("Hull vertices: {}", hull.len());
+    eprintln!("Time taken: {:.3} ms", elapsed.as_secs_f64() * 1000.0);
+    if options.counts && cfg!(feature = "operation-counts") {
+        eprintln!("Orientation tests: {}", operations.orientation_tests);
+        eprintln!("Medians: {} with {} comparisons", operations.medians, operations.comparisons);
+        eprintln!("Bridge rounds: {} pruning {} candidates", operations.bridge_rounds, operations.pruned_candidates);
+    } else if options.counts {
+        eprintln!("Operations aren't counted without the `operation-counts` feature");
+    }
+
+    ExitCode::SUCCESS
+}
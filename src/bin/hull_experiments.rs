@@ -0,0 +1,171 @@
+//! # Hull Experiments
+//! Sweeps the algorithms over point sets of several sizes and distributions, and writes their timings as a CSV.
+//!
+//! ```text
+//! hull_experiments [--counts <list>] [--distributions <list>] [--algorithms <list>] [--runs <count>] [--seed <start>] [--output <path>]
+//! ```
+//!
+//! The lists are separated by commas. The counts default to `1000,10000,100000`, the distributions to `circle,square`,
+//! which are named like the `dist` query parameter of the web build, and the algorithms to all exact ones, which
+//! are named like the `--algorithm` of `hull_cli`.
+//!
+//! Every configuration is run `--runs` times (3 by default). Run `r` generates its points from the seed
+//! `start + r`, so all algorithms are timed on the same point sets and the sweep can be repeated exactly.
+//! Each run is one row of `distribution, points, algorithm, run, seed, time_ms, hull_vertices`, written to the
+//! output file if one is given and to stdout otherwise.
+//!
+//! The `profiling` crate benchmarks all algorithms with criterion, on a fixed input and on inputs with small and
+//! large hulls. This sweep instead times single runs on seeded point sets across sizes and distributions.
+//!
+//! The default `operation-counts` feature counts the operations of the algorithms, which skews the timings.
+//! Build the sweep without it, like the `profiling` crate does:
+//! ```text
+//! cargo run --release --no-default-features --bin hull_experiments -- <options>
+//! ```
+//! When the feature is enabled a warning is printed that the timings include the counting.
+
+use std::process::ExitCode;
+
+use convex_hull_simulation::{
+    algorithms::{compute_hull, AlgorithmType, DEFAULT_ORIENTATION_EPSILON},
+    distributions::{generate_points, Distribution, DistributionParameters, DistributionType},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use web_time::Instant;
+
+const USAGE: &str = "Usage: hull_experiments [--counts <list>] [--distributions <list>] [--algorithms <list>] [--runs <count>] [--seed <start>] [--output <path>]
+Distributions: fibonacci, circle (default), perimeter, square (default), clusters, ellipse, ellipse-perimeter, ring, parametric, poisson, halton, sobol, grid
+Algorithms: jarvis, kps, monotone, quickhull, chan, divide, incremental, melkman, approximate";
+
+/// Header of the CSV written by the sweep
+const CSV_HEADER: &str = "distribution, points, algorithm, run, seed, time_ms, hull_vertices";
+
+/// Options given on the command line
+struct Options {
+    counts: Vec<usize>,
+    distributions: Vec<DistributionType>,
+    algorithms: Vec<AlgorithmType>,
+    /// Number of times every configuration is run
+    runs: u64,
+    /// Seed of the points of the first run
+    seed: u64,
+    output: Option<String>,
+}
+
+/// Parses every entry of a comma separated list
+fn parse_list<T>(list: &str, parse: fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+    list.split(',').map(str::trim).filter(|name| !name.is_empty()).map(parse).collect()
+}
+
+/// Parses the command line arguments, excluding the program name
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        counts: vec![1000, 10_000, 100_000],
+        distributions: parse_list("circle,square", str::parse)?,
+        algorithms: parse_list("jarvis,kps,monotone,quickhull,chan,divide,incremental", str::parse)?,
+        runs: 3,
+        seed: 0,
+        output: None,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--counts" => {
+                options.counts = parse_list(&value()?, |count| {
+                    count.parse::<usize>().map_err(|_| format!("Invalid count `{}`", count))
+                })?;
+            }
+            "--distributions" => options.distributions = parse_list(&value()?, str::parse)?,
+            "--algorithms" => options.algorithms = parse_list(&value()?, str::parse)?,
+            "--runs" => {
+                let runs = value()?;
+                options.runs = runs.parse().map_err(|_| format!("Invalid number of runs `{}`", runs))?;
+            }
+            "--seed" => {
+                let seed = value()?;
+                options.seed = seed.parse().map_err(|_| format!("Invalid seed `{}`", seed))?;
+            }
+            "--output" | "-o" => options.output = Some(value()?),
+            _ => return Err(format!("Unknown option `{}`", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+/// Runs every configuration of the sweep, returning the rows of the CSV
+fn run_sweep(options: &Options) -> Result<Vec<String>, String> {
+    let mut rows = vec![CSV_HEADER.to_string()];
+
+    for distribution_type in &options.distributions {
+        let distribution = Distribution(*distribution_type, DistributionParameters::default());
+        for &count in &options.counts {
+            for run in 0..options.runs {
+                let seed = options.seed.wrapping_add(run);
+                let points = generate_points(&distribution, count, &mut StdRng::seed_from_u64(seed))?;
+
+                for algorithm in &options.algorithms {
+                    let start = Instant::now();
+                    let hull = compute_hull(points.clone(), *algorithm, DEFAULT_ORIENTATION_EPSILON);
+                    let elapsed = start.elapsed();
+
+                    rows.push(format!(
+                        "{}, {}, {}, {}, {}, {:.3}, {}",
+                        distribution_type.name(),
+                        points.len(),
+                        algorithm.name(),
+                        run,
+                        seed,
+                        elapsed.as_secs_f64() * 1000.0,
+                        hull.len()
+                    ));
+                    // The rows only reach stdout at the end, so they are echoed as progress when writing a file
+                    if options.output.is_some() {
+                        eprintln!("{}", rows.last().unwrap());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cfg!(feature = "operation-counts") {
+        eprintln!(
+            "The operations are counted, so the timings include the counting. \
+             Build with --no-default-features to time the algorithms alone"
+        );
+    }
+
+    let rows = match run_sweep(&options) {
+        Ok(rows) => rows,
+        Err(error) => {
+            eprintln!("Failed to generate the points: {}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let csv = rows.join("\n") + "\n";
+    match &options.output {
+        Some(path) => {
+            if let Err(error) = std::fs::write(path, csv) {
+                eprintln!("Failed to write {}: {}", path, error);
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{}", csv),
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,140 @@
+//! Contains the capturing of the rendered frames of the simulation.
+//!
+//! `graphics_drawing` sends a [StepDrawn] event whenever the scene of the drawing history changes. While a
+//! [GifRecording] is running, every such step is captured from the window, or every few steps of long histories,
+//! and the frames are encoded into an animated GIF once all of them arrived. Single frames can be saved as
+//! screenshots with [save_screenshot].
+
+use std::sync::{Arc, Mutex};
+
+use bevy::{prelude::*, render::view::screenshot::ScreenshotManager, window::PrimaryWindow};
+use image::{
+    imageops::{resize, FilterType},
+    Delay, Frame,
+};
+
+/// Event sent whenever a step of the drawing history is drawn.
+///
+/// It contains data in the following order: the index of the history that is drawn | how long the step is shown in seconds
+#[derive(Event)]
+pub struct StepDrawn(pub usize, pub f32);
+
+/// Largest number of frames of a GIF, longer histories only capture every few steps
+const MAX_GIF_FRAMES: usize = 300;
+
+/// Largest width of the frames of a GIF in pixels, larger windows are scaled down to it
+const MAX_GIF_WIDTH: u32 = 640;
+
+/// Resource to store the frames of the animation while it is recorded into a GIF.
+///
+/// There is a slot for every captured step of the history, which is filled once its screenshot is taken.
+/// The screenshots are taken asynchronously by the renderer, so the slots are shared with its callbacks.
+///
+/// It contains data in the following order: the slots of the frames | the number of steps per frame
+#[derive(Resource, Default)]
+pub struct GifRecording(pub Option<Arc<Mutex<Vec<Option<Frame>>>>>, pub usize);
+
+impl GifRecording {
+    /// Starts recording a history of the given number of steps, capturing at most [MAX_GIF_FRAMES] of them
+    pub fn start(&mut self, steps: usize) {
+        self.1 = steps.div_ceil(MAX_GIF_FRAMES).max(1);
+        self.0 = Some(Arc::new(Mutex::new((0..steps.div_ceil(self.1)).map(|_| None).collect())));
+    }
+
+    /// Returns the number of frames captured so far and the number of frames of the whole history
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        let frames = self.0.as_ref()?.lock().unwrap();
+        Some((frames.iter().flatten().count(), frames.len()))
+    }
+}
+
+/// Takes a screenshot of every captured step while a [GifRecording] is running.
+///
+/// The frames are scaled down to [MAX_GIF_WIDTH], and shown as long as all the steps they stand for together.
+pub fn capture_step_system(
+    mut steps: EventReader<StepDrawn>,
+    recording: Res<GifRecording>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    mut screenshots: ResMut<ScreenshotManager>,
+) {
+    let Some(frames) = &recording.0 else {
+        steps.clear();
+        return;
+    };
+    let stride = recording.1;
+
+    for StepDrawn(index, duration) in steps.read() {
+        // Index 0 is the empty scene before the first step
+        if *index == 0 || (index - 1) % stride != 0 || (index - 1) / stride >= frames.lock().unwrap().len() {
+            continue;
+        }
+
+        let (frames, step, slot, duration) = (frames.clone(), *index, (index - 1) / stride, *duration * stride as f32);
+        let result = screenshots.take_screenshot(window.single(), move |image| match image.try_into_dynamic() {
+            Ok(image) => {
+                let mut image = image.to_rgba8();
+                if image.width() > MAX_GIF_WIDTH {
+                    let height = (image.height() as u64 * MAX_GIF_WIDTH as u64 / image.width() as u64).max(1) as u32;
+                    image = resize(&image, MAX_GIF_WIDTH, height, FilterType::Triangle);
+                }
+                let delay = Delay::from_numer_denom_ms((duration * 1000.0).max(10.0) as u32, 1);
+                frames.lock().unwrap()[slot] = Some(Frame::from_parts(image, 0, 0, delay));
+            }
+            Err(e) => warn!("Failed to capture step {}: {}", step, e),
+        });
+        if let Err(e) = result {
+            warn!("Failed to capture step {}: {}", index, e);
+        }
+    }
+}
+
+/// Encodes the GIF once every frame of the [GifRecording] is captured, on a separate thread as it takes a while
+#[cfg(not(target_arch = "wasm32"))]
+pub fn finish_gif_system(mut recording: ResMut<GifRecording>) {
+    let complete = recording
+        .0
+        .as_ref()
+        .is_some_and(|frames| frames.lock().unwrap().iter().all(Option::is_some));
+    if !complete {
+        return;
+    }
+
+    let frames: Vec<Frame> = std::mem::take(&mut *recording.0.take().unwrap().lock().unwrap())
+        .into_iter()
+        .flatten()
+        .collect();
+    let path = format!("animation_{}.gif", timestamp());
+    std::thread::spawn(move || match write_gif(&path, frames) {
+        Ok(()) => info!("Recorded the animation to {}", path),
+        Err(e) => warn!("Failed to record the animation: {}", e),
+    });
+}
+
+/// Writes the frames into an animated GIF at `path`, which loops forever
+#[cfg(not(target_arch = "wasm32"))]
+fn write_gif(path: &str, frames: Vec<Frame>) -> Result<(), String> {
+    use image::codecs::gif::{GifEncoder, Repeat};
+
+    let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = GifEncoder::new(std::io::BufWriter::new(file));
+    encoder.set_repeat(Repeat::Infinite).map_err(|e| e.to_string())?;
+    encoder.encode_frames(frames).map_err(|e| e.to_string())
+}
+
+/// Returns the milliseconds since the Unix epoch, which keeps the names of the captured files unique
+pub fn timestamp() -> u128 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis())
+}
+
+/// Saves the next rendered frame of the window as a PNG named after the current [timestamp].
+///
+/// Native builds write the file next to the executable, on the web the browser downloads it.
+pub fn save_screenshot(screenshots: &mut ScreenshotManager, window: Entity) {
+    let path = format!("screenshot_{}.png", timestamp());
+    match screenshots.save_screenshot_to_disk(window, &path) {
+        Ok(()) => info!("Saving a screenshot to {}", path),
+        Err(e) => warn!("Failed to take a screenshot: {}", e),
+    }
+}
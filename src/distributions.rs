@@ -3,22 +3,174 @@
 //! The distributions are:
 //! - Fibonacci
 //! - Random
+//! - Clusters
+//! - Ellipse
+//! - Ring
+//! - Parametric
+//! - Poisson disk
+//! - Halton and Sobol sequences
+//! - Grid
 
 use bevy::prelude::*;
-use rand::{thread_rng, Rng};
+use evalexpr::{build_operator_tree, ContextWithMutableVariables, HashMapContext, Node, Value};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::parsing::MAX_COORDINATE;
 
 /// The different types of distributions that can be used to place the points
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum DistributionType {
     Fibonacci,
     CircleArea,
     CirclePerimeter,
     SquareArea,
+    Clusters,
+    EllipseArea,
+    EllipsePerimeter,
+    Ring,
+    Parametric,
+    PoissonDisk,
+    Halton,
+    Sobol,
+    Grid,
+}
+
+impl DistributionType {
+    /// Returns the name of the distribution on the command line and in the query parameters of the web build
+    pub fn name(self) -> &'static str {
+        match self {
+            DistributionType::Fibonacci => "fibonacci",
+            DistributionType::CircleArea => "circle",
+            DistributionType::CirclePerimeter => "perimeter",
+            DistributionType::SquareArea => "square",
+            DistributionType::Clusters => "clusters",
+            DistributionType::EllipseArea => "ellipse",
+            DistributionType::EllipsePerimeter => "ellipse-perimeter",
+            DistributionType::Ring => "ring",
+            DistributionType::Parametric => "parametric",
+            DistributionType::PoissonDisk => "poisson",
+            DistributionType::Halton => "halton",
+            DistributionType::Sobol => "sobol",
+            DistributionType::Grid => "grid",
+        }
+    }
+}
+
+impl std::str::FromStr for DistributionType {
+    type Err = String;
+
+    /// Parses the [name](DistributionType::name) of a distribution
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "fibonacci" => Ok(DistributionType::Fibonacci),
+            "circle" => Ok(DistributionType::CircleArea),
+            "perimeter" => Ok(DistributionType::CirclePerimeter),
+            "square" => Ok(DistributionType::SquareArea),
+            "clusters" => Ok(DistributionType::Clusters),
+            "ellipse" => Ok(DistributionType::EllipseArea),
+            "ellipse-perimeter" => Ok(DistributionType::EllipsePerimeter),
+            "ring" => Ok(DistributionType::Ring),
+            "parametric" => Ok(DistributionType::Parametric),
+            "poisson" => Ok(DistributionType::PoissonDisk),
+            "halton" => Ok(DistributionType::Halton),
+            "sobol" => Ok(DistributionType::Sobol),
+            "grid" => Ok(DistributionType::Grid),
+            other => Err(format!("Unknown distribution `{}`", other)),
+        }
+    }
+}
+
+/// The parameters of the distributions, each [DistributionType] only uses the ones that apply to it
+#[derive(Clone)]
+pub struct DistributionParameters {
+    /// Radius of the area the points are spread over, unless it
+    /// [scales with their count](DistributionParameters::scale_with_count)
+    pub radius: f32,
+    /// Whether the area grows with the number of points, to a radius of `100 * sqrt(count)` like the original
+    /// distributions, instead of having the fixed [DistributionParameters::radius]
+    pub scale_with_count: bool,
+    /// Number of clusters the points are grouped into
+    pub cluster_count: usize,
+    /// Standard deviation of the clusters, relative to [DistributionParameters::radius]
+    pub cluster_spread: f32,
+    /// Ratio of the width to the height of elliptical distributions
+    pub aspect_ratio: f32,
+    /// Counterclockwise rotation of elliptical distributions in degrees
+    pub ellipse_rotation: f32,
+    /// Inner radius of the ring distribution, the outer one being [DistributionParameters::radius]
+    pub inner_radius: f32,
+    /// Whether the fibonacci spiral is scaled to fit [DistributionParameters::radius], otherwise its
+    /// extent grows with the number of points
+    pub fit_fibonacci: bool,
+    /// Largest offset of the points of the grid from their lattice position, relative to its spacing, see [lattice]
+    pub grid_jitter: f32,
+    /// Bases of the x and y coordinates of the Halton sequence, see [halton]
+    pub halton_bases: (u32, u32),
+    /// Dimensions of the Sobol sequence used as the x and y coordinates, counted from 0, see [sobol]
+    pub sobol_dimensions: (usize, usize),
+    /// Expression of the x coordinate of the parametric curve, see [ParametricCurve]
+    pub x_expression: String,
+    /// Expression of the y coordinate of the parametric curve, see [ParametricCurve]
+    pub y_expression: String,
+}
+
+impl DistributionParameters {
+    /// Returns the x and y scale factors that give the ellipse its [DistributionParameters::aspect_ratio].
+    ///
+    /// The longer axis keeps the full radius, so the ellipse always fits within the circle of that radius.
+    pub fn ellipse_scales(&self) -> (f32, f32) {
+        if self.aspect_ratio >= 1.0 {
+            (1.0, 1.0 / self.aspect_ratio)
+        } else {
+            (self.aspect_ratio, 1.0)
+        }
+    }
+
+    /// Returns the radius of the area the given number of points are spread over
+    pub fn world_radius(&self, count: usize) -> f32 {
+        if self.scale_with_count {
+            100.0 * (count as f32 - 0.5).max(0.0).sqrt()
+        } else {
+            self.radius
+        }
+    }
+}
+
+impl Default for DistributionParameters {
+    fn default() -> Self {
+        Self {
+            radius: 1000.0,
+            scale_with_count: true,
+            cluster_count: 5,
+            cluster_spread: CLUSTER_SPREAD,
+            aspect_ratio: 0.5,
+            ellipse_rotation: 0.0,
+            inner_radius: 500.0,
+            fit_fibonacci: false,
+            grid_jitter: 0.0,
+            halton_bases: (2, 3),
+            sobol_dimensions: (0, 1),
+            x_expression: "1000 * math::cos(2 * pi * t)".to_string(),
+            y_expression: "500 * math::sin(4 * pi * t)".to_string(),
+        }
+    }
 }
 
-/// A resource that stores the current distribution type
+/// A resource that stores the current distribution type and its parameters
 #[derive(Resource)]
-pub struct Distribution(pub DistributionType);
+pub struct Distribution(pub DistributionType, pub DistributionParameters);
+
+/// Default standard deviation of the clusters generated by [clustered], relative to the distribution radius
+pub const CLUSTER_SPREAD: f32 = 0.1;
+
+/// Number of points per squared minimum distance that [poisson_disk] picks the minimum distance for.
+///
+/// Bridson's algorithm fills a little more, about 0.64 on large circles, so the requested number of points is reached.
+const POISSON_DENSITY: f32 = 0.62;
+
+/// Number of candidates [poisson_disk] tries around a point before it stops growing from it
+const POISSON_ATTEMPTS: usize = 30;
 
 /// Stores the value for [Golden Angle](https://en.wikipedia.org/wiki/Golden_angle).
 /// Used by [fibonacci_circle] function to generate points in a fibonacci spiral.
@@ -34,11 +186,21 @@ const GOLDEN_ANGLE: f32 = 2.3998277;
 /// - [Why this produces evenly distributed points](https://math.stackexchange.com/questions/1934101/why-does-a-golden-angle-based-spiral-produce-evenly-distributed-points)
 /// - [Extended to 3D](https://math.stackexchange.com/questions/3291489/can-the-fibonacci-lattice-be-extended-to-dimensions-higher-than-3)
 /// - [Going a step beyond in 3D](https://extremelearning.com.au/how-to-evenly-distribute-points-on-a-sphere-more-effectively-than-the-canonical-fibonacci-lattice/)
-pub fn fibonacci_circle(index: usize) -> (f32, f32) {
+///
+/// When `world_size` is given the spiral of `count` points is scaled so that its outermost point lies at
+/// that distance from the origin, otherwise the spacing of the points is fixed and the spiral grows with `count`.
+pub fn fibonacci_circle(index: usize, count: usize, world_size: Option<f32>) -> (f32, f32) {
     let index: f32 = (index as f32) - (index as f32) / 2.0;
 
     let angle = 2.0 * std::f32::consts::PI * index * (1.0 / GOLDEN_ANGLE);
-    let radius = 100.0 * (index - 0.5).sqrt();
+    let mut radius = 100.0 * (index - 0.5).sqrt();
+
+    if let Some(world_size) = world_size {
+        let outermost = 100.0 * (count as f32 / 2.0 - 0.5).sqrt();
+        if outermost > 0.0 {
+            radius *= world_size / outermost;
+        }
+    }
 
     let x = (angle.cos() * radius).round();
     let y = (angle.sin() * radius).round();
@@ -46,49 +208,543 @@ pub fn fibonacci_circle(index: usize) -> (f32, f32) {
     (x, y)
 }
 
-/// Generates a random point within a circle
+/// Generates a random point within a circle of the given radius
 /// 
 /// Utilizes rejection sampling on the square area distribution to get
 /// circular distribution.
-pub fn circle_area(num_shapes: usize) -> (f32, f32) {
-    let radius = 100.0 * (num_shapes as f32 - 0.5).sqrt();
-
+pub fn circle_area(rng: &mut impl Rng, radius: f32) -> (f32, f32) {
     loop {
-        let (x, y) = square_area(num_shapes);
+        let (x, y) = square_area(rng, radius);
         
-        if (x*x + y*y)/radius <= radius {
+        if x * x + y * y <= radius * radius {
             return (x, y);
         }
     }
 }
 
-/// Generates points on the perimeter of circle
+/// Returns the point rotated counterclockwise around the origin by `rotation` radians
+fn rotate((x, y): (f32, f32), rotation: f32) -> (f32, f32) {
+    let (sin, cos) = rotation.sin_cos();
+    (x * cos - y * sin, x * sin + y * cos)
+}
+
+/// Generates a random point within an ellipse, rotated counterclockwise by `rotation` radians
+///
+/// Samples a point with [circle_area] and scales its coordinates independently,
+/// which keeps the points uniformly distributed over the area of the ellipse.
+pub fn ellipse_area(rng: &mut impl Rng, radius: f32, x_scale: f32, y_scale: f32, rotation: f32) -> (f32, f32) {
+    let (x, y) = circle_area(rng, radius);
+    let (x, y) = rotate((x * x_scale, y * y_scale), rotation);
+
+    (x.round(), y.round())
+}
+
+/// Generates a random point on the perimeter of an ellipse, rotated counterclockwise by `rotation` radians
+///
+/// Scaling the points of [circle_perimeter] would crowd them at the ends of the longer axis, so the angle is
+/// drawn by rejection sampling with the speed of the parametrization, which spreads the points evenly along
+/// the perimeter.
+pub fn ellipse_perimeter(rng: &mut impl Rng, radius: f32, x_scale: f32, y_scale: f32, rotation: f32) -> (f32, f32) {
+    let (width, height) = (radius * x_scale, radius * y_scale);
+    let fastest = width.max(height);
+    let angle = loop {
+        let angle: f32 = rng.gen::<f32>() * 2.0 * std::f32::consts::PI;
+        let speed = (width * angle.sin()).hypot(height * angle.cos());
+        if rng.gen::<f32>() * fastest <= speed {
+            break angle;
+        }
+    };
+    let (x, y) = rotate((width * angle.cos(), height * angle.sin()), rotation);
+
+    (x.round(), y.round())
+}
+
+/// Generates points on the perimeter of circle of the given radius
 ///
 /// The points are generated in this fashion
 /// ![Example Image](https://mathworld.wolfram.com/images/eps-svg/CirclePointPicking_800.svg)
 ///
 /// Further reading
 /// - [Circle Point Picking](https://mathworld.wolfram.com/CirclePointPicking.html)
-pub fn circle_perimeter(num_shapes: usize) -> (f32, f32) {
-    let radius = 100.0 * (num_shapes as f32 - 0.5).sqrt();
-    let angle: f32 = rand::random::<f32>() * 2.0 * std::f32::consts::PI;
+pub fn circle_perimeter(rng: &mut impl Rng, radius: f32) -> (f32, f32) {
+    let angle: f32 = rng.gen::<f32>() * 2.0 * std::f32::consts::PI;
     let x = (angle.cos() * radius).round();
     let y = (angle.sin() * radius).round();
 
     (x, y)
 }
 
-/// Generates points inside a square
-pub fn square_area(num_shapes: usize) -> (f32, f32) {
-    let mut rng = thread_rng();
-    let side_length = 2_f32 * (100_f32 * (num_shapes as f32 - 0.5).sqrt());
+/// Generates a random point within the ring between `inner_radius` and `outer_radius`
+///
+/// The radius is sampled as $\sqrt{u (r_{outer}^2 - r_{inner}^2) + r_{inner}^2}$ for a uniform $u$,
+/// which keeps the points uniformly distributed over the area of the ring.
+pub fn annulus(rng: &mut impl Rng, inner_radius: f32, outer_radius: f32) -> (f32, f32) {
+    let u: f32 = rng.gen();
+    let angle: f32 = rng.gen::<f32>() * 2.0 * std::f32::consts::PI;
+
+    let inner_squared = inner_radius * inner_radius;
+    let radius = (u * (outer_radius * outer_radius - inner_squared) + inner_squared).sqrt();
+    let x = (angle.cos() * radius).round();
+    let y = (angle.sin() * radius).round();
+
+    (x, y)
+}
 
+/// Generates points inside a square, the radius being half of the side length, or the origin when the radius
+/// isn't positive
+pub fn square_area(rng: &mut impl Rng, radius: f32) -> (f32, f32) {
+    if radius <= 0.0 {
+        return (0.0, 0.0);
+    }
     let x: f32 = rng
-        .gen_range(-(side_length/2.0)..(side_length/2.0))
+        .gen_range(-radius..radius)
         .round();
     let y: f32 = rng
-        .gen_range(-(side_length/2.0)..(side_length/2.0))
+        .gen_range(-radius..radius)
         .round();
 
     (x, y)
 }
+
+/// Picks the centers of the clusters used by [clustered], uniformly within the square of the given radius.
+///
+/// At least one center is picked, so that [clustered] always has a cluster to place the points in.
+pub fn cluster_centers(rng: &mut impl Rng, cluster_count: usize, radius: f32) -> Vec<(f32, f32)> {
+    (0..cluster_count.max(1)).map(|_| square_area(rng, radius)).collect()
+}
+
+/// Generates a point around one of the given cluster centers
+///
+/// The center is chosen at random, or is the origin when there are none, and the point is sampled from a Gaussian
+/// with standard deviation `spread` around it, using the
+/// [Box-Muller transform](https://en.wikipedia.org/wiki/Box%E2%80%93Muller_transform).
+pub fn clustered(rng: &mut impl Rng, centers: &[(f32, f32)], spread: f32) -> (f32, f32) {
+    let (center_x, center_y) = if centers.is_empty() {
+        (0.0, 0.0)
+    } else {
+        centers[rng.gen_range(0..centers.len())]
+    };
+
+    // `1.0 - u` lies in (0, 1], which keeps the logarithm finite
+    let u: f32 = 1.0 - rng.gen::<f32>();
+    let v: f32 = rng.gen();
+    let magnitude = spread * (-2.0 * u.ln()).sqrt();
+    let angle = 2.0 * std::f32::consts::PI * v;
+
+    let x = (center_x + magnitude * angle.cos()).round();
+    let y = (center_y + magnitude * angle.sin()).round();
+
+    (x, y)
+}
+
+/// A curve whose coordinates are given by expressions of the parameter `t`, parsed with [evalexpr].
+///
+/// Besides `t` the expressions can use the constant `pi` and the functions of evalexpr,
+/// such as `math::sin`, `math::cos` and `math::sqrt`.
+pub struct ParametricCurve {
+    x: Node,
+    y: Node,
+}
+
+impl ParametricCurve {
+    /// Parses the expressions of the x and y coordinates
+    pub fn new(x_expression: &str, y_expression: &str) -> Result<Self, String> {
+        let parse = |name: &str, expression: &str| {
+            build_operator_tree(expression).map_err(|e| format!("Invalid {}(t): {}", name, e))
+        };
+
+        Ok(Self {
+            x: parse("x", x_expression)?,
+            y: parse("y", y_expression)?,
+        })
+    }
+
+    /// Evaluates the coordinates of the curve at `t`
+    pub fn evaluate(&self, t: f32) -> Result<(f32, f32), String> {
+        let mut context = HashMapContext::new();
+        context
+            .set_value("t".to_string(), Value::Float(t as f64))
+            .and_then(|_| context.set_value("pi".to_string(), Value::Float(std::f64::consts::PI)))
+            .map_err(|e| e.to_string())?;
+
+        let evaluate = |name: &str, node: &Node| {
+            node.eval_number_with_context(&context)
+                .map_err(|e| format!("Failed to evaluate {}(t) at t = {}: {}", name, t, e))
+        };
+
+        Ok((evaluate("x", &self.x)? as f32, evaluate("y", &self.y)? as f32))
+    }
+}
+
+/// Generates the point at the index of a square lattice of `count` points within the square of the given radius,
+/// filled row by row from the bottom.
+///
+/// The spacing is rounded down to a whole number, but kept at least 1 even if the lattice outgrows the square, so
+/// without jitter the rows, columns and diagonals stay exactly collinear. Otherwise both coordinates are offset by
+/// up to `jitter` times the spacing.
+pub fn lattice(rng: &mut impl Rng, index: usize, count: usize, radius: f32, jitter: f32) -> (f32, f32) {
+    let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+    let spacing = if columns > 1 {
+        (2.0 * radius / (columns - 1) as f32).floor().max(1.0)
+    } else {
+        0.0
+    };
+    let start = -(spacing * (columns - 1) as f32 / 2.0).round();
+    let (column, row) = (index % columns, index / columns);
+    let (mut x, mut y) = (start + column as f32 * spacing, start + row as f32 * spacing);
+
+    if jitter > 0.0 && spacing > 0.0 {
+        let offset = jitter * spacing;
+        x = (x + rng.gen_range(-offset..=offset)).round();
+        y = (y + rng.gen_range(-offset..=offset)).round();
+    }
+
+    (x, y)
+}
+
+/// Direction numbers of the Sobol sequence by [Joe and Kuo](https://web.maths.unsw.edu.au/~fkuo/sobol/) for the
+/// dimensions after the first: the degree of the primitive polynomial, its inner coefficients as bits and the
+/// initial direction numbers
+const SOBOL_POLYNOMIALS: [(usize, u32, &[u32]); 7] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+];
+
+/// Number of dimensions of the Sobol sequence that [sobol_directions] supports
+pub const SOBOL_DIMENSIONS: usize = SOBOL_POLYNOMIALS.len() + 1;
+
+/// Returns the radical inverse of the index in the given base, which mirrors its digits around the decimal point
+pub fn radical_inverse(mut index: u64, base: u32) -> f64 {
+    let base = base.max(2) as u64;
+    let mut inverse = 0.0;
+    let mut digit_value = 1.0 / base as f64;
+    while index > 0 {
+        inverse += (index % base) as f64 * digit_value;
+        index /= base;
+        digit_value /= base as f64;
+    }
+    inverse
+}
+
+/// Generates the point at the index of the [Halton sequence](https://en.wikipedia.org/wiki/Halton_sequence)
+/// within the square of the given radius.
+///
+/// The coordinates are the radical inverses in the two bases, which cover the square evenly when they are coprime.
+pub fn halton(index: u64, bases: (u32, u32), radius: f32) -> (f32, f32) {
+    let x = (2.0 * radical_inverse(index, bases.0) as f32 - 1.0) * radius;
+    let y = (2.0 * radical_inverse(index, bases.1) as f32 - 1.0) * radius;
+
+    (x.round(), y.round())
+}
+
+/// Returns whether the bases share no common factor, which [halton] needs to spread the points over the square
+/// rather than onto a few lines.
+pub fn coprime(mut a: u32, mut b: u32) -> bool {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a == 1
+}
+
+/// Returns the 32 direction numbers of the dimension of the Sobol sequence, which is clamped to [SOBOL_DIMENSIONS].
+///
+/// The first dimension is the van der Corput sequence in base 2, the others follow the recurrence of
+/// [Bratley and Fox](https://doi.org/10.1145/42288.214372) from the initial numbers of their polynomial.
+pub fn sobol_directions(dimension: usize) -> [u32; 32] {
+    let mut directions = [0; 32];
+    if dimension == 0 {
+        for (k, direction) in directions.iter_mut().enumerate() {
+            *direction = 1 << (31 - k);
+        }
+        return directions;
+    }
+
+    let (degree, coefficients, initial) = SOBOL_POLYNOMIALS[(dimension - 1).min(SOBOL_POLYNOMIALS.len() - 1)];
+    for k in 0..32 {
+        directions[k] = if k < degree {
+            initial[k] << (31 - k)
+        } else {
+            let mut direction = directions[k - degree] ^ (directions[k - degree] >> degree);
+            for j in 1..degree {
+                if (coefficients >> (degree - 1 - j)) & 1 == 1 {
+                    direction ^= directions[k - j];
+                }
+            }
+            direction
+        };
+    }
+    directions
+}
+
+/// Generates the point at the index of the [Sobol sequence](https://en.wikipedia.org/wiki/Sobol_sequence) within
+/// the square of the given radius, from the direction numbers of its two dimensions.
+///
+/// Every coordinate is the exclusive or of the direction numbers of the set bits of the index.
+pub fn sobol(index: u64, directions: &[[u32; 32]; 2], radius: f32) -> (f32, f32) {
+    let coordinate = |directions: &[u32; 32]| {
+        let bits = (0..32).filter(|bit| (index >> bit) & 1 == 1);
+        let value = bits.fold(0, |value, bit| value ^ directions[bit]);
+        (2.0 * (value as f64 / 4_294_967_296.0) as f32 - 1.0) * radius
+    };
+    let (x, y) = (coordinate(&directions[0]), coordinate(&directions[1]));
+
+    (x.round(), y.round())
+}
+
+/// Generates a point on the curve at a uniformly random `t` in $[0, 1]$
+pub fn parametric(rng: &mut impl Rng, curve: &ParametricCurve) -> Result<(f32, f32), String> {
+    let (x, y) = curve.evaluate(rng.gen())?;
+
+    Ok((x.round(), y.round()))
+}
+
+/// Generates up to `count` evenly spaced points within a circle of the given radius with
+/// [Bridson's algorithm](https://www.cs.ubc.ca/~rbridson/docs/bridson-siggraph07-poissondisk.pdf).
+///
+/// No two points are closer than a minimum distance, chosen from [POISSON_DENSITY] so that a little more than
+/// `count` points would fill the circle. Starting from a random point, new points are tried in the ring between
+/// one and two minimum distances around a random active point, which is retired once [POISSON_ATTEMPTS] candidates
+/// failed. A background grid with cells small enough to hold at most one point keeps every check to the
+/// neighboring cells.
+pub fn poisson_disk(rng: &mut impl Rng, count: usize, radius: f32) -> Vec<(f32, f32)> {
+    if count == 0 || radius <= 0.0 {
+        return Vec::new();
+    }
+
+    let distance = (POISSON_DENSITY * std::f32::consts::PI * radius * radius / count as f32).sqrt();
+    let cell = distance / std::f32::consts::SQRT_2;
+    let size = (2.0 * radius / cell).ceil() as usize + 1;
+    let cell_of = |(x, y): (f32, f32)| (((x + radius) / cell) as usize, ((y + radius) / cell) as usize);
+    let mut grid: Vec<Option<usize>> = vec![None; size * size];
+
+    let first = circle_area(rng, radius);
+    let (column, row) = cell_of(first);
+    grid[row * size + column] = Some(0);
+    let mut points = vec![first];
+    let mut active = vec![0];
+
+    while !active.is_empty() && points.len() < count {
+        let slot = rng.gen_range(0..active.len());
+        let (center_x, center_y) = points[active[slot]];
+
+        let candidate = (0..POISSON_ATTEMPTS)
+            .map(|_| {
+                let angle = rng.gen::<f32>() * 2.0 * std::f32::consts::PI;
+                let offset = distance * (1.0 + rng.gen::<f32>());
+                (center_x + offset * angle.cos(), center_y + offset * angle.sin())
+            })
+            .find(|&(x, y)| {
+                if x * x + y * y > radius * radius {
+                    return false;
+                }
+                // Points closer than the distance lie at most two cells away
+                let (column, row) = cell_of((x, y));
+                (row.saturating_sub(2)..(row + 3).min(size)).all(|row| {
+                    (column.saturating_sub(2)..(column + 3).min(size)).all(|column| {
+                        let Some(index) = grid[row * size + column] else {
+                            return true;
+                        };
+                        let (other_x, other_y) = points[index];
+                        (x - other_x).powi(2) + (y - other_y).powi(2) >= distance * distance
+                    })
+                })
+            });
+
+        match candidate {
+            Some(candidate) => {
+                let (column, row) = cell_of(candidate);
+                grid[row * size + column] = Some(points.len());
+                active.push(points.len());
+                points.push(candidate);
+            }
+            None => {
+                active.swap_remove(slot);
+            }
+        }
+    }
+
+    points.into_iter().map(|(x, y)| (x.round(), y.round())).collect()
+}
+
+/// Generates `count` points from the given distribution, drawing the random numbers from `rng`.
+///
+/// Only the [DistributionType::Parametric] distribution can fail, when its expressions can't be evaluated. Its
+/// non-finite points are skipped, so it may give fewer than `count` points, and its coordinates are clamped to
+/// [MAX_COORDINATE].
+pub fn generate_points(distribution: &Distribution, count: usize, rng: &mut impl Rng) -> Result<Vec<Vec2>, String> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let parameters = &distribution.1;
+    let radius = parameters.world_radius(count);
+    // The points of a Poisson disk depend on each other, so they are generated all at once
+    if distribution.0 == DistributionType::PoissonDisk {
+        let points = poisson_disk(rng, count, radius);
+        return Ok(points.into_iter().map(|(x, y)| Vec2::new(x, y)).collect());
+    }
+
+    // Only the distributions that need them draw the centers, so the others give the same points for the same seed
+    let centers = match distribution.0 {
+        DistributionType::Clusters => cluster_centers(rng, parameters.cluster_count, radius),
+        _ => Vec::new(),
+    };
+    let curve = match distribution.0 {
+        DistributionType::Parametric => {
+            Some(ParametricCurve::new(&parameters.x_expression, &parameters.y_expression)?)
+        }
+        _ => None,
+    };
+    let (x_dimension, y_dimension) = parameters.sobol_dimensions;
+    let directions = [sobol_directions(x_dimension), sobol_directions(y_dimension)];
+
+    let mut points = Vec::with_capacity(count);
+    for i in 0..count {
+        let (x, y) = match distribution.0 {
+            DistributionType::Fibonacci => {
+                let world_size = parameters.fit_fibonacci.then_some(radius);
+                fibonacci_circle(i + 1, count, world_size)
+            }
+            DistributionType::CircleArea => circle_area(rng, radius),
+            DistributionType::CirclePerimeter => circle_perimeter(rng, radius),
+            DistributionType::SquareArea => square_area(rng, radius),
+            DistributionType::EllipseArea => {
+                let (x_scale, y_scale) = parameters.ellipse_scales();
+                ellipse_area(rng, radius, x_scale, y_scale, parameters.ellipse_rotation.to_radians())
+            }
+            DistributionType::EllipsePerimeter => {
+                let (x_scale, y_scale) = parameters.ellipse_scales();
+                let rotation = parameters.ellipse_rotation.to_radians();
+                ellipse_perimeter(rng, radius, x_scale, y_scale, rotation)
+            }
+            DistributionType::Ring => {
+                let inner_radius = parameters.inner_radius.min(radius);
+                annulus(rng, inner_radius, radius)
+            }
+            DistributionType::Clusters => clustered(rng, &centers, radius * parameters.cluster_spread),
+            DistributionType::Parametric => parametric(rng, curve.as_ref().unwrap())?,
+            DistributionType::Grid => lattice(rng, i, count, radius, parameters.grid_jitter),
+            // Both sequences start at the corner of the square, which is skipped
+            DistributionType::Halton => halton(i as u64 + 1, parameters.halton_bases, radius),
+            DistributionType::Sobol => sobol(i as u64 + 1, &directions, radius),
+            DistributionType::PoissonDisk => unreachable!("Poisson disks are generated before the loop"),
+        };
+
+        // A parametric curve can evaluate to NaN or infinity, like `1 / t` at `t = 0`, which is dropped, and to
+        // coordinates beyond those of parsed points, which are clamped
+        if x.is_finite() && y.is_finite() {
+            points.push(Vec2::new(x, y).clamp(Vec2::splat(-MAX_COORDINATE), Vec2::splat(MAX_COORDINATE)));
+        }
+    }
+
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn degenerate_parameters_do_not_panic() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(square_area(&mut rng, 0.0), (0.0, 0.0));
+        assert_eq!(circle_area(&mut rng, 0.0), (0.0, 0.0));
+        assert_eq!(cluster_centers(&mut rng, 0, 100.0).len(), 1);
+        assert_eq!(clustered(&mut rng, &[], 0.0), (0.0, 0.0));
+
+        for distribution in [DistributionType::SquareArea, DistributionType::CircleArea, DistributionType::Clusters] {
+            let parameters = DistributionParameters {
+                radius: 0.0,
+                scale_with_count: false,
+                cluster_count: 0,
+                ..default()
+            };
+            let points = generate_points(&Distribution(distribution, parameters), 10, &mut rng).unwrap();
+            assert_eq!(points, vec![Vec2::ZERO; 10], "{}", distribution.name());
+        }
+    }
+
+    #[test]
+    fn halton_starts_with_the_radical_inverses() {
+        let base_2 = [0.5, 0.25, 0.75, 0.125];
+        let base_3 = [1.0 / 3.0, 2.0 / 3.0, 1.0 / 9.0, 4.0 / 9.0];
+        for index in 1..=4 {
+            assert!((radical_inverse(index, 2) - base_2[index as usize - 1]).abs() < 1e-12, "{}", index);
+            assert!((radical_inverse(index, 3) - base_3[index as usize - 1]).abs() < 1e-12, "{}", index);
+        }
+
+        assert_eq!(halton(1, (2, 3), 900.0), (0.0, -300.0));
+        assert_eq!(halton(2, (2, 3), 900.0), (-450.0, 300.0));
+    }
+
+    #[test]
+    fn sobol_starts_with_the_known_points() {
+        let directions = [sobol_directions(0), sobol_directions(1)];
+        let expected = [(0.5, 0.5), (0.25, 0.75), (0.75, 0.25), (0.125, 0.625), (0.625, 0.125)];
+        for (index, (x, y)) in (1..).zip(expected) {
+            let point = ((2.0 * x - 1.0) * 1000.0, (2.0 * y - 1.0) * 1000.0);
+            assert_eq!(sobol(index, &directions, 1000.0), point, "{}", index);
+        }
+    }
+
+    #[test]
+    fn poisson_disk_keeps_the_minimum_distance() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (count, radius) = (200, 1000.0);
+        let points = poisson_disk(&mut rng, count, radius);
+        assert!(points.len() > count * 9 / 10 && points.len() <= count, "{}", points.len());
+
+        // The coordinates are rounded, which moves two points closer by at most the diagonal of a unit square
+        let distance = (POISSON_DENSITY * std::f32::consts::PI * radius * radius / count as f32).sqrt();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            assert!(x.hypot(y) <= radius + 1.0, "({}, {})", x, y);
+            for &(other_x, other_y) in &points[i + 1..] {
+                let spacing = (x - other_x).hypot(y - other_y);
+                assert!(spacing >= distance - std::f32::consts::SQRT_2, "{} < {}", spacing, distance);
+            }
+        }
+    }
+
+    #[test]
+    fn annulus_stays_between_its_radii() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            let (x, y) = annulus(&mut rng, 500.0, 1000.0);
+            let distance = x.hypot(y);
+            assert!((499.0..=1001.0).contains(&distance), "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn ellipse_perimeter_stays_on_the_ellipse() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let rotation = 30.0_f32.to_radians();
+        for _ in 0..1000 {
+            let (x, y) = rotate(ellipse_perimeter(&mut rng, 1000.0, 1.0, 0.5, rotation), -rotation);
+            let value = (x / 1000.0).powi(2) + (y / 500.0).powi(2);
+            assert!((value - 1.0).abs() < 0.01, "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn lattice_fills_the_rows_from_the_bottom() {
+        let mut rng = StdRng::seed_from_u64(0);
+        assert_eq!(lattice(&mut rng, 0, 9, 100.0, 0.0), (-100.0, -100.0));
+        assert_eq!(lattice(&mut rng, 1, 9, 100.0, 0.0), (0.0, -100.0));
+        assert_eq!(lattice(&mut rng, 4, 9, 100.0, 0.0), (0.0, 0.0));
+        assert_eq!(lattice(&mut rng, 8, 9, 100.0, 0.0), (100.0, 100.0));
+
+        // The jitter moves the points by at most the given fraction of the spacing
+        for index in 0..9 {
+            let (x, y) = lattice(&mut rng, index, 9, 100.0, 0.25);
+            let (column, row) = ((index % 3) as f32 * 100.0 - 100.0, (index / 3) as f32 * 100.0 - 100.0);
+            assert!((x - column).abs() <= 25.0 && (y - row).abs() <= 25.0, "({}, {})", x, y);
+        }
+    }
+}
@@ -0,0 +1,484 @@
+//! Contains the export of the animation frames to SVG, and of the computed hulls to CSV and JSON.
+//!
+//! A frame consists of the hull edges accumulated up to a step of the [DrawingHistory](crate::algorithms::DrawingHistory),
+//! along with the temporary primitives of that step. It is exported together with the points it was computed from,
+//! and the colors match the ones used by the simulation.
+
+use bevy::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::algorithms::{HighlightKind, LineType};
+
+/// Background color of the exported SVG, which matches the default clear color of the simulation
+const BACKGROUND: &str = "#666666";
+
+/// Number of distinct colors of the [LineType::LayerEdge], deeper layers cycle through them again
+pub const LAYER_COLORS: usize = 8;
+
+/// Radius of the halo ring of a [LineType::HighlightPoint], as a multiple of the radius of the points
+pub const HALO_RADIUS: f32 = 2.5;
+
+/// Returns the color of the edges of the convex layer with the given index
+pub fn layer_color(layer: usize) -> Color {
+    Color::hsl(360.0 * (layer % LAYER_COLORS) as f32 / LAYER_COLORS as f32, 0.8, 0.6)
+}
+
+/// Returns the color of the halo ring of a [LineType::HighlightPoint] with the given kind
+pub fn highlight_color(kind: HighlightKind) -> Color {
+    match kind {
+        HighlightKind::Current => Color::rgb(1.0, 1.0, 0.3),
+        HighlightKind::Candidate => Color::rgb(0.2, 0.6, 1.0),
+        HighlightKind::Rejected => Color::rgb(1.0, 0.25, 0.25),
+        HighlightKind::Confirmed => Color::rgb(0.3, 1.0, 0.3),
+    }
+}
+
+/// Returns the primitives on the screen once the first `index` steps of the history are drawn.
+///
+/// The hull edges, hull vertices, layer edges and discarded points of every step are kept until a
+/// [LineType::ClearScreen], while the other primitives are only shown for the step that drew them.
+pub fn current_frame(history: &[Vec<LineType>], index: usize) -> Vec<&LineType> {
+    let index = index.min(history.len());
+    let mut frame = Vec::new();
+
+    for (i, step) in history[..index].iter().enumerate() {
+        if step.iter().any(|line| matches!(line, LineType::ClearScreen)) {
+            frame.clear();
+        }
+
+        let last = i + 1 == index;
+        frame.extend(
+            step.iter()
+                .filter(|line| last || is_persistent(line))
+                .filter(|line| !matches!(line, LineType::ClearScreen)),
+        );
+    }
+
+    frame
+}
+
+/// Returns the milestones of the history along with the index that shows them, which is the one after their step.
+///
+/// See [LineType::Milestone], the milestones are in the order of the history.
+pub fn milestones(history: &[Vec<LineType>]) -> Vec<(usize, &str)> {
+    history
+        .iter()
+        .enumerate()
+        .flat_map(|(i, step)| {
+            step.iter().filter_map(move |line| match line {
+                LineType::Milestone(name) => Some((i + 1, name.as_str())),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+/// Whether the primitive stays on the screen after the step that drew it, see [current_frame]
+fn is_persistent(line: &LineType) -> bool {
+    matches!(
+        line,
+        LineType::PartOfHull(..) | LineType::LayerEdge(..) | LineType::HullVertex(_) | LineType::DiscardedPoint(_)
+    )
+}
+
+/// Serializes the primitives of a frame and the points with their colors into an SVG document.
+///
+/// The view box fits all the points and primitives, with the y axis flipped so the image looks like the simulation.
+/// The points are drawn below the primitives, and the hull edges are joined into paths wherever they are connected.
+/// Vertical lines span the whole height of the image and text comments are placed at its bottom left.
+pub fn frame_to_svg(frame: &[&LineType], points: &[(Vec2, Color)], point_radius: f32) -> String {
+    let (min, max) = frame_bounds(frame, points, point_radius);
+    let margin = ((max - min).max_element() * 0.05).max(point_radius * 2.0);
+    let (min, max) = (min - Vec2::splat(margin), max + Vec2::splat(margin));
+    let size = max - min;
+    let font_size = size.y * 0.03;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+        min.x, -max.y, size.x, size.y
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+        min.x, -max.y, size.x, size.y, BACKGROUND
+    );
+
+    for (point, color) in points {
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            point.x,
+            -point.y,
+            point_radius,
+            hex_color(*color)
+        );
+    }
+
+    let mut comments = Vec::new();
+    let mut hull_edges = Vec::new();
+    for line in frame {
+        match line {
+            LineType::PartOfHull(a, b) => hull_edges.push((*a, *b)),
+            LineType::Temporary(a, b) => write_line(&mut svg, *a, *b, "#707070"),
+            LineType::Diameter(a, b) => write_line(&mut svg, *a, *b, "#33ff66"),
+            LineType::ClosingEdge(a, b) => write_line(&mut svg, *a, *b, "#ffe633"),
+            LineType::Tangent(a, b) => write_line(&mut svg, *a, *b, "#33e6ff"),
+            LineType::LayerEdge(a, b, layer) => write_line(&mut svg, *a, *b, &hex_color(layer_color(*layer))),
+            LineType::VerticalLine(x, _) => {
+                write_line(&mut svg, Vec2::new(*x, min.y), Vec2::new(*x, max.y), "#ff0000")
+            }
+            LineType::CollinearPoint(point) => {
+                let _ = writeln!(
+                    svg,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="#ff9900"/>"##,
+                    point.x,
+                    -point.y,
+                    point_radius * 1.5
+                );
+            }
+            LineType::HighlightPoint(point, kind) => {
+                let _ = writeln!(
+                    svg,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{}"/>"##,
+                    point.x,
+                    -point.y,
+                    point_radius * HALO_RADIUS,
+                    hex_color(highlight_color(*kind)),
+                    point_radius * 0.5
+                );
+            }
+            LineType::DiscardedPoint(point) => {
+                let _ = writeln!(
+                    svg,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="#595959"/>"##,
+                    point.x, -point.y, point_radius
+                );
+            }
+            LineType::HullVertex(point) => {
+                let _ = writeln!(
+                    svg,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="#ff66b3"/>"##,
+                    point.x,
+                    -point.y,
+                    point_radius * 2.0
+                );
+            }
+            LineType::FilledHull(vertices) => {
+                let vertices: Vec<_> = vertices.iter().map(|vertex| format!("{},{}", vertex.x, -vertex.y)).collect();
+                let _ = writeln!(
+                    svg,
+                    r##"<polygon points="{}" fill="#ffffff" fill-opacity="0.15"/>"##,
+                    vertices.join(" ")
+                );
+            }
+            LineType::Polygon(corners, layer) => {
+                let corners: Vec<_> = corners.iter().map(|corner| format!("{},{}", corner.x, -corner.y)).collect();
+                let _ = writeln!(
+                    svg,
+                    r##"<polygon points="{}" fill="{}" fill-opacity="0.2"/>"##,
+                    corners.join(" "),
+                    hex_color(layer_color(*layer))
+                );
+            }
+            LineType::Region(a, b, c) => {
+                let _ = writeln!(
+                    svg,
+                    r##"<polygon points="{},{} {},{} {},{}" fill="#4d99ff" fill-opacity="0.25"/>"##,
+                    a.x, -a.y, b.x, -b.y, c.x, -c.y
+                );
+            }
+            LineType::Arc(center, radius, start, sweep, best) => {
+                let end = *start + *sweep;
+                let fill = if *best { "#ff9900" } else { "#999999" };
+                // The y axis is flipped, which turns the counterclockwise sweeps of the arc clockwise
+                let _ = writeln!(
+                    svg,
+                    r##"<path d="M {} {} L {} {} A {} {} 0 {} {} {} {} Z" fill="{}" fill-opacity="0.4"/>"##,
+                    center.x,
+                    -center.y,
+                    center.x + radius * start.cos(),
+                    -(center.y + radius * start.sin()),
+                    radius,
+                    radius,
+                    u8::from(sweep.abs() > std::f32::consts::PI),
+                    u8::from(*sweep < 0.0),
+                    center.x + radius * end.cos(),
+                    -(center.y + radius * end.sin()),
+                    fill
+                );
+            }
+            LineType::Circle(center, radius) => {
+                let _ = writeln!(
+                    svg,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="#cc4dff" vector-effect="non-scaling-stroke"/>"##,
+                    center.x, -center.y, radius
+                );
+            }
+            LineType::TextComment(comment) => comments.extend(comment.lines()),
+            LineType::ClearScreen | LineType::Milestone(_) => {}
+        }
+    }
+    write_hull_paths(&mut svg, &hull_edges);
+
+    for (i, comment) in comments.iter().rev().enumerate() {
+        let _ = writeln!(
+            svg,
+            r##"<text x="{}" y="{}" font-size="{}" font-family="sans-serif" fill="#ffffff">{}</text>"##,
+            min.x + font_size * 0.5,
+            -min.y - font_size * (0.5 + 1.2 * i as f32),
+            font_size,
+            escape_xml(comment)
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes an SVG line between the two points, flipping the y axis
+fn write_line(svg: &mut String, a: Vec2, b: Vec2, color: &str) {
+    let _ = writeln!(
+        svg,
+        r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" vector-effect="non-scaling-stroke"/>"#,
+        a.x, -a.y, b.x, -b.y, color
+    );
+}
+
+/// Writes the hull edges as SVG paths, each path following the connected edges from one to the next.
+///
+/// The algorithms don't add the edges in the order of the hull, e.g. KPS adds them by recursion depth,
+/// so the edges continuing a path are looked up by their endpoints, which are indexed once for all paths.
+fn write_hull_paths(svg: &mut String, edges: &[(Vec2, Vec2)]) {
+    let mut edges_at: HashMap<[u32; 2], Vec<usize>> = HashMap::new();
+    for (i, (a, b)) in edges.iter().enumerate() {
+        edges_at.entry(point_key(*a)).or_default().push(i);
+        edges_at.entry(point_key(*b)).or_default().push(i);
+    }
+    let mut used = vec![false; edges.len()];
+    let next_edge_at = |used: &[bool], point: Vec2| edges_at[&point_key(point)].iter().copied().find(|&i| !used[i]);
+
+    // Paths start at the ends of the chains first, closed chains can start anywhere
+    let chain_ends: Vec<Vec2> = edges
+        .iter()
+        .flat_map(|(a, b)| [*a, *b])
+        .filter(|point| edges_at[&point_key(*point)].len() == 1)
+        .collect();
+    let mut starts = chain_ends.into_iter().chain(edges.iter().map(|(a, _)| *a));
+
+    let mut path = String::new();
+    while let Some(start) = starts.find(|start| next_edge_at(&used, *start).is_some()) {
+        let mut end = start;
+        let _ = write!(path, "M {} {} ", start.x, -start.y);
+        while let Some(next) = next_edge_at(&used, end) {
+            used[next] = true;
+            end = if edges[next].0 == end { edges[next].1 } else { edges[next].0 };
+            let _ = write!(path, "L {} {} ", end.x, -end.y);
+        }
+    }
+
+    if !path.is_empty() {
+        let _ = writeln!(
+            svg,
+            r##"<path d="{}" fill="none" stroke="#ffffff" vector-effect="non-scaling-stroke"/>"##,
+            path.trim_end()
+        );
+    }
+}
+
+/// Returns the key of the point in the index of [write_hull_paths], which treats `-0.0` like `0.0` as `==` does
+fn point_key(point: Vec2) -> [u32; 2] {
+    [(point.x + 0.0).to_bits(), (point.y + 0.0).to_bits()]
+}
+
+/// Returns the color as a hexadecimal SVG color, ignoring its alpha
+fn hex_color(color: Color) -> String {
+    let [red, green, blue, _] = color.as_rgba_u8();
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+/// Returns the bottom left and top right corners of the area covered by the points and the primitives of the frame
+fn frame_bounds(frame: &[&LineType], input: &[(Vec2, Color)], point_radius: f32) -> (Vec2, Vec2) {
+    let mut points = Vec::new();
+    for (point, _) in input {
+        let radius = Vec2::splat(point_radius);
+        points.extend([*point - radius, *point + radius]);
+    }
+
+    bounds(frame, points, point_radius).unwrap_or((Vec2::splat(-point_radius), Vec2::splat(point_radius)))
+}
+
+/// Returns the bottom left and top right corners of the area covered by the primitives of the step.
+///
+/// Vertical lines only have an x coordinate, so a step without any other primitive covers no area and
+/// returns [None], as do steps of text comments only.
+pub fn step_bounds(step: &[LineType], point_radius: f32) -> Option<(Vec2, Vec2)> {
+    let step: Vec<&LineType> = step.iter().collect();
+    bounds(&step, Vec::new(), point_radius)
+}
+
+/// Returns the corners of the area covered by the `points` along with the primitives
+fn bounds(primitives: &[&LineType], mut points: Vec<Vec2>, point_radius: f32) -> Option<(Vec2, Vec2)> {
+    for line in primitives {
+        match line {
+            LineType::PartOfHull(a, b)
+            | LineType::Temporary(a, b)
+            | LineType::Diameter(a, b)
+            | LineType::ClosingEdge(a, b)
+            | LineType::Tangent(a, b)
+            | LineType::LayerEdge(a, b, _) => {
+                points.extend([*a, *b]);
+            }
+            LineType::CollinearPoint(point) => {
+                let radius = Vec2::splat(point_radius * 1.5);
+                points.extend([*point - radius, *point + radius]);
+            }
+            LineType::HullVertex(point) => {
+                let radius = Vec2::splat(point_radius * 2.0);
+                points.extend([*point - radius, *point + radius]);
+            }
+            LineType::HighlightPoint(point, _) => {
+                let radius = Vec2::splat(point_radius * HALO_RADIUS);
+                points.extend([*point - radius, *point + radius]);
+            }
+            LineType::DiscardedPoint(point) => {
+                let radius = Vec2::splat(point_radius);
+                points.extend([*point - radius, *point + radius]);
+            }
+            LineType::Circle(center, radius) | LineType::Arc(center, radius, ..) => {
+                points.extend([*center - Vec2::splat(*radius), *center + Vec2::splat(*radius)]);
+            }
+            LineType::Region(a, b, c) => {
+                points.extend([*a, *b, *c]);
+            }
+            LineType::Polygon(corners, _) | LineType::FilledHull(corners) => points.extend(corners),
+            LineType::VerticalLine(..)
+            | LineType::TextComment(_)
+            | LineType::ClearScreen
+            | LineType::Milestone(_) => {}
+        }
+    }
+
+    // Vertical lines only contribute their x coordinate, at the height of the rest of the primitives
+    let y = points.first()?.y;
+    for line in primitives {
+        if let LineType::VerticalLine(x, _) = line {
+            points.push(Vec2::new(*x, y));
+        }
+    }
+
+    Some(points.iter().fold((points[0], points[0]), |(min, max), point| {
+        (min.min(*point), max.max(*point))
+    }))
+}
+
+/// Escapes the characters that have a special meaning in XML
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// The formats the hull vertices can be exported in, see [hull_to_text]
+#[derive(PartialEq, Clone, Copy)]
+pub enum HullFormat {
+    /// One `x, y` pair per line, the format of [parse_points](crate::parsing::parse_points)
+    Csv,
+    /// An object with the `hull` vertices and optionally the input `points`, as `[x, y]` arrays
+    Json,
+    /// A [GeoJSON](https://geojson.org) Feature with the hull as its Polygon, or a FeatureCollection
+    /// with a MultiPoint Feature of the input points next to it
+    GeoJson,
+    /// A WKT `POLYGON`, or a `GEOMETRYCOLLECTION` with a `MULTIPOINT` of the input points next to it
+    Wkt,
+}
+
+impl HullFormat {
+    /// Returns the file extension of the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            HullFormat::Csv => "csv",
+            HullFormat::Json => "json",
+            HullFormat::GeoJson => "geojson",
+            HullFormat::Wkt => "wkt",
+        }
+    }
+}
+
+/// The document of a [HullFormat::Json] export
+#[derive(Serialize)]
+struct HullExport<'a> {
+    hull: &'a [Vec2],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    points: Option<&'a [Vec2]>,
+}
+
+/// Serializes the hull vertices in their order around the hull, along with the input points if they are given.
+///
+/// In CSV the rows of the hull vertices and of the input points are told apart by a third column,
+/// which [parse_points](crate::parsing::parse_points) ignores.
+pub fn hull_to_text(hull: &[Vec2], points: Option<&[Vec2]>, format: HullFormat) -> String {
+    match (format, points) {
+        (HullFormat::Csv, None) => hull.iter().map(|p| format!("{}, {}\n", p.x, p.y)).collect(),
+        (HullFormat::Csv, Some(points)) => hull
+            .iter()
+            .map(|p| format!("{}, {}, hull\n", p.x, p.y))
+            .chain(points.iter().map(|p| format!("{}, {}, point\n", p.x, p.y)))
+            .collect(),
+        (HullFormat::Json, _) => serde_json::to_string_pretty(&HullExport { hull, points }).unwrap_or_default(),
+        (HullFormat::GeoJson, _) => serde_json::to_string_pretty(&hull_to_geojson(hull, points)).unwrap_or_default(),
+        (HullFormat::Wkt, None) => format!("{}\n", wkt_polygon(hull)),
+        (HullFormat::Wkt, Some(points)) => {
+            let points: Vec<_> = points.iter().map(|p| format!("({} {})", p.x, p.y)).collect();
+            let points = match points.is_empty() {
+                true => "MULTIPOINT EMPTY".to_string(),
+                false => format!("MULTIPOINT ({})", points.join(", ")),
+            };
+            format!("GEOMETRYCOLLECTION ({}, {})\n", wkt_polygon(hull), points)
+        }
+    }
+}
+
+/// Returns the hull as a WKT `POLYGON`, its ring closed by repeating the first vertex
+fn wkt_polygon(hull: &[Vec2]) -> String {
+    let Some(first) = hull.first() else {
+        return "POLYGON EMPTY".to_string();
+    };
+    let ring: Vec<_> = hull.iter().chain([first]).map(|p| format!("{} {}", p.x, p.y)).collect();
+    format!("POLYGON (({}))", ring.join(", "))
+}
+
+/// Returns the GeoJSON document of [HullFormat::GeoJson].
+///
+/// The ring of the Polygon is closed by repeating its first vertex, and it is counterclockwise like the hulls
+/// of the algorithms, as GeoJSON expects of exterior rings.
+fn hull_to_geojson(hull: &[Vec2], points: Option<&[Vec2]>) -> serde_json::Value {
+    let mut ring: Vec<_> = hull.iter().map(|p| [p.x, p.y]).collect();
+    ring.extend(ring.first().copied());
+    let polygon = serde_json::json!({
+        "type": "Feature",
+        "properties": { "name": "hull" },
+        "geometry": { "type": "Polygon", "coordinates": [ring] },
+    });
+
+    match points {
+        None => polygon,
+        Some(points) => serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [
+                polygon,
+                {
+                    "type": "Feature",
+                    "properties": { "name": "points" },
+                    "geometry": {
+                        "type": "MultiPoint",
+                        "coordinates": points.iter().map(|p| [p.x, p.y]).collect::<Vec<_>>(),
+                    },
+                },
+            ],
+        }),
+    }
+}
@@ -0,0 +1,92 @@
+//! Contains the conversions between the points and hulls of the algorithms and the types of the
+//! [geo](https://docs.rs/geo) crate, enabled by the `geo` feature.
+//!
+//! The points of the algorithms are [Vec2] and [DVec2](bevy::math::DVec2), which like the geo types come
+//! from another crate, so the conversions go through the [PointSet] and [Hull] wrappers. A [geo::MultiPoint]
+//! converts into a [PointSet] whose points go into [compute_hull](crate::algorithms::compute_hull), and the
+//! returned vertices wrapped in a [Hull] convert into a [geo::Polygon].
+
+use bevy::prelude::*;
+use geo::ConvexHull;
+
+use crate::algorithms::canonical_hull;
+use crate::point::{Point, Scalar};
+
+/// Largest distance between two vertices that [validate_hull] considers the same
+pub const VALIDATION_TOLERANCE: f32 = 1e-3;
+
+/// Points converted from and into a [geo::MultiPoint]
+pub struct PointSet<P: Point>(pub Vec<P>);
+
+/// Vertices of a hull converted from and into a [geo::Polygon], counterclockwise without repeating the first one
+pub struct Hull<P: Point>(pub Vec<P>);
+
+/// Returns the geo coordinate of the point
+fn to_coord<P: Point>(point: &P) -> geo::Coord<f64> {
+    geo::Coord {
+        x: point.x().to_f64(),
+        y: point.y().to_f64(),
+    }
+}
+
+/// Returns the point at the geo coordinate
+fn from_coord<P: Point>(coord: geo::Coord<f64>) -> P {
+    P::new(P::Scalar::from_f64(coord.x), P::Scalar::from_f64(coord.y))
+}
+
+impl<P: Point> From<&geo::MultiPoint<f64>> for PointSet<P> {
+    fn from(points: &geo::MultiPoint<f64>) -> Self {
+        PointSet(points.iter().map(|point| from_coord(point.0)).collect())
+    }
+}
+
+impl<P: Point> From<PointSet<P>> for geo::MultiPoint<f64> {
+    fn from(points: PointSet<P>) -> Self {
+        points.0.iter().map(|point| geo::Point(to_coord(point))).collect()
+    }
+}
+
+impl<P: Point> From<&geo::Polygon<f64>> for Hull<P> {
+    /// Takes the exterior ring of the polygon, the holes can't be part of a convex hull
+    fn from(polygon: &geo::Polygon<f64>) -> Self {
+        let mut vertices: Vec<P> = polygon.exterior().coords().map(|coord| from_coord(*coord)).collect();
+        // The rings of geo are closed by repeating the first coordinate
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        Hull(vertices)
+    }
+}
+
+impl<P: Point> From<Hull<P>> for geo::Polygon<f64> {
+    fn from(hull: Hull<P>) -> Self {
+        let ring: Vec<_> = hull.0.iter().map(to_coord).collect();
+        geo::Polygon::new(ring.into(), Vec::new())
+    }
+}
+
+/// Compares the hull of the points with the one [geo::ConvexHull] finds for them.
+///
+/// Both hulls are brought into the [canonical_hull] form first, so they may differ in where they start and in
+/// their collinear vertices. Returns a description of the first difference, if there is one.
+pub fn validate_hull(points: &[Vec2], hull: &[Vec2], epsilon: f32) -> Result<(), String> {
+    let multi_point = geo::MultiPoint::from(PointSet(points.to_vec()));
+    let reference = canonical_hull(&Hull::<Vec2>::from(&multi_point.convex_hull()).0, epsilon);
+    let hull = canonical_hull(hull, epsilon);
+
+    if reference.len() != hull.len() {
+        return Err(format!(
+            "geo::ConvexHull found {} vertices, the hull has {}",
+            reference.len(),
+            hull.len()
+        ));
+    }
+    match reference
+        .iter()
+        .zip(&hull)
+        .find(|(a, b)| a.distance(**b) > VALIDATION_TOLERANCE)
+    {
+        Some((a, b)) => Err(format!("Vertex {} of the hull differs from {} of geo::ConvexHull", b, a)),
+        None => Ok(()),
+    }
+}
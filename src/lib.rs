@@ -0,0 +1,15 @@
+//! # Convex Hull Simulation
+//! The algorithms and point generators behind the simulation, usable without launching the visualization.
+//!
+//! See the `convex_hull_simulation` binary for the visualization and `hull_cli` for the headless
+//! command line interface. In the web build the hull can also be computed from JavaScript, see `wasm`.
+
+pub mod algorithms;
+pub mod distributions;
+pub mod export;
+#[cfg(feature = "geo")]
+pub mod geo_interop;
+pub mod parsing;
+pub mod point;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
@@ -102,7 +102,12 @@
 //! stays just above `50ms`. Thus we can conclude that Kirkpatrick-Seidel is faster than Jarvis March.
 
 use bevy::{
+    ecs::{query::QueryFilter, system::SystemParam},
     prelude::*,
+    render::{
+        camera::{ClearColorConfig, Viewport},
+        view::{screenshot::ScreenshotManager, RenderLayers},
+    },
     sprite::{MaterialMesh2dBundle, Mesh2dHandle},
     window::PrimaryWindow,
 };
@@ -115,18 +120,75 @@ use bevy_egui::{
 
 use bevy_pancam::{PanCam, PanCamPlugin};
 
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
 use bevy::render::render_asset::RenderAssetUsages;
 use bevy::render::render_resource::PrimitiveTopology;
 
-mod algorithms;
-use algorithms::*;
+use convex_hull_simulation::{
+    algorithms::*,
+    distributions::*,
+    export::{
+        current_frame, frame_to_svg, highlight_color, hull_to_text, layer_color, milestones, step_bounds, HullFormat,
+        HALO_RADIUS, LAYER_COLORS,
+    },
+    parsing::parse_input,
+};
+#[cfg(feature = "geo")]
+use convex_hull_simulation::geo_interop::validate_hull;
+
+mod benchmark;
+use benchmark::*;
+
+mod capture;
+use capture::*;
+
+mod point_editing;
+use point_editing::*;
+
+mod point_editor;
+use point_editor::*;
+
+mod point_labels;
+use point_labels::*;
+
+mod recursion_panel;
+use recursion_panel::*;
 
-mod distributions;
-use distributions::*;
+mod settings;
+use settings::AppSettings;
+
+mod world_grid;
+use world_grid::*;
+
+#[cfg(target_arch = "wasm32")]
+mod url_parameters;
+
+#[cfg(all(feature = "video", not(target_arch = "wasm32")))]
+mod video;
+
+#[cfg(all(feature = "rerun", not(target_arch = "wasm32")))]
+mod rerun_logging;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod session;
+#[cfg(not(target_arch = "wasm32"))]
+use session::*;
 
 /// Component to identify the points. Used by [despawn_entities] function to despawn all the points.
+///
+/// Contains the index of the point in [PointData], the copies drawn for the [ComparisonHistory] share the
+/// index of their original. Points are appended, moved or replaced all at once, which keeps the indices
+/// valid; deleting points shifts the indices of the points after them.
 #[derive(Component)]
-struct PointSingle;
+struct PointSingle(usize);
 
 /// Resource to contain all data regarding the points.
 ///
@@ -138,6 +200,373 @@ struct PointData(Vec<Vec2>, String, f32, usize, bool);
 #[derive(Resource)]
 struct SimulationTimer(Timer, f32);
 
+/// The kinds of steps of a drawing history, which can be shown for different durations, see [StepDurations]
+#[derive(PartialEq, Clone, Copy)]
+enum StepKind {
+    /// Steps that add hull edges or vertices
+    Hull,
+    /// Steps that only show intermediate calculations
+    Temporary,
+    /// Steps with nothing but comments and overlays
+    Other,
+}
+
+impl StepKind {
+    /// Returns the kind of the step, the hull taking precedence over the intermediate calculations
+    fn of(step: &[LineType]) -> Self {
+        let hull = step.iter().any(|line| {
+            matches!(
+                line,
+                LineType::PartOfHull(..) | LineType::HullVertex(_) | LineType::LayerEdge(..) | LineType::FilledHull(_)
+            )
+        });
+        let temporary = step.iter().any(|line| {
+            matches!(
+                line,
+                LineType::Temporary(..)
+                    | LineType::Tangent(..)
+                    | LineType::Region(..)
+                    | LineType::Arc(..)
+                    | LineType::Polygon(..)
+                    | LineType::HighlightPoint(..)
+                    | LineType::VerticalLine(..)
+                    | LineType::DiscardedPoint(_)
+            )
+        });
+
+        if hull {
+            StepKind::Hull
+        } else if temporary {
+            StepKind::Temporary
+        } else {
+            StepKind::Other
+        }
+    }
+}
+
+/// Resource that contains the playback speed and how long each [StepKind] is shown, relative to the
+/// time step of the [SimulationTimer]
+#[derive(Resource, Clone, Serialize, Deserialize)]
+struct StepDurations {
+    /// Multiplier of the playback speed, 2 plays twice as fast and 0.5 half as fast
+    speed: f32,
+    hull: f32,
+    temporary: f32,
+    other: f32,
+}
+
+impl StepDurations {
+    /// Returns how long the step is shown in seconds, for the given time step of the [SimulationTimer]
+    fn duration(&self, time_step: f32, step: &[LineType]) -> f32 {
+        let factor = match StepKind::of(step) {
+            StepKind::Hull => self.hull,
+            StepKind::Temporary => self.temporary,
+            StepKind::Other => self.other,
+        };
+        time_step * factor / self.speed
+    }
+}
+
+impl Default for StepDurations {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            hull: 1.0,
+            temporary: 1.0,
+            other: 1.0,
+        }
+    }
+}
+
+/// Resource to store the playback state of the animation.
+///
+/// It contains data in the following order: playback is paused | a single step forward was requested
+#[derive(Resource)]
+struct Playback(bool, bool);
+
+/// Resource to store how far the scenes of the histories are drawn, so `graphics_drawing` only draws the steps
+/// that changed. Resetting it to 0 makes the next frame draw the scenes from scratch.
+///
+/// It contains data in the following order: step of the [DrawingHistory] | step of the [ComparisonHistory]
+#[derive(Resource, Default)]
+struct RenderedSteps(usize, usize);
+
+/// Resource to store the output of the last run algorithm.
+///
+/// It contains data in the following order: The hull vertices | # of collinear points on the hull edges | time taken to compute the hull | diameter of the hull | whether the points changed since | primitive operations of the algorithm
+#[derive(Resource)]
+struct HullData(Vec<Vec2>, usize, Duration, f32, bool, OperationCounts);
+
+/// Resource to store which overlays are drawn on top of the hull after running an algorithm.
+///
+/// It contains data in the following order: draw the minimum enclosing circle | draw the diameter | fill the hull
+#[derive(Resource)]
+struct HullOverlays(bool, bool, bool);
+
+/// Resource to store the [akl_toussaint] pre-filter, which runs before the selected algorithm when enabled.
+///
+/// It contains data in the following order: filter is enabled | # of discarded points and the speedup of the last run
+#[derive(Resource)]
+struct AklToussaint(bool, Option<(usize, f64)>);
+
+impl AklToussaint {
+    /// Returns whether the filter runs before the algorithm.
+    ///
+    /// Melkman's algorithm is skipped, as dropping points from its polyline leaves a sequence that isn't simple.
+    fn filters(&self, algorithm: AlgorithmType) -> bool {
+        self.0 && algorithm != AlgorithmType::Melkman
+    }
+}
+
+/// Resource to store the settings of the [approximate_hull] algorithm.
+///
+/// It contains data in the following order: # of vertical strips | approximation error of the last run versus the exact hull
+#[derive(Resource)]
+struct ApproximateHull(usize, Option<f32>);
+
+/// Resource to store the validation of the hulls against [geo::ConvexHull], checked whenever an algorithm runs.
+///
+/// It contains data in the following order: validation is enabled | result of the last validation
+#[cfg(feature = "geo")]
+#[derive(Resource)]
+struct GeoValidation(bool, Option<Result<(), String>>);
+
+/// Resource to store whether the clicked points are drawn as a polyline, in the order [melkman] expects them
+#[derive(Resource)]
+struct PolygonDrawing(bool);
+
+/// Component to identify the edges drawn between consecutive points in the [PolygonDrawing] mode
+#[derive(Component)]
+struct PolylineEdge;
+
+/// Resource to store how manually added points are snapped to a grid.
+///
+/// It contains data in the following order: snapping is enabled | size of the grid cells
+#[derive(Resource)]
+struct GridSnap(bool, f32);
+
+/// The ways the points can be colored
+#[derive(PartialEq, Clone, Copy)]
+enum ColorMode {
+    /// Hue derived from the index of the point
+    Rainbow,
+    /// All points are white
+    Solid,
+    /// Hue derived from the distance of the point from the centroid of all points
+    DistanceFromCentroid,
+}
+
+/// Resource that contains the [ColorMode] of the points, see [point_color_system]
+#[derive(Resource)]
+struct PointColoring(ColorMode);
+
+/// Resource that contains the [hull_input_key] of the last run of an algorithm, while its hull and
+/// drawing history are still intact.
+///
+/// Running the algorithm again on the same input replays that history instead of computing the hull again.
+/// Everything that changes the points or replaces the history resets it to [None].
+#[derive(Resource)]
+struct HullCache(Option<u64>);
+
+/// Returns a hash of everything the result of "Generate Mesh" depends on
+fn hull_input_key(
+    points: &[Vec2],
+    algorithm: AlgorithmType,
+    epsilon: f32,
+    overlays: &HullOverlays,
+    prefilter: bool,
+    strips: usize,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for point in points {
+        point.x.to_bits().hash(&mut hasher);
+        point.y.to_bits().hash(&mut hasher);
+    }
+    (algorithm as u8).hash(&mut hasher);
+    epsilon.to_bits().hash(&mut hasher);
+    overlays.0.hash(&mut hasher);
+    overlays.1.hash(&mut hasher);
+    overlays.2.hash(&mut hasher);
+    prefilter.hash(&mut hasher);
+    strips.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Resource to store the outcome of the last import of a point file, which is shown in the inspector.
+///
+/// It contains data in the following order: name of the file | # of parsed rows | # of rows that failed to parse
+#[derive(Resource)]
+struct PointImport(Option<(String, usize, usize)>);
+
+/// Resource to store how the hull is exported.
+///
+/// It contains data in the following order: format of the file | whether the input points are exported along the hull
+#[derive(Resource)]
+struct HullExportFormat(HullFormat, bool);
+
+/// Resource that contains the largest displacement applied to each coordinate when jittering the points
+#[derive(Resource)]
+struct Jitter(f32);
+
+/// Resource to store the state of the point-in-hull query tool.
+///
+/// It contains data in the following order: query mode is enabled | whether the last queried point is inside the hull
+#[derive(Resource)]
+struct HullQuery(bool, Option<bool>);
+
+/// Component to identify the marker of the last point queried with the [HullQuery] tool.
+#[derive(Component)]
+struct QueryMarker;
+
+/// Component to identify the entities drawn for the [ComparisonHistory], including the copies of the points.
+#[derive(Component)]
+struct Comparison;
+
+/// Resource that contains the history of the second algorithm when comparing both algorithms.
+///
+/// It contains data in the following order: history of points | the current point index | horizontal offset of the drawing
+#[derive(Resource)]
+struct ComparisonHistory(Vec<Vec<LineType>>, usize, f32);
+
+/// Render layer of the entities drawn for the [ComparisonHistory], so that the split screen can show them apart.
+const COMPARISON_LAYER: u8 = 1;
+
+/// Component to identify the camera showing the [ComparisonHistory] in the right half of the split screen.
+#[derive(Component)]
+struct ComparisonCamera;
+
+/// Resource to store whether the comparison is shown side by side in two viewports instead of a single one.
+#[derive(Resource)]
+struct SplitScreen(bool);
+
+/// Resource to store the seed the random points are generated from, so that a world can be generated again.
+///
+/// Seeds are drawn below `u32::MAX`, which keeps them exact in the inspector and short to share.
+///
+/// It contains data in the following order: seed of the current world | keep the seed for the next world | generator seeded with it
+#[derive(Resource)]
+struct WorldSeed(u64, bool, StdRng);
+
+impl WorldSeed {
+    /// Returns a new random seed
+    fn random_seed() -> u64 {
+        thread_rng().gen::<u32>() as u64
+    }
+}
+
+impl Default for WorldSeed {
+    fn default() -> Self {
+        let seed = Self::random_seed();
+        Self(seed, false, StdRng::seed_from_u64(seed))
+    }
+}
+
+/// Resource to store the errors of the last [GenerateWorld], which are listed by [world_errors_window]
+#[derive(Resource)]
+struct WorldErrors(Vec<String>);
+
+/// Event that rebuilds the world with new points, parsed from the point editor if it has any text and
+/// generated from the current [Distribution] otherwise.
+#[derive(Event)]
+struct GenerateWorld;
+
+/// Resource to store whether the main camera follows the geometry of the current step of the [DrawingHistory].
+#[derive(Resource)]
+struct FollowStep(bool);
+
+/// Event to frame all the points with the main camera, sent by the "Fit view" button and after every [GenerateWorld].
+#[derive(Event)]
+struct FitView;
+
+/// Width and dash pattern of a kind of line
+#[derive(Clone, Copy, PartialEq)]
+struct LineStyle {
+    /// Width in pixels on the screen, which [line_scale_system] keeps while zooming
+    width: f32,
+    dashed: bool,
+}
+
+/// Styles of the lines the drawing histories are made of, which can be changed in the inspector.
+#[derive(Clone, Copy, PartialEq)]
+struct LineStyles {
+    /// Style of the hull edges, the layer edges and the closing edges
+    hull: LineStyle,
+    /// Style of the temporary lines, the tangents, the diameter and the outlines of the circles
+    temporary: LineStyle,
+    /// Style of the vertical lines of the medians
+    vertical_line: LineStyle,
+}
+
+impl Default for LineStyles {
+    fn default() -> Self {
+        Self {
+            hull: LineStyle { width: 2.0, dashed: false },
+            temporary: LineStyle { width: 1.0, dashed: false },
+            vertical_line: LineStyle { width: 1.0, dashed: true },
+        }
+    }
+}
+
+/// Resource that contains the assets shared by the primitives of the drawing histories, created once in [setup].
+///
+/// Solid segments are drawn by transforming [DrawingAssets::unit_line] with [segment_transform] instead of
+/// adding a mesh per segment, and every kind of primitive reuses the same material. Likewise the points and the
+/// markers of points scale [DrawingAssets::unit_circle], and the points share a material per color.
+#[derive(Resource)]
+struct DrawingAssets {
+    /// Quad from (0, -0.5) to (1, 0.5), stretched to the length and the width of the segments
+    unit_line: Handle<Mesh>,
+    /// Circle of radius 1, scaled to the radius of the points and of the markers, see [circle_transform]
+    unit_circle: Handle<Mesh>,
+    /// Materials of the points keyed by their color in 8 bit RGBA, added the first time a color is used
+    point_materials: HashMap<[u8; 4], Handle<ColorMaterial>>,
+    line_styles: LineStyles,
+    /// World units per pixel of the main camera when the lines were drawn, which their widths are multiplied by
+    line_scale: f32,
+    hull: Handle<ColorMaterial>,
+    comparison_hull: Handle<ColorMaterial>,
+    temporary: Handle<ColorMaterial>,
+    /// Materials of the vertical lines indexed by depth, see [vertical_line_color]
+    vertical_lines: Vec<Handle<ColorMaterial>>,
+    collinear: Handle<ColorMaterial>,
+    hull_vertex: Handle<ColorMaterial>,
+    diameter: Handle<ColorMaterial>,
+    closing_edge: Handle<ColorMaterial>,
+    discarded: Handle<ColorMaterial>,
+    tangent: Handle<ColorMaterial>,
+    circle: Handle<ColorMaterial>,
+    region: Handle<ColorMaterial>,
+    hull_fill: Handle<ColorMaterial>,
+    arc: Handle<ColorMaterial>,
+    best_arc: Handle<ColorMaterial>,
+    /// Materials of the halo rings indexed by [HighlightKind], see [DrawingAssets::highlight]
+    highlights: [Handle<ColorMaterial>; 4],
+    /// Materials of the convex layers, see [layer_color]
+    layers: Vec<Handle<ColorMaterial>>,
+    /// Translucent materials of the [LineType::Polygon]s, in the colors of the convex layers
+    polygons: Vec<Handle<ColorMaterial>>,
+}
+
+impl DrawingAssets {
+    /// Returns the material of a vertical line found at the given depth of the KPS recursion.
+    fn vertical_line(&self, depth: usize) -> Handle<ColorMaterial> {
+        self.vertical_lines[depth.min(FADED_DEPTHS)].clone()
+    }
+
+    /// Returns the material of the halo ring of a [LineType::HighlightPoint] with the given kind.
+    fn highlight(&self, kind: HighlightKind) -> Handle<ColorMaterial> {
+        self.highlights[kind as usize].clone()
+    }
+
+    /// Returns the material of the points with the given color, adding it if no point had that color before.
+    fn point_material(&mut self, materials: &mut Assets<ColorMaterial>, color: Color) -> Handle<ColorMaterial> {
+        self.point_materials
+            .entry(color.as_rgba_u8())
+            .or_insert_with(|| materials.add(color))
+            .clone()
+    }
+}
+
 /// Component to identify the color text.
 #[derive(Component)]
 struct ColorText;
@@ -152,27 +581,167 @@ const MAX_ZOOM_OUT: f32 = 500.0;
 /// Text size of the text at bottom
 const TEXT_SIZE: f32 = 30.0;
 
+/// Depth of the shaded regions, below the points and the temporary lines, see [line_depth]
+const REGION_DEPTH: f32 = -0.5;
+
+/// Depth of the temporary lines, see [line_depth]
+const TEMPORARY_DEPTH: f32 = 0.0;
+
+/// Depth of the hull edges, see [line_depth]
+const HULL_DEPTH: f32 = 1.0;
+
+/// Depth of the overlays drawn on top of the hull, see [line_depth]
+const OVERLAY_DEPTH: f32 = 1.5;
+
+/// Depth of the highlighted points, see [line_depth]
+const HIGHLIGHT_DEPTH: f32 = 2.0;
+
+/// Smallest radius the points are drawn with, as circles without a positive radius give degenerate meshes
+const MIN_POINT_RADIUS: f32 = 1.0;
+
+/// Largest radius the inspector draws the points with
+const MAX_POINT_RADIUS: f32 = 1000.0;
+
+/// Largest number of points the inspector generates, more of them make the simulation unresponsive
+const MAX_POINT_COUNT: usize = 15_000;
+
+/// Time step in seconds the simulation starts with
+const DEFAULT_TIME_STEP: f32 = 1.0;
+
+/// Longest time step in seconds the inspector lets the simulation wait between its steps
+const MAX_TIME_STEP: f32 = 10.0;
+
+/// How long the [AppSettings] have to stay unchanged before they are saved, so dragging a slider doesn't
+/// write them on every frame
+const SETTINGS_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Range of the playback speed of the [StepDurations]
+const PLAYBACK_SPEED_RANGE: RangeInclusive<f32> = 0.1..=10.0;
+
+/// Range of how long each [StepKind] is shown, relative to the time step
+const STEP_DURATION_RANGE: RangeInclusive<f32> = 0.1..=5.0;
+
+/// Opacity of the [LineType::Polygon]s, which stays low so the points inside them remain visible
+const POLYGON_ALPHA: f32 = 0.2;
+
+/// Factor the bounding box of the points is enlarged by when fitting the view, so they don't touch its edges
+const FIT_MARGIN: f32 = 1.1;
+
+/// Factor the bounding box of the current step is enlarged by while following it, which keeps some context around it
+const FOLLOW_MARGIN: f32 = 1.5;
+
+/// Rate at which the camera closes in on the current step while following it, in 1/s
+const FOLLOW_SPEED: f32 = 4.0;
+
+/// Length of a dash and the gap after it as a multiple of the width of a dashed [LineStyle]
+const DASH_PERIOD: f32 = 8.0;
+
+/// Number of dashes a dashed line is split into at most
+const MAX_DASHES: usize = 256;
+
+/// Factor the zoom may change by before the lines are redrawn with new widths, see [line_scale_system]
+const LINE_SCALE_TOLERANCE: f32 = 1.25;
+
+/// Number of segments used to approximate a [LineType::Circle]
+const CIRCLE_SEGMENTS: usize = 128;
+
+/// Factor the opacity of the vertical lines is multiplied by at each depth of the KPS recursion
+const VERTICAL_LINE_FADE: f32 = 0.7;
+
+/// Opacity of the vertical lines found at the deepest levels of the KPS recursion
+const MIN_VERTICAL_LINE_ALPHA: f32 = 0.2;
+
+/// Depth of the KPS recursion from which the vertical lines are drawn with [MIN_VERTICAL_LINE_ALPHA]
+const FADED_DEPTHS: usize = 5;
+
 fn main() {
-    App::new()
-        .add_plugins((DefaultPlugins, EguiPlugin, PanCamPlugin))
+    let mut app = App::new();
+    app.add_plugins((DefaultPlugins, EguiPlugin, PanCamPlugin))
         .add_systems(Startup, setup)
         .add_systems(Update, ui)
+        .add_systems(Update, world_errors_window)
+        .add_systems(Update, benchmark_ui)
+        .add_systems(Update, recursion_panel)
+        .add_systems(Update, point_color_system)
+        .add_systems(Update, point_label_system)
+        .add_systems(Update, point_tooltip_system)
+        .add_systems(Update, world_grid_system)
+        .add_systems(Update, snap_preview_system.after(world_grid_system))
+        .add_systems(Update, line_scale_system.before(graphics_drawing))
         .add_systems(Update, graphics_drawing)
+        .add_systems(Update, capture_step_system.after(graphics_drawing))
         .add_systems(Update, keyboard_input_system)
         .add_systems(Update, mouse_position_system)
+        .add_systems(Update, point_drag_system.before(mouse_position_system).before(pan_cam_system))
+        .add_systems(
+            Update,
+            point_deletion_system
+                .before(point_drag_system)
+                .before(mouse_position_system)
+                .before(pan_cam_system),
+        )
         .add_systems(Update, check_egui_wants_focus)
         .add_systems(Update, pan_cam_system)
+        .add_systems(Update, split_screen_system)
+        .add_systems(Update, generate_world_system)
+        .add_systems(Update, fit_view_system.after(generate_world_system))
+        .add_systems(Update, follow_step_system.after(graphics_drawing))
+        .add_systems(Update, save_settings_system)
+        .add_event::<GenerateWorld>()
+        .add_event::<FitView>()
+        .add_event::<StepDrawn>()
+        .init_resource::<GifRecording>()
         .insert_resource(PointData(vec![], String::new(), 10.0, 0, false))
-        .insert_resource(Distribution(DistributionType::Fibonacci))
+        .insert_resource(Distribution(
+            DistributionType::Fibonacci,
+            DistributionParameters::default(),
+        ))
         .insert_resource(SimulationTimer(
-            Timer::from_seconds(1.0, TimerMode::Repeating),
-            1.0,
+            Timer::from_seconds(DEFAULT_TIME_STEP, TimerMode::Repeating),
+            DEFAULT_TIME_STEP,
         ))
+        .insert_resource(Playback(false, false))
+        .insert_resource(StepDurations::default())
+        .init_resource::<RenderedSteps>()
+        .init_resource::<Benchmark>()
         .insert_resource(DrawingHistory(vec![], 0))
+        .insert_resource(ComparisonHistory(vec![], 0, 0.0))
+        .insert_resource(SplitScreen(false))
+        .init_resource::<RecursionTree>()
+        .init_resource::<PointLabels>()
+        .insert_resource(WorldGrid(true))
+        .insert_resource(FollowStep(false))
+        .insert_resource(HullData(vec![], 0, Duration::ZERO, 0.0, false, OperationCounts::default()))
+        .insert_resource(HullOverlays(false, false, false))
+        .insert_resource(HullQuery(false, None))
+        .insert_resource(GridSnap(false, 10.0))
+        .insert_resource(PolygonDrawing(false))
+        .init_resource::<PointDrag>()
+        .init_resource::<PointDeletion>()
+        .insert_resource(AklToussaint(false, None))
+        .insert_resource(ApproximateHull(DEFAULT_APPROXIMATION_STRIPS, None))
+        .insert_resource(Jitter(5.0))
+        .insert_resource(PointImport(None))
+        .insert_resource(HullExportFormat(HullFormat::Csv, false))
+        .insert_resource(HullCache(None))
+        .insert_resource(PointColoring(ColorMode::Rainbow))
         .insert_resource(Algorithm(AlgorithmType::JarvisMarch))
+        .insert_resource(OrientationEpsilon(DEFAULT_ORIENTATION_EPSILON))
         .insert_resource(TextComment)
-        .insert_resource(EguiWantsFocus(false))
-        .run();
+        .insert_resource(EguiWantsFocus(false, false))
+        .init_resource::<WorldSeed>()
+        .insert_resource(WorldErrors(vec![]));
+
+    #[cfg(not(target_arch = "wasm32"))]
+    app.add_systems(Update, finish_gif_system);
+
+    #[cfg(all(feature = "rerun", not(target_arch = "wasm32")))]
+    app.add_systems(Update, rerun_logging::rerun_system);
+
+    #[cfg(feature = "geo")]
+    app.insert_resource(GeoValidation(false, None));
+
+    app.run();
 }
 
 /// Creates a combo box with the given label and choices.
@@ -200,247 +769,1462 @@ where
         });
 }
 
-/// Despawns all entities with the given component.
-fn despawn_entities<T: Component>(commands: &mut Commands, query: &Query<Entity, With<T>>) {
+/// Returns the corners of the smallest axis aligned box containing the points, if there are any.
+fn bounding_box(points: &[Vec2]) -> Option<(Vec2, Vec2)> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(min, max), p| (min.min(*p), max.max(*p))))
+}
+
+/// Despawns all entities matched by the given query.
+fn despawn_entities<F: QueryFilter>(commands: &mut Commands, query: &Query<Entity, F>) {
     for entity in query.iter() {
         commands.entity(entity).despawn();
     }
 }
 
-/// Initial setup function
-fn setup(mut commands: Commands) {
-    commands.spawn(Camera2dBundle::default()).insert(PanCam {
-        grab_buttons: vec![MouseButton::Left, MouseButton::Middle], // which buttons should drag the camera
-        enabled: true,        // when false, controls are disabled. See toggle example.
-        zoom_to_cursor: true, // whether to zoom towards the mouse or the center of the screen
-        min_scale: 1.,        // prevent the camera from zooming too far in
-        max_scale: Some(MAX_ZOOM_OUT), // prevent the camera from zooming too far out
-        ..default()
-    });
+/// Returns the radius clamped to at least [MIN_POINT_RADIUS], which also replaces radii that aren't finite.
+///
+/// The slider keeps the radius in range, but loaded sessions and settings could still contain any value.
+fn clamp_point_radius(radius: f32) -> f32 {
+    if radius.is_finite() {
+        radius.max(MIN_POINT_RADIUS)
+    } else {
+        MIN_POINT_RADIUS
+    }
 }
 
-/// Adds controls for pancam system. Namely disables the camera when egui wants focus.
-fn pan_cam_system(egui_wants_focus: Res<EguiWantsFocus>, mut pan_cam: Query<&mut PanCam>) {
-    for mut cam in pan_cam.iter_mut() {
-        cam.enabled = !egui_wants_focus.0;
+/// Returns the time step clamped to the range of the inspector, time steps that aren't finite are reset to
+/// [DEFAULT_TIME_STEP]. A negative, huge or NaN time step would make [Duration::from_secs_f32] panic.
+fn clamp_time_step(time_step: f32) -> f32 {
+    if time_step.is_finite() {
+        time_step.clamp(0.0, MAX_TIME_STEP)
+    } else {
+        DEFAULT_TIME_STEP
     }
 }
 
-/// Controls the keyboard input for the simulation.
-fn keyboard_input_system(
-    input: Res<ButtonInput<KeyCode>>,
-    mut point_data: ResMut<PointData>,
-    egui_resources: InputResources,
-) {
-    let ctrl = input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+/// Returns the transform that scales the unit circle of [DrawingAssets] to the given center and radius.
+fn circle_transform(center: Vec2, radius: f32, z: f32) -> Transform {
+    Transform::from_xyz(center.x, center.y, z).with_scale(Vec3::new(radius, radius, 1.0))
+}
 
-    if ctrl && input.just_pressed(KeyCode::KeyD) {
-        point_data.1.clear();
+/// Spawns a single point of the world at the given position, tagged with its index in [PointData].
+fn spawn_point(
+    commands: &mut Commands,
+    assets: &mut DrawingAssets,
+    materials: &mut Assets<ColorMaterial>,
+    index: usize,
+    position: Vec2,
+    radius: f32,
+    color: Color,
+) -> Entity {
+    commands
+        .spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(assets.unit_circle.clone()),
+                material: assets.point_material(materials, color),
+                transform: circle_transform(position, clamp_point_radius(radius), 0.0),
+                ..default()
+            },
+            PointSingle(index),
+        ))
+        .id()
+}
+
+/// Returns the colors of the points for the given [ColorMode].
+///
+/// [ColorMode::DistanceFromCentroid] goes from blue for the points closest to the centroid to red for the farthest ones.
+fn point_colors(mode: ColorMode, points: &[Vec2]) -> Vec<Color> {
+    match mode {
+        ColorMode::Rainbow | ColorMode::Solid => (0..points.len()).map(|i| point_color(mode, points, i)).collect(),
+        ColorMode::DistanceFromCentroid => {
+            let (centroid, max_distance) = centroid_distance(points);
+            points.iter().map(|p| distance_color(*p, centroid, max_distance)).collect()
+        }
     }
+}
 
-    if ctrl && input.just_pressed(KeyCode::KeyV) {
-        let clipboard = egui_resources.egui_clipboard;
-        match clipboard.get_contents() {
-            Some(contents) => {
-                point_data.1 += "\n";
-                point_data.1 += &contents;
-            }
-            None => warn!("Clipboard is empty"),
+/// Returns the color of the point at the index for the given [ColorMode], without the colors of the other points
+fn point_color(mode: ColorMode, points: &[Vec2], index: usize) -> Color {
+    match mode {
+        ColorMode::Rainbow => Color::hsl(360. * index as f32 / points.len() as f32, 0.95, 0.7),
+        ColorMode::Solid => Color::WHITE,
+        ColorMode::DistanceFromCentroid => {
+            let (centroid, max_distance) = centroid_distance(points);
+            distance_color(points[index], centroid, max_distance)
         }
     }
 }
 
-/// Draws the graphics as declared in [LineType] enum.
-fn graphics_drawing(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
+/// Returns the centroid of the points along with the largest distance of a point from it
+fn centroid_distance(points: &[Vec2]) -> (Vec2, f32) {
+    let centroid = points.iter().copied().sum::<Vec2>() / points.len().max(1) as f32;
+    let max_distance = points.iter().map(|p| p.distance(centroid)).fold(0.0, f32::max);
+    (centroid, max_distance)
+}
+
+/// Returns the color of the point in [ColorMode::DistanceFromCentroid]
+fn distance_color(point: Vec2, centroid: Vec2, max_distance: f32) -> Color {
+    let t = if max_distance > 0.0 { point.distance(centroid) / max_distance } else { 0.0 };
+    Color::hsl(240.0 * (1.0 - t), 0.95, 0.6)
+}
+
+/// Recolors the points once their number or the [ColorMode] changes.
+///
+/// Only [ColorMode::Solid] colors a point on its own, in the other modes adding or deleting a point changes the
+/// colors of all of them. The new points are already spawned with their color, so they can be recolored a frame later.
+fn point_color_system(
+    point_data: Res<PointData>,
+    point_coloring: Res<PointColoring>,
+    mut points: Query<(&PointSingle, &mut Handle<ColorMaterial>)>,
+    mut assets: ResMut<DrawingAssets>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    time: Res<Time>,
-    mut simulation_timer: ResMut<SimulationTimer>,
-    gizmo_query: Query<Entity, With<Gizmo>>,
-    text_query: Query<Entity, With<ColorText>>,
-    convex_hull_query: Query<Entity, With<ConvexHull>>,
-    mut drawing_history: ResMut<DrawingHistory>,
-    window: Query<&mut Window, With<PrimaryWindow>>,
+    mut colored: Local<Option<(usize, ColorMode)>>,
 ) {
-    let window = window.single();
-    if drawing_history.0.is_empty() || drawing_history.0.len() == drawing_history.1 {
-        // despawn_entities(&mut commands, &gizmo_query);
+    if !point_data.is_changed() && !point_coloring.is_changed() {
         return;
     }
-
-    simulation_timer.0.tick(time.delta());
-    if !simulation_timer.0.finished() {
+    let wanted = Some((point_data.0.len(), point_coloring.0));
+    if *colored == wanted {
         return;
     }
+    *colored = wanted;
 
-    despawn_entities(&mut commands, &gizmo_query);
-    despawn_entities(&mut commands, &text_query);
-
-    for i in &drawing_history.0[drawing_history.1] {
-        match i {
-            LineType::PartOfHull(a, b) => {
-                commands.spawn((
-                    MaterialMesh2dBundle {
-                        mesh: Mesh2dHandle(
-                            meshes.add(
-                                Mesh::new(
-                                    PrimitiveTopology::LineStrip,
-                                    RenderAssetUsages::default(),
-                                )
-                                .with_inserted_attribute(
-                                    Mesh::ATTRIBUTE_POSITION,
-                                    vec![[a.x, a.y, 0.0], [b.x, b.y, 0.0]],
-                                ),
-                            ),
-                        ),
-                        material: materials.add(Color::rgb(1.0, 1.0, 1.0)),
-                        ..default()
-                    },
-                    ConvexHull,
-                ));
-            }
-            LineType::Temporary(a, b) => {
-                commands.spawn((
-                    MaterialMesh2dBundle {
-                        mesh: Mesh2dHandle(
-                            meshes.add(
-                                Mesh::new(
-                                    PrimitiveTopology::LineStrip,
-                                    RenderAssetUsages::default(),
-                                )
-                                .with_inserted_attribute(
-                                    Mesh::ATTRIBUTE_POSITION,
-                                    vec![[a.x, a.y, 0.0], [b.x, b.y, 0.0]],
-                                ),
-                            ),
-                        ),
-                        material: materials.add(Color::rgb(0.44, 0.44, 0.44)),
-                        ..default()
-                    },
-                    Gizmo,
-                ));
-            }
-            LineType::TextComment(comment) => {
-                commands.spawn((
-                    TextBundle::from_section(
-                        comment,
-                        TextStyle {
-                            font_size: TEXT_SIZE,
-                            ..default()
-                        },
-                    )
-                    .with_text_justify(JustifyText::Center)
-                    .with_style(Style {
-                        position_type: PositionType::Absolute,
-                        bottom: Val::Px(5.0),
-                        left: Val::Px(5.0),
-                        ..default()
-                    }),
-                    ColorText,
-                ));
-            }
-            LineType::VerticalLine(x) => {
-                commands.spawn((
-                    MaterialMesh2dBundle {
-                        mesh: Mesh2dHandle(
-                            meshes.add(
-                                Mesh::new(
-                                    PrimitiveTopology::LineStrip,
-                                    RenderAssetUsages::default(),
-                                )
-                                .with_inserted_attribute(
-                                    Mesh::ATTRIBUTE_POSITION,
-                                    vec![
-                                        [*x, -window.height() * MAX_ZOOM_OUT, 0.0],
-                                        [*x, window.height() * MAX_ZOOM_OUT, 0.0],
-                                    ],
-                                ),
-                            ),
-                        ),
-                        material: materials.add(Color::rgb(1.0, 0.0, 0.0)),
-                        ..default()
-                    },
-                    Gizmo,
-                ));
-            }
-            LineType::ClearScreen => {
-                despawn_entities(&mut commands, &gizmo_query);
-                despawn_entities(&mut commands, &text_query);
-                despawn_entities(&mut commands, &convex_hull_query);
-            }
+    let colors = point_colors(point_coloring.0, &point_data.0);
+    for (point, mut material) in points.iter_mut() {
+        if let Some(color) = colors.get(point.0) {
+            *material = assets.point_material(&mut materials, *color);
         }
     }
+}
 
-    drawing_history.1 += 1;
+/// Spawns the points at the given offset, colored with the given [ColorMode] and tagged with their index in `points`.
+fn spawn_points(
+    commands: &mut Commands,
+    assets: &mut DrawingAssets,
+    materials: &mut Assets<ColorMaterial>,
+    points: &[Vec2],
+    offset: Vec2,
+    radius: f32,
+    mode: ColorMode,
+) -> Vec<Entity> {
+    points
+        .iter()
+        .zip(point_colors(mode, points))
+        .enumerate()
+        .map(|(index, (point, color))| {
+            spawn_point(commands, assets, materials, index, *point + offset, radius, color)
+        })
+        .collect()
 }
 
-/// Resource to store whether egui wants focus or not.
-#[derive(Resource, PartialEq)]
-struct EguiWantsFocus(bool);
+/// System parameter to access the settings of the tools in the inspector.
+#[derive(SystemParam)]
+struct InspectorTools<'w> {
+    hull_overlays: ResMut<'w, HullOverlays>,
+    hull_query: ResMut<'w, HullQuery>,
+    editing: EditingTools<'w>,
+    akl_toussaint: ResMut<'w, AklToussaint>,
+    approximate_hull: ResMut<'w, ApproximateHull>,
+    playback: ResMut<'w, Playback>,
+    gif_recording: ResMut<'w, GifRecording>,
+    step_durations: ResMut<'w, StepDurations>,
+    point_coloring: ResMut<'w, PointColoring>,
+    jitter: ResMut<'w, Jitter>,
+    point_import: ResMut<'w, PointImport>,
+    hull_export: ResMut<'w, HullExportFormat>,
+    display: DisplaySettings<'w>,
+    recursion_tree: ResMut<'w, RecursionTree>,
+    #[cfg(feature = "geo")]
+    geo_validation: ResMut<'w, GeoValidation>,
+}
 
-/// Checks if egui wants focus or not by checking if the pointer is over the egui area.
-fn check_egui_wants_focus(
-    mut contexts: Query<&mut bevy_egui::EguiContext>,
-    mut wants_focus: ResMut<EguiWantsFocus>,
-) {
-    let ctx = contexts.iter_mut().next();
+/// System parameter to generate the world and to access the outcome of the last generation.
+#[derive(SystemParam)]
+struct WorldGeneration<'w> {
+    generate: EventWriter<'w, GenerateWorld>,
+    errors: Res<'w, WorldErrors>,
+    seed: ResMut<'w, WorldSeed>,
+}
+
+/// System parameter to access the settings of the tools editing the points with the mouse.
+#[derive(SystemParam)]
+struct EditingTools<'w> {
+    grid_snap: ResMut<'w, GridSnap>,
+    polygon_drawing: ResMut<'w, PolygonDrawing>,
+    point_drag: ResMut<'w, PointDrag>,
+    point_deletion: ResMut<'w, PointDeletion>,
+}
+
+/// System parameter to access the settings of how the scene is displayed, along with the meshes and materials it
+/// is drawn with.
+#[derive(SystemParam)]
+struct DisplaySettings<'w> {
+    split_screen: ResMut<'w, SplitScreen>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<ColorMaterial>>,
+    assets: ResMut<'w, DrawingAssets>,
+    point_labels: ResMut<'w, PointLabels>,
+    world_grid: ResMut<'w, WorldGrid>,
+    fit_view: EventWriter<'w, FitView>,
+    follow_step: ResMut<'w, FollowStep>,
+}
+
+/// System parameter to access all the entities spawned into the world, along with the comparison drawing.
+#[derive(SystemParam)]
+struct SceneEntities<'w, 's> {
+    points: Query<'w, 's, Entity, (With<PointSingle>, Without<Comparison>)>,
+    polyline: Query<'w, 's, Entity, With<PolylineEdge>>,
+    convex_hulls: Query<'w, 's, Entity, With<ConvexHull>>,
+    gizmos: Query<'w, 's, Entity, With<Gizmo>>,
+    texts: Query<'w, 's, Entity, With<ColorText>>,
+    comparison_points: Query<'w, 's, Entity, (With<PointSingle>, With<Comparison>)>,
+    comparison_history: ResMut<'w, ComparisonHistory>,
+    rendered_steps: ResMut<'w, RenderedSteps>,
+}
+
+impl SceneEntities<'_, '_> {
+    /// Despawns everything the algorithms have drawn and stops the comparison, keeping the points.
+    fn clear_drawing(&mut self, commands: &mut Commands) {
+        despawn_entities(commands, &self.convex_hulls);
+        despawn_entities(commands, &self.gizmos);
+        despawn_entities(commands, &self.texts);
+        despawn_entities(commands, &self.comparison_points);
+        self.comparison_history.0.clear();
+        self.comparison_history.1 = 0;
+        *self.rendered_steps = RenderedSteps(0, 0);
+    }
+
+    /// Despawns the points and the [PolylineEdge]s between them, along with everything the algorithms have drawn.
+    fn clear_world(&mut self, commands: &mut Commands) {
+        self.clear_drawing(commands);
+        despawn_entities(commands, &self.points);
+        despawn_entities(commands, &self.polyline);
+    }
+}
+
+/// System parameter to access the mouse and the keyboard, along with the cursor in the world of the main camera.
+#[derive(SystemParam)]
+struct UserInput<'w, 's> {
+    mouse_button_input: Res<'w, ButtonInput<MouseButton>>,
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    window: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    camera_query: Query<'w, 's, MainCamera, Without<ComparisonCamera>>,
+    egui_wants_focus: Res<'w, EguiWantsFocus>,
+}
+
+/// Components of the main camera that map the cursor into the world
+type MainCamera = (&'static GlobalTransform, &'static Camera, &'static OrthographicProjection);
+
+impl UserInput<'_, '_> {
+    /// Returns the position of the cursor in the world along with the scale of the main camera, or nothing
+    /// while the cursor is outside of the window.
+    fn world_cursor(&self) -> Option<(Vec2, f32)> {
+        let (camera_transform, camera, projection) = self.camera_query.single();
+        let cursor = self
+            .window
+            .single()
+            .cursor_position()
+            .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))?;
+        Some((cursor, projection.scale))
+    }
+}
+
+/// System parameter to access the points along with their hull and the drawing history, which change together.
+#[derive(SystemParam)]
+struct WorldState<'w> {
+    point_data: ResMut<'w, PointData>,
+    hull_data: ResMut<'w, HullData>,
+    hull_cache: ResMut<'w, HullCache>,
+    drawing_history: ResMut<'w, DrawingHistory>,
+}
+
+/// System parameter to access what the world is generated from, along with the errors of the last generation.
+#[derive(SystemParam)]
+struct WorldSource<'w> {
+    distribution: Res<'w, Distribution>,
+    point_coloring: Res<'w, PointColoring>,
+    errors: ResMut<'w, WorldErrors>,
+    seed: ResMut<'w, WorldSeed>,
+}
+
+/// System parameter to access the drawing histories along with their playback and how far they are drawn.
+#[derive(SystemParam)]
+struct Animation<'w> {
+    drawing_history: ResMut<'w, DrawingHistory>,
+    comparison_history: ResMut<'w, ComparisonHistory>,
+    playback: ResMut<'w, Playback>,
+    rendered_steps: ResMut<'w, RenderedSteps>,
+}
+
+/// System parameter to time the steps of the animation and to announce the drawn ones.
+#[derive(SystemParam)]
+struct StepTiming<'w> {
+    time: Res<'w, Time>,
+    simulation_timer: ResMut<'w, SimulationTimer>,
+    step_durations: Res<'w, StepDurations>,
+    step_drawn: EventWriter<'w, StepDrawn>,
+}
+
+/// Filter of the entities drawn by the steps of the main drawing, which only last until the next step
+type StepFilter = (Or<(With<Gizmo>, With<ColorText>)>, Without<Comparison>);
+
+/// Filter of the hull edges of the main drawing, which persist until the screen is cleared
+type ConvexHullFilter = (With<ConvexHull>, Without<Comparison>);
+
+/// Filter of the entities drawn by the comparison, without its copies of the points
+type ComparisonFilter = (With<Comparison>, Without<PointSingle>);
+
+/// System parameter to access the entities drawn by the steps of both histories.
+#[derive(SystemParam)]
+struct DrawnEntities<'w, 's> {
+    steps: Query<'w, 's, Entity, StepFilter>,
+    convex_hulls: Query<'w, 's, Entity, ConvexHullFilter>,
+    comparison: Query<'w, 's, (Entity, Has<ConvexHull>), ComparisonFilter>,
+}
+
+/// System parameter to access the settings of the simulation in the inspector.
+#[derive(SystemParam)]
+struct SimulationSettings<'w> {
+    distribution: ResMut<'w, Distribution>,
+    simulation_timer: ResMut<'w, SimulationTimer>,
+    algorithm: ResMut<'w, Algorithm>,
+    orientation_epsilon: ResMut<'w, OrientationEpsilon>,
+}
+
+/// System parameter to access the settings the hull is computed with.
+#[derive(SystemParam)]
+struct HullSettings<'w> {
+    algorithm: Res<'w, Algorithm>,
+    orientation_epsilon: Res<'w, OrientationEpsilon>,
+}
+
+/// Initial setup function
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut point_data: ResMut<PointData>,
+    mut simulation_timer: ResMut<SimulationTimer>,
+    mut distribution: ResMut<Distribution>,
+    mut algorithm: ResMut<Algorithm>,
+    #[cfg(target_arch = "wasm32")] mut generate_world: EventWriter<GenerateWorld>,
+    #[cfg(target_arch = "wasm32")] mut world_seed: ResMut<WorldSeed>,
+) {
+    if let Some(settings) = AppSettings::load() {
+        point_data.2 = clamp_point_radius(settings.point_radius);
+        point_data.3 = settings.point_count.min(MAX_POINT_COUNT);
+        let time_step = clamp_time_step(settings.time_step);
+        simulation_timer.1 = time_step;
+        simulation_timer.0.set_duration(Duration::from_secs_f32(time_step));
+        distribution.0 = settings.distribution;
+        algorithm.0 = settings.algorithm;
+        point_data.4 = settings.manual_points;
+    }
+
+    // A shared link opens with the world it describes already generated
+    #[cfg(target_arch = "wasm32")]
+    {
+        let parameters = url_parameters::UrlParameters::read();
+        if let Some(point_radius) = parameters.point_radius {
+            point_data.2 = clamp_point_radius(point_radius);
+        }
+        if let Some(point_count) = parameters.point_count {
+            point_data.3 = point_count;
+        }
+        if let Some(time_step) = parameters.time_step {
+            simulation_timer.1 = time_step;
+            simulation_timer.0.set_duration(Duration::from_secs_f32(time_step));
+        }
+        if let Some(distribution_type) = parameters.distribution {
+            distribution.0 = distribution_type;
+        }
+        if let Some(algorithm_type) = parameters.algorithm {
+            algorithm.0 = algorithm_type;
+        }
+        if let Some(seed) = parameters.seed {
+            world_seed.0 = seed;
+            world_seed.1 = true;
+        }
+        if !parameters.is_empty() {
+            generate_world.send(GenerateWorld);
+        }
+    }
+
+    commands.insert_resource(DrawingAssets {
+        unit_line: meshes.add(quad_mesh(&[(0.0, 1.0)], 1.0)),
+        unit_circle: meshes.add(Circle { radius: 1.0 }),
+        point_materials: HashMap::new(),
+        line_styles: LineStyles::default(),
+        line_scale: 1.0,
+        hull: materials.add(Color::WHITE),
+        comparison_hull: materials.add(Color::CYAN),
+        temporary: materials.add(Color::rgb(0.44, 0.44, 0.44)),
+        vertical_lines: (0..=FADED_DEPTHS)
+            .map(|depth| materials.add(vertical_line_color(depth)))
+            .collect(),
+        collinear: materials.add(Color::rgb(1.0, 0.6, 0.0)),
+        hull_vertex: materials.add(Color::rgb(1.0, 0.4, 0.7)),
+        diameter: materials.add(Color::rgb(0.2, 1.0, 0.4)),
+        closing_edge: materials.add(Color::rgb(1.0, 0.9, 0.2)),
+        discarded: materials.add(Color::rgb(0.35, 0.35, 0.35)),
+        tangent: materials.add(Color::rgb(0.2, 0.9, 1.0)),
+        circle: materials.add(Color::rgb(0.8, 0.3, 1.0)),
+        region: materials.add(Color::rgba(0.3, 0.6, 1.0, 0.25)),
+        hull_fill: materials.add(Color::rgba(1.0, 1.0, 1.0, 0.15)),
+        arc: materials.add(Color::rgba(0.6, 0.6, 0.6, 0.3)),
+        best_arc: materials.add(Color::rgba(1.0, 0.6, 0.0, 0.45)),
+        highlights: [
+            HighlightKind::Current,
+            HighlightKind::Candidate,
+            HighlightKind::Rejected,
+            HighlightKind::Confirmed,
+        ]
+        .map(|kind| materials.add(highlight_color(kind))),
+        layers: (0..LAYER_COLORS).map(|layer| materials.add(layer_color(layer))).collect(),
+        polygons: (0..LAYER_COLORS)
+            .map(|layer| materials.add(layer_color(layer).with_a(POLYGON_ALPHA)))
+            .collect(),
+    });
+
+    commands
+        .spawn(Camera2dBundle::default())
+        .insert(PanCam {
+            grab_buttons: vec![MouseButton::Left, MouseButton::Middle], // which buttons should drag the camera
+            enabled: true,        // when false, controls are disabled. See toggle example.
+            zoom_to_cursor: true, // whether to zoom towards the mouse or the center of the screen
+            min_scale: 1.,        // prevent the camera from zooming too far in
+            max_scale: Some(MAX_ZOOM_OUT), // prevent the camera from zooming too far out
+            ..default()
+        })
+        .insert(RenderLayers::from_layers(&[0, COMPARISON_LAYER]));
+
+    // Drawn after the main camera without clearing it, it is only active while the screen is split
+    commands
+        .spawn(Camera2dBundle {
+            camera: Camera {
+                order: 1,
+                is_active: false,
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(RenderLayers::layer(COMPARISON_LAYER))
+        .insert(ComparisonCamera);
+}
+
+/// Stores the [AppSettings] once they changed and then stayed the same for [SETTINGS_SAVE_DELAY], so that they
+/// are restored by [setup] on the next launch.
+fn save_settings_system(
+    point_data: Res<PointData>,
+    simulation_timer: Res<SimulationTimer>,
+    distribution: Res<Distribution>,
+    algorithm: Res<Algorithm>,
+    mut saved: Local<Option<AppSettings>>,
+    mut changed: Local<Option<(AppSettings, Instant)>>,
+) {
+    let settings = AppSettings {
+        point_radius: point_data.2,
+        point_count: point_data.3,
+        time_step: simulation_timer.1,
+        distribution: distribution.0,
+        algorithm: algorithm.0,
+        manual_points: point_data.4,
+    };
+    if *saved == Some(settings) {
+        *changed = None;
+        return;
+    }
+    match *changed {
+        Some((pending, since)) if pending == settings => {
+            if since.elapsed() < SETTINGS_SAVE_DELAY {
+                return;
+            }
+        }
+        _ => {
+            *changed = Some((settings, Instant::now()));
+            return;
+        }
+    }
+
+    if let Err(e) = settings.save() {
+        warn!("Failed to save settings: {}", e);
+    }
+    *saved = Some(settings);
+    *changed = None;
+}
+
+/// Adds controls for pancam system. Namely disables the camera when egui wants focus or the mouse edits the points.
+fn pan_cam_system(
+    egui_wants_focus: Res<EguiWantsFocus>,
+    point_drag: Res<PointDrag>,
+    point_deletion: Res<PointDeletion>,
+    mut pan_cam: Query<&mut PanCam>,
+) {
+    for mut cam in pan_cam.iter_mut() {
+        // Dragging a point or a selection rectangle grabs the same button as panning
+        cam.enabled = !egui_wants_focus.0 && point_drag.2.is_none() && point_deletion.1.is_none();
+    }
+}
+
+/// Splits the screen between the two cameras while comparing both algorithms, if the [SplitScreen] is enabled.
+///
+/// The main camera keeps the left half and the [ComparisonCamera] follows it in the right half, shifted by the
+/// offset of the comparison, so both drawings stay aligned while panning and zooming.
+fn split_screen_system(
+    mut commands: Commands,
+    split_screen: Res<SplitScreen>,
+    comparison_history: Res<ComparisonHistory>,
+    added_comparisons: Query<Entity, Added<Comparison>>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut main_camera: Query<
+        (&mut Camera, &Transform, &OrthographicProjection, &mut RenderLayers),
+        Without<ComparisonCamera>,
+    >,
+    mut comparison_camera: Query<
+        (&mut Camera, &mut Transform, &mut OrthographicProjection),
+        With<ComparisonCamera>,
+    >,
+) {
+    for entity in added_comparisons.iter() {
+        commands.entity(entity).insert(RenderLayers::layer(COMPARISON_LAYER));
+    }
+
+    let (mut camera, transform, projection, mut layers) = main_camera.single_mut();
+    let (mut right_camera, mut right_transform, mut right_projection) = comparison_camera.single_mut();
+    let resolution = &window.single().resolution;
+    let size = UVec2::new(resolution.physical_width(), resolution.physical_height());
+    let split = split_screen.0 && !comparison_history.0.is_empty() && size.x >= 2 && size.y > 0;
+
+    right_camera.is_active = split;
+    if !split {
+        camera.viewport = None;
+        *layers = RenderLayers::from_layers(&[0, COMPARISON_LAYER]);
+        return;
+    }
+
+    let half = UVec2::new(size.x / 2, size.y);
+    camera.viewport = Some(Viewport {
+        physical_position: UVec2::ZERO,
+        physical_size: half,
+        ..default()
+    });
+    right_camera.viewport = Some(Viewport {
+        physical_position: UVec2::new(half.x, 0),
+        physical_size: UVec2::new(size.x - half.x, size.y),
+        ..default()
+    });
+    *layers = RenderLayers::layer(0);
+
+    right_transform.translation = transform.translation + Vec3::new(comparison_history.2, 0.0, 0.0);
+    right_projection.scale = projection.scale;
+}
+
+/// Eases the main camera towards the bounding box of the last drawn step with any geometry, if [FollowStep] is enabled.
+///
+/// Steps without an area, e.g. the comments or a lone median, keep the camera on the step before them.
+fn follow_step_system(
+    time: Res<Time>,
+    follow_step: Res<FollowStep>,
+    drawing_history: Res<DrawingHistory>,
+    point_data: Res<PointData>,
+    mut camera: Query<(&Camera, &mut Transform, &mut OrthographicProjection), Without<ComparisonCamera>>,
+) {
+    if !follow_step.0 {
+        return;
+    }
+
+    let drawn = drawing_history.1.min(drawing_history.0.len());
+    let radius = clamp_point_radius(point_data.2);
+    let Some((min, max)) = drawing_history.0[..drawn]
+        .iter()
+        .rev()
+        .find_map(|step| step_bounds(step, radius))
+    else {
+        return;
+    };
+
+    let (camera, mut transform, mut projection) = camera.single_mut();
+    let Some(size) = camera.logical_viewport_size() else {
+        return;
+    };
+    let extent = max - min + Vec2::splat(2.0 * radius);
+    let scale = ((extent / size).max_element() * FOLLOW_MARGIN).clamp(1.0, MAX_ZOOM_OUT);
+    let center = (min + max) / 2.0;
+
+    // Exponential easing, which moves by the same fraction of the remaining way in every second
+    let t = 1.0 - (-FOLLOW_SPEED * time.delta_seconds()).exp();
+    let translation = transform.translation.truncate().lerp(center, t);
+    transform.translation = translation.extend(transform.translation.z);
+    projection.scale += (scale - projection.scale) * t;
+}
+
+/// Controls the keyboard input for the simulation.
+///
+/// `R` regenerates the world with new random points, drawing a new [WorldSeed] even when it is kept, `Space`
+/// pauses and resumes the animation, the left and right arrows pause it and step backward and forward, `F12`
+/// saves a screenshot, `Ctrl+D` clears the point editor and `Ctrl+V` pastes the clipboard into it.
+///
+/// The shortcuts are ignored while egui is taking keyboard input, so that typing in the
+/// point editor doesn't clear it or paste the clipboard twice.
+fn keyboard_input_system(
+    user_input: UserInput,
+    mut point_data: ResMut<PointData>,
+    egui_resources: InputResources,
+    world: WorldGeneration,
+    animation: Animation,
+    mut screenshots: ResMut<ScreenshotManager>,
+    window: Query<Entity, With<PrimaryWindow>>,
+) {
+    let UserInput { keyboard_input: input, egui_wants_focus, .. } = user_input;
+    let WorldGeneration { generate: mut generate_world, seed: mut world_seed, .. } = world;
+    let Animation { mut drawing_history, mut comparison_history, mut playback, .. } = animation;
+    if egui_wants_focus.1 {
+        return;
+    }
+
+    let ctrl = input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+
+    if !ctrl && input.just_pressed(KeyCode::KeyR) {
+        world_seed.0 = WorldSeed::random_seed();
+        generate_world.send(GenerateWorld);
+    }
+
+    if !ctrl && input.just_pressed(KeyCode::Space) {
+        playback.0 = !playback.0;
+    }
+
+    if input.just_pressed(KeyCode::ArrowLeft) {
+        playback.0 = true;
+        step_back(&mut drawing_history, &mut comparison_history);
+    }
+
+    if input.just_pressed(KeyCode::ArrowRight) {
+        playback.0 = true;
+        playback.1 = true;
+    }
+
+    if input.just_pressed(KeyCode::F12) {
+        save_screenshot(&mut screenshots, window.single());
+    }
+
+    if ctrl && input.just_pressed(KeyCode::KeyD) {
+        point_data.1.clear();
+    }
+
+    if ctrl && input.just_pressed(KeyCode::KeyV) {
+        let clipboard = egui_resources.egui_clipboard;
+        match clipboard.get_contents() {
+            Some(contents) => {
+                point_data.1 += "\n";
+                point_data.1 += &contents;
+            }
+            None => warn!("Clipboard is empty"),
+        }
+    }
+}
+
+/// Rebuilds the world on every [GenerateWorld] event, replacing the points and everything drawn.
+///
+/// The points are generated from the [WorldSeed], which is replaced by a random one unless it's kept.
+fn generate_world_system(
+    mut generate_world: EventReader<GenerateWorld>,
+    mut commands: Commands,
+    state: WorldState,
+    source: WorldSource,
+    display: DisplaySettings,
+    mut scene: SceneEntities,
+) {
+    let WorldState { mut point_data, mut hull_data, mut hull_cache, mut drawing_history } = state;
+    let WorldSource { distribution, point_coloring, errors: mut world_errors, seed: mut world_seed } = source;
+    let DisplaySettings { mut assets, mut materials, mut fit_view, .. } = display;
+    // Several events in one frame would only rebuild the same world again
+    if generate_world.read().count() == 0 {
+        return;
+    }
+
+    if !world_seed.1 {
+        world_seed.0 = WorldSeed::random_seed();
+    }
+    world_seed.2 = StdRng::seed_from_u64(world_seed.0);
+
+    world_errors.0.clear();
+    let points = if point_data.1.is_empty() {
+        match generate_points(&distribution, point_data.3, &mut world_seed.2) {
+            Ok(points) => points,
+            Err(error) => {
+                // Keep the current world, so a typo in an expression doesn't wipe it
+                world_errors.0.push(error);
+                return;
+            }
+        }
+    } else {
+        let (points, errors) = parse_input(&point_data.1);
+        world_errors.0 = errors;
+        points
+    };
+
+    scene.clear_world(&mut commands);
+    point_data.0.clear();
+    drawing_history.0.clear();
+    hull_data.0.clear();
+    hull_cache.0 = None;
+
+    spawn_points(&mut commands, &mut assets, &mut materials, &points, Vec2::ZERO, point_data.2, point_coloring.0);
+    point_data.0 = points;
+    fit_view.send(FitView);
+}
+
+/// Moves and zooms the main camera on every [FitView] event, so that the bounding box of the points fills its view.
+///
+/// The zoom stays within the limits of the [PanCam].
+fn fit_view_system(
+    mut fit_view: EventReader<FitView>,
+    point_data: Res<PointData>,
+    mut camera: Query<(&Camera, &mut Transform, &mut OrthographicProjection), Without<ComparisonCamera>>,
+) {
+    if fit_view.read().count() == 0 || point_data.0.is_empty() {
+        return;
+    }
+
+    let (camera, mut transform, mut projection) = camera.single_mut();
+    let Some(size) = camera.logical_viewport_size() else {
+        return;
+    };
+    let (min, max) = point_data
+        .0
+        .iter()
+        .fold((Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)), |(min, max), p| (min.min(*p), max.max(*p)));
+    let extent = max - min + Vec2::splat(2.0 * clamp_point_radius(point_data.2));
+
+    transform.translation = ((min + max) / 2.0).extend(transform.translation.z);
+    projection.scale = ((extent / size).max_element() * FIT_MARGIN).clamp(1.0, MAX_ZOOM_OUT);
+}
+
+/// Describes where a drawing history is rendered on the screen.
+struct Lane {
+    /// Translation applied to every primitive of the history
+    offset: Vec2,
+    /// Material of the hull edges
+    hull_material: Handle<ColorMaterial>,
+    /// Whether the entities belong to the [ComparisonHistory]
+    comparison: bool,
+}
+
+/// Creates a mesh of quads along the x axis, one for each of the given ranges, centered on the axis with the given width
+fn quad_mesh(ranges: &[(f32, f32)], width: f32) -> Mesh {
+    let half = width / 2.0;
+    let vertices = ranges
+        .iter()
+        .flat_map(|&(start, end)| {
+            [[start, -half], [end, -half], [end, half], [start, -half], [end, half], [start, half]]
+        })
+        .map(|[x, y]| [x, y, 0.0])
+        .collect::<Vec<_>>();
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
+}
+
+/// Creates a mesh of the given width along the closed or open polyline through the vertices, one quad per edge
+fn polyline_mesh(vertices: &[Vec2], width: f32, z: f32) -> Mesh {
+    let half = width / 2.0;
+    let vertices = vertices
+        .windows(2)
+        .flat_map(|edge| {
+            let normal = (edge[1] - edge[0]).normalize_or_zero().perp() * half;
+            let (a, b, c, d) = (edge[0] - normal, edge[1] - normal, edge[1] + normal, edge[0] + normal);
+            [a, b, c, a, c, d]
+        })
+        .map(|corner| [corner.x, corner.y, z])
+        .collect::<Vec<_>>();
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
+}
+
+/// Returns the vertices of the circle with the given center and radius, the first one repeated at the end
+fn circle_vertices(center: Vec2, radius: f32) -> Vec<Vec2> {
+    (0..=CIRCLE_SEGMENTS)
+        .map(|i| center + Vec2::from_angle(std::f32::consts::TAU * i as f32 / CIRCLE_SEGMENTS as f32) * radius)
+        .collect()
+}
+
+/// Creates a triangle fan covering the wedge of a [LineType::Arc], with as many segments as the circles would have
+fn wedge_mesh(center: Vec2, radius: f32, start: f32, sweep: f32, z: f32) -> Mesh {
+    let segments = ((sweep.abs() / std::f32::consts::TAU * CIRCLE_SEGMENTS as f32).ceil() as usize).max(1);
+    let corner = |i: usize| {
+        let angle = start + sweep * i as f32 / segments as f32;
+        [center.x + radius * angle.cos(), center.y + radius * angle.sin(), z]
+    };
+    // Clockwise sweeps are walked backwards, so every triangle stays counterclockwise
+    let vertices = (0..segments)
+        .flat_map(|i| {
+            let (a, b) = if sweep < 0.0 { (i + 1, i) } else { (i, i + 1) };
+            [[center.x, center.y, z], corner(a), corner(b)]
+        })
+        .collect::<Vec<_>>();
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
+}
+
+/// Creates a triangle fan covering the convex polygon with the given corners, in either order
+fn polygon_mesh(corners: &[Vec2], z: f32) -> Mesh {
+    let area: f32 = (0..corners.len())
+        .map(|i| corners[i].perp_dot(corners[(i + 1) % corners.len()]))
+        .sum();
+    let mut corners = corners.to_vec();
+    if area < 0.0 {
+        corners.reverse();
+    }
+    let vertices = (1..corners.len().saturating_sub(1))
+        .flat_map(|i| [corners[0], corners[i], corners[i + 1]])
+        .map(|corner| [corner.x, corner.y, z])
+        .collect::<Vec<_>>();
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
+}
+
+/// Creates a triangle mesh with the given corners, which are put in counterclockwise order
+fn triangle_mesh(a: Vec2, b: Vec2, c: Vec2, z: f32) -> Mesh {
+    let (b, c) = if (b - a).perp_dot(c - a) < 0.0 { (c, b) } else { (b, c) };
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[a.x, a.y, z], [b.x, b.y, z], [c.x, c.y, z]])
+}
+
+/// Returns the transform that maps the unit line of [DrawingAssets] onto the segment from `a` to `b`.
+fn segment_transform(a: Vec2, b: Vec2, z: f32) -> Transform {
+    let direction = b - a;
+    Transform {
+        translation: a.extend(z),
+        rotation: Quat::from_rotation_z(direction.y.atan2(direction.x)),
+        scale: Vec3::new(direction.length(), 1.0, 1.0),
+    }
+}
+
+/// Returns the mesh and the transform that draw the segment from `a` to `b` with the given style.
+///
+/// Solid segments stretch the unit line of [DrawingAssets], while dashed ones get a mesh of their own,
+/// as stretching would stretch their dashes as well.
+fn styled_segment(
+    meshes: &mut Assets<Mesh>,
+    assets: &DrawingAssets,
+    (a, b): (Vec2, Vec2),
+    z: f32,
+    style: LineStyle,
+) -> (Mesh2dHandle, Transform) {
+    let mut transform = segment_transform(a, b, z);
+    let width = style.width * assets.line_scale;
+    if !style.dashed {
+        transform.scale.y = width;
+        return (Mesh2dHandle(assets.unit_line.clone()), transform);
+    }
+
+    let length = transform.scale.x;
+    // The dashes grow on long lines, e.g. the vertical lines, so that their number stays bounded
+    let period = (width * DASH_PERIOD).max(length / MAX_DASHES as f32);
+    let dashes: Vec<_> = (0..(length / period).ceil() as usize)
+        .map(|i| (i as f32 * period, (i as f32 * period + period / 2.0).min(length)))
+        .collect();
+    transform.scale = Vec3::ONE;
+    (Mesh2dHandle(meshes.add(quad_mesh(&dashes, width))), transform)
+}
+
+/// Redraws the drawing histories once the zoom of the main camera changed by more than [LINE_SCALE_TOLERANCE]
+/// since they were drawn, which keeps the widths of the [LineStyle]s close to their size in pixels.
+fn line_scale_system(
+    camera_query: Query<&OrthographicProjection, (With<Camera>, Without<ComparisonCamera>)>,
+    mut assets: ResMut<DrawingAssets>,
+    mut rendered_steps: ResMut<RenderedSteps>,
+) {
+    let Ok(projection) = camera_query.get_single() else {
+        return;
+    };
+    let ratio = projection.scale / assets.line_scale;
+    if (1.0 / LINE_SCALE_TOLERANCE..=LINE_SCALE_TOLERANCE).contains(&ratio) {
+        return;
+    }
+
+    assets.line_scale = projection.scale;
+    *rendered_steps = RenderedSteps::default();
+}
+
+/// Returns the color of a [LineType::VerticalLine] found at the given depth of the KPS recursion.
+///
+/// The lines fade with the depth, so the medians of the first splits stand out from the
+/// many lines of the deeper ones.
+fn vertical_line_color(depth: usize) -> Color {
+    let alpha = VERTICAL_LINE_FADE.powi(depth as i32).max(MIN_VERTICAL_LINE_ALPHA);
+    Color::rgba(1.0, 0.0, 0.0, alpha)
+}
+
+/// Returns the depth a [LineType] is drawn at.
+///
+/// Each kind of primitive gets its own depth so that overlapping primitives don't z-fight and
+/// the hull is always drawn on top of the temporary lines.
+fn line_depth(line: &LineType) -> f32 {
+    match line {
+        LineType::Region(..) | LineType::Arc(..) | LineType::Polygon(..) | LineType::FilledHull(_) => {
+            REGION_DEPTH
+        }
+        LineType::Temporary(..) | LineType::VerticalLine(..) => TEMPORARY_DEPTH,
+        LineType::PartOfHull(..) | LineType::LayerEdge(..) | LineType::Circle(..) => HULL_DEPTH,
+        LineType::Diameter(..) | LineType::ClosingEdge(..) | LineType::Tangent(..) => OVERLAY_DEPTH,
+        LineType::CollinearPoint(_)
+        | LineType::HullVertex(_)
+        | LineType::DiscardedPoint(_)
+        | LineType::HighlightPoint(..) => HIGHLIGHT_DEPTH,
+        LineType::TextComment(_) | LineType::ClearScreen | LineType::Milestone(_) => 0.0,
+    }
+}
+
+/// Spawns the entities for a single step of a drawing history, as declared in [LineType] enum.
+///
+/// [LineType::ClearScreen] is handled by the caller, as it needs to know which entities to despawn.
+/// [LineType::Milestone]s draw nothing.
+fn draw_step<'a>(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    assets: &DrawingAssets,
+    step: impl IntoIterator<Item = &'a LineType>,
+    lane: &Lane,
+    window_height: f32,
+    point_radius: f32,
+) {
+    let point_radius = clamp_point_radius(point_radius);
+    let offset = lane.offset;
+    for line in step {
+        let z = line_depth(line);
+        let mut entity = match line {
+            LineType::PartOfHull(a, b) => {
+                let (a, b) = (*a + offset, *b + offset);
+                let (mesh, transform) = styled_segment(meshes, assets, (a, b), z, assets.line_styles.hull);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh,
+                        material: lane.hull_material.clone(),
+                        transform,
+                        ..default()
+                    },
+                    ConvexHull,
+                ))
+            }
+            LineType::LayerEdge(a, b, layer) => {
+                let (a, b) = (*a + offset, *b + offset);
+                let (mesh, transform) = styled_segment(meshes, assets, (a, b), z, assets.line_styles.hull);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh,
+                        material: assets.layers[layer % LAYER_COLORS].clone(),
+                        transform,
+                        ..default()
+                    },
+                    ConvexHull,
+                ))
+            }
+            LineType::Temporary(a, b) => {
+                let (a, b) = (*a + offset, *b + offset);
+                let (mesh, transform) = styled_segment(meshes, assets, (a, b), z, assets.line_styles.temporary);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh,
+                        material: assets.temporary.clone(),
+                        transform,
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::TextComment(comment) => {
+                let style = if lane.comparison {
+                    Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(5.0),
+                        right: Val::Px(5.0),
+                        ..default()
+                    }
+                } else {
+                    Style {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(5.0),
+                        left: Val::Px(5.0),
+                        ..default()
+                    }
+                };
+                commands.spawn((
+                    TextBundle::from_section(
+                        comment,
+                        TextStyle {
+                            font_size: TEXT_SIZE,
+                            ..default()
+                        },
+                    )
+                    .with_text_justify(JustifyText::Center)
+                    .with_style(style),
+                    ColorText,
+                ))
+            }
+            LineType::VerticalLine(x, depth) => {
+                let x = *x + offset.x;
+                let (a, b) = (
+                    Vec2::new(x, -window_height * MAX_ZOOM_OUT),
+                    Vec2::new(x, window_height * MAX_ZOOM_OUT),
+                );
+                let (mesh, transform) = styled_segment(meshes, assets, (a, b), z, assets.line_styles.vertical_line);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh,
+                        material: assets.vertical_line(*depth),
+                        transform,
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::CollinearPoint(point) => {
+                let point = *point + offset;
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(assets.unit_circle.clone()),
+                        material: assets.collinear.clone(),
+                        transform: circle_transform(point, point_radius * 1.5, z),
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::HighlightPoint(point, kind) => {
+                let vertices = circle_vertices(*point + offset, point_radius * HALO_RADIUS);
+                let mesh = polyline_mesh(&vertices, assets.line_styles.temporary.width * assets.line_scale, z);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(meshes.add(mesh)),
+                        material: assets.highlight(*kind),
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::DiscardedPoint(point) => {
+                let point = *point + offset;
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(assets.unit_circle.clone()),
+                        material: assets.discarded.clone(),
+                        transform: circle_transform(point, point_radius, z),
+                        ..default()
+                    },
+                    ConvexHull,
+                ))
+            }
+            LineType::HullVertex(point) => {
+                let point = *point + offset;
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(assets.unit_circle.clone()),
+                        material: assets.hull_vertex.clone(),
+                        transform: circle_transform(point, point_radius * 2.0, z),
+                        ..default()
+                    },
+                    ConvexHull,
+                ))
+            }
+            LineType::Diameter(a, b) => {
+                let (a, b) = (*a + offset, *b + offset);
+                let (mesh, transform) = styled_segment(meshes, assets, (a, b), z, assets.line_styles.temporary);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh,
+                        material: assets.diameter.clone(),
+                        transform,
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::ClosingEdge(a, b) => {
+                let (a, b) = (*a + offset, *b + offset);
+                let (mesh, transform) = styled_segment(meshes, assets, (a, b), z, assets.line_styles.hull);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh,
+                        material: assets.closing_edge.clone(),
+                        transform,
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::Tangent(a, b) => {
+                let (a, b) = (*a + offset, *b + offset);
+                let (mesh, transform) = styled_segment(meshes, assets, (a, b), z, assets.line_styles.temporary);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh,
+                        material: assets.tangent.clone(),
+                        transform,
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::Circle(center, radius) => {
+                let vertices = circle_vertices(*center + offset, *radius);
+                let mesh = polyline_mesh(&vertices, assets.line_styles.temporary.width * assets.line_scale, z);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(meshes.add(mesh)),
+                        material: assets.circle.clone(),
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::Arc(center, radius, start, sweep, best) => {
+                let mesh = wedge_mesh(*center + offset, *radius, *start, *sweep, z);
+                let material = if *best { &assets.best_arc } else { &assets.arc };
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(meshes.add(mesh)),
+                        material: material.clone(),
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::FilledHull(vertices) => {
+                let vertices: Vec<_> = vertices.iter().map(|vertex| *vertex + offset).collect();
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(meshes.add(polygon_mesh(&vertices, z))),
+                        material: assets.hull_fill.clone(),
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::Polygon(corners, layer) => {
+                let corners: Vec<_> = corners.iter().map(|corner| *corner + offset).collect();
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(meshes.add(polygon_mesh(&corners, z))),
+                        material: assets.polygons[layer % LAYER_COLORS].clone(),
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::Region(a, b, c) => {
+                let mesh = triangle_mesh(*a + offset, *b + offset, *c + offset, z);
+                commands.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: Mesh2dHandle(meshes.add(mesh)),
+                        material: assets.region.clone(),
+                        ..default()
+                    },
+                    Gizmo,
+                ))
+            }
+            LineType::ClearScreen | LineType::Milestone(_) => continue,
+        };
+
+        if lane.comparison {
+            entity.insert(Comparison);
+        }
+    }
+}
+
+/// Moves both histories back by one step, [graphics_drawing] then rebuilds their scenes
+fn step_back(drawing_history: &mut DrawingHistory, comparison_history: &mut ComparisonHistory) {
+    drawing_history.1 = drawing_history.1.min(drawing_history.0.len()).saturating_sub(1);
+    comparison_history.1 = comparison_history.1.min(comparison_history.0.len()).saturating_sub(1);
+}
+
+/// Draws the graphics of the [DrawingHistory] and the [ComparisonHistory].
+///
+/// Both histories advance on the same [SimulationTimer], each one stops once all of its steps are drawn.
+/// While the [Playback] is paused the timer stands still and the histories only advance on a requested step.
+/// Every drawn step sets the duration of the timer to how long it is shown, see [StepDurations].
+///
+/// The scenes of both histories always match their current index, which can also be changed from the ui.
+/// When an index moves forward only the new steps are drawn, when it moves backward the scene is rebuilt
+/// by replaying the history up to the index.
+fn graphics_drawing(
+    mut commands: Commands,
+    display: DisplaySettings,
+    drawn: DrawnEntities,
+    animation: Animation,
+    timing: StepTiming,
+    window: Query<&mut Window, With<PrimaryWindow>>,
+    point_data: Res<PointData>,
+) {
+    let DisplaySettings { mut meshes, assets, .. } = display;
+    let DrawnEntities { steps: step_query, convex_hulls: convex_hull_query, comparison: comparison_query } = drawn;
+    let Animation { mut drawing_history, mut comparison_history, mut playback, mut rendered_steps } = animation;
+    let StepTiming { time, mut simulation_timer, step_durations, mut step_drawn } = timing;
+    let window = window.single();
+    let drawing_done = drawing_history.1 >= drawing_history.0.len();
+    let comparison_done = comparison_history.1 >= comparison_history.0.len();
+
+    if (!drawing_done || !comparison_done) && !playback.0 {
+        simulation_timer.0.tick(time.delta());
+    }
+    // The timer keeps its finished state while paused, so it only counts when playing
+    let step_finished = std::mem::take(&mut playback.1) || (!playback.0 && simulation_timer.0.finished());
+
+    if !drawing_done && step_finished {
+        drawing_history.1 += 1;
+    }
+
+    let RenderedSteps(rendered_step, rendered_comparison_step) = &mut *rendered_steps;
+    let target = drawing_history.1.min(drawing_history.0.len());
+    if *rendered_step != target {
+        despawn_entities(&mut commands, &step_query);
+
+        let start = if target < *rendered_step { 0 } else { *rendered_step };
+        let steps = &drawing_history.0[start..target];
+        let clear_screen = steps.iter().flatten().any(|line| matches!(line, LineType::ClearScreen));
+        if start == 0 || clear_screen {
+            despawn_entities(&mut commands, &convex_hull_query);
+        }
+
+        let lane = Lane {
+            offset: Vec2::ZERO,
+            hull_material: assets.hull.clone(),
+            comparison: false,
+        };
+        draw_step(
+            &mut commands,
+            &mut meshes,
+            &assets,
+            current_frame(steps, steps.len()),
+            &lane,
+            window.height(),
+            point_data.2,
+        );
+        *rendered_step = target;
+
+        let duration = target
+            .checked_sub(1)
+            .map_or(0.0, |last| step_durations.duration(simulation_timer.1, &drawing_history.0[last]));
+        step_drawn.send(StepDrawn(target, duration));
+    }
+
+    if !comparison_done && step_finished {
+        comparison_history.1 += 1;
+    }
+
+    let comparison_target = comparison_history.1.min(comparison_history.0.len());
+    if *rendered_comparison_step != comparison_target {
+        let start = if comparison_target < *rendered_comparison_step { 0 } else { *rendered_comparison_step };
+        let steps = &comparison_history.0[start..comparison_target];
+        let clear_screen = steps.iter().flatten().any(|line| matches!(line, LineType::ClearScreen));
+        // Hull edges of the comparison persist, everything else only lives for one step
+        for (entity, is_hull) in comparison_query.iter() {
+            if start == 0 || clear_screen || !is_hull {
+                commands.entity(entity).despawn();
+            }
+        }
+
+        let lane = Lane {
+            offset: Vec2::new(comparison_history.2, 0.0),
+            hull_material: assets.comparison_hull.clone(),
+            comparison: true,
+        };
+        draw_step(
+            &mut commands,
+            &mut meshes,
+            &assets,
+            current_frame(steps, steps.len()),
+            &lane,
+            window.height(),
+            point_data.2,
+        );
+        *rendered_comparison_step = comparison_target;
+    }
+
+    if step_finished {
+        // The step that was just drawn decides how long it lingers, the comparison's once the drawing is done
+        let shown = if drawing_done {
+            comparison_history.0[..comparison_target].last()
+        } else {
+            drawing_history.0[..target].last()
+        };
+        if let Some(step) = shown {
+            let duration = step_durations.duration(simulation_timer.1, step);
+            simulation_timer.0.set_duration(Duration::from_secs_f32(duration));
+        }
+    }
+}
+
+/// Resource to store whether egui wants focus or not.
+///
+/// The first field is set when the pointer is over an egui area and the second one
+/// when egui is taking keyboard input, e.g. while a text field is being edited.
+#[derive(Resource, PartialEq)]
+struct EguiWantsFocus(bool, bool);
+
+/// Checks if egui wants focus or not by checking if the pointer is over the egui area
+/// and whether a text field is taking keyboard input.
+fn check_egui_wants_focus(
+    mut contexts: Query<&mut bevy_egui::EguiContext>,
+    mut wants_focus: ResMut<EguiWantsFocus>,
+) {
+    let ctx = contexts.iter_mut().next();
     let new_wants_focus = if let Some(ctx) = ctx {
         let ctx = ctx.into_inner().get_mut();
-        ctx.is_pointer_over_area()
+        EguiWantsFocus(ctx.is_pointer_over_area(), ctx.wants_keyboard_input())
     } else {
-        false
+        EguiWantsFocus(false, false)
     };
-    wants_focus.set_if_neq(EguiWantsFocus(new_wants_focus));
+    wants_focus.set_if_neq(new_wants_focus);
 }
 
 /// System to add points to the world by clicking.
+///
+/// When the [HullQuery] tool is enabled the clicked point is tested against the hull instead of being added.
+/// Otherwise, if an up to date hull was already computed, the point is inserted into it with [insert_point],
+/// and if not the hull of all the points is computed with the selected [Algorithm]. The updated hull is drawn
+/// as a new step of the [DrawingHistory].
 fn mouse_position_system(
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut point_data: ResMut<PointData>,
-    mouse_button_input: Res<ButtonInput<MouseButton>>,
-    mut window: Query<&mut Window, With<PrimaryWindow>>,
-    camera_query: Query<(&GlobalTransform, &Camera), With<Camera>>,
-    egui_wants_focus: Res<EguiWantsFocus>,
+    input: UserInput,
+    state: WorldState,
+    settings: HullSettings,
+    query_marker: Query<Entity, With<QueryMarker>>,
+    mut tools: InspectorTools,
 ) {
-    if egui_wants_focus.0 {
+    let WorldState { mut point_data, mut hull_data, mut hull_cache, mut drawing_history } = state;
+    let HullSettings { algorithm, orientation_epsilon } = settings;
+    // The press that grabbed a point or started a selection doesn't add one
+    let editing = tools.editing.point_drag.2.is_some() || tools.editing.point_deletion.1.is_some();
+    if input.egui_wants_focus.0 || editing {
         return;
     }
 
-    if !point_data.4 {
+    if !point_data.4 && !tools.hull_query.0 && !tools.editing.polygon_drawing.0 {
         return;
     }
 
-    let window = window.single_mut();
-    let (camera_transform, camera) = camera_query.single();
+    if input.mouse_button_input.just_pressed(MouseButton::Left) {
+        let Some((world_position, _)) = input.world_cursor() else {
+            return;
+        };
 
-    if mouse_button_input.just_pressed(MouseButton::Left) {
-        let world_position = window
-            .cursor_position()
-            .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
-            .map(|ray| ray.origin.truncate())
-            .unwrap();
+        if tools.hull_query.0 {
+            let inside = point_in_hull(&hull_data.0, world_position, orientation_epsilon.0);
+            tools.hull_query.1 = Some(inside);
+
+            despawn_entities(&mut commands, &query_marker);
+            let color = if inside {
+                Color::rgb(0.0, 1.0, 0.0)
+            } else {
+                Color::rgb(1.0, 0.0, 0.0)
+            };
+            let radius = clamp_point_radius(point_data.2) * 1.5;
+            commands.spawn((
+                MaterialMesh2dBundle {
+                    mesh: Mesh2dHandle(tools.display.assets.unit_circle.clone()),
+                    material: tools.display.assets.point_material(&mut tools.display.materials, color),
+                    transform: circle_transform(world_position, radius, HIGHLIGHT_DEPTH),
+                    ..default()
+                },
+                QueryMarker,
+                Gizmo,
+            ));
+            return;
+        }
 
+        let world_position = if tools.editing.grid_snap.0 && tools.editing.grid_snap.1 > 0.0 {
+            (world_position / tools.editing.grid_snap.1).round() * tools.editing.grid_snap.1
+        } else {
+            world_position
+        };
+
+        let index = point_data.0.len();
         point_data
             .0
             .push(Vec2::new(world_position.x, world_position.y));
+        hull_cache.0 = None;
         point_data.3 += 1;
 
-        let color = Color::WHITE;
+        let color = point_color(tools.point_coloring.0, &point_data.0, index);
+        spawn_point(
+            &mut commands,
+            &mut tools.display.assets,
+            &mut tools.display.materials,
+            index,
+            world_position,
+            point_data.2,
+            color,
+        );
 
-        commands.spawn((
-            MaterialMesh2dBundle {
-                mesh: Mesh2dHandle(meshes.add(Circle {
-                    radius: point_data.2,
-                })),
-                material: materials.add(color),
-                transform: Transform::from_xyz(world_position.x, world_position.y, 0.0),
-                ..default()
-            },
-            PointSingle,
-        ));
+        if tools.editing.polygon_drawing.0 && index > 0 {
+            let edge = (point_data.0[index - 1], world_position);
+            let assets = &tools.display.assets;
+            let (mesh, transform) =
+                styled_segment(&mut tools.display.meshes, assets, edge, TEMPORARY_DEPTH, assets.line_styles.temporary);
+            commands.spawn((
+                MaterialMesh2dBundle {
+                    mesh,
+                    material: assets.temporary.clone(),
+                    transform,
+                    ..default()
+                },
+                PolylineEdge,
+            ));
+        }
+
+        let comment = if !hull_data.0.is_empty() && !hull_data.4 {
+            insert_point(&mut hull_data.0, world_position, orientation_epsilon.0);
+            format!("Inserted {} into the hull", world_position)
+        } else {
+            let start = Instant::now();
+            hull_data.0 = compute_hull(point_data.0.clone(), algorithm.0, orientation_epsilon.0);
+            hull_data.2 = start.elapsed();
+            hull_data.4 = false;
+            format!("Computed the hull of the {} points", point_data.0.len())
+        };
+        hull_data.1 = collinear_hull_points(&point_data.0, &hull_data.0, orientation_epsilon.0).len();
+        hull_data.3 = hull_diameter(&hull_data.0).2;
+
+        let step = hull_step(&hull_data.0, comment);
+        drawing_history.0.push(step);
+        drawing_history.1 = drawing_history.0.len();
+    }
+}
+
+/// Returns a step that clears the screen and draws the whole hull, for the changes of the points made by hand
+fn hull_step(hull: &[Vec2], comment: String) -> Vec<LineType> {
+    let mut step = vec![LineType::ClearScreen];
+    step.extend((0..hull.len()).map(|i| LineType::PartOfHull(hull[i], hull[(i + 1) % hull.len()])));
+    step.extend(hull_vertices(hull));
+    step.push(LineType::TextComment(comment));
+    step
+}
+
+/// Lists all [WorldErrors] in a window, which dismisses them when closed.
+///
+/// The errors of the parsed text start with their line number, so the failed rows can be found in the editor.
+fn world_errors_window(mut contexts: EguiContexts, mut world_errors: ResMut<WorldErrors>) {
+    // A single error fits into the inspector
+    if world_errors.0.len() < 2 {
+        return;
+    }
+
+    let mut open = true;
+    egui::Window::new("Input errors")
+        .open(&mut open)
+        .default_width(320.0)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.label(format!("{} parts of the input were skipped", world_errors.0.len()));
+            egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                for error in &world_errors.0 {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+            });
+        });
+    if !open {
+        world_errors.0.clear();
     }
 }
 
@@ -448,33 +2232,43 @@ fn mouse_position_system(
 fn ui(
     mut contexts: EguiContexts,
     mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut point_data: ResMut<PointData>,
-    mut distribution: ResMut<Distribution>,
-    mut simulation_timer: ResMut<SimulationTimer>,
-    mut algorithm: ResMut<Algorithm>,
-    mut drawing_history: ResMut<DrawingHistory>,
-    point_query: Query<Entity, With<PointSingle>>,
-    convex_hull_query: Query<Entity, With<ConvexHull>>,
-    gizmo_query: Query<Entity, With<Gizmo>>,
-    text_query: Query<Entity, With<ColorText>>,
+    state: WorldState,
+    settings: SimulationSettings,
+    mut tools: InspectorTools,
+    mut scene: SceneEntities,
+    mut world: WorldGeneration,
 ) {
+    let WorldState { mut point_data, mut hull_data, mut hull_cache, mut drawing_history } = state;
+    let SimulationSettings { mut distribution, mut simulation_timer, mut algorithm, mut orientation_epsilon } = settings;
     egui::Window::new("Inspector").show(contexts.ctx_mut(), |ui| {
         ui.label("Choose the number of points and the simulation time Δt.");
-        ui.add(egui::Slider::new(&mut point_data.3, 0..=15_000).text("Number of points"));
+        ui.add(egui::Slider::new(&mut point_data.3, 0..=MAX_POINT_COUNT).text("Number of points"));
         if ui
-            .add(egui::Slider::new(&mut simulation_timer.1, 0.0..=10.0).text("Simulation time (s)"))
+            .add(egui::Slider::new(&mut simulation_timer.1, 0.0..=MAX_TIME_STEP).text("Simulation time (s)"))
             .changed()
         {
             let simulation_timer_time = simulation_timer.1;
             simulation_timer
                 .0
-                .set_duration(std::time::Duration::from_secs_f32(simulation_timer_time));
+                .set_duration(Duration::from_secs_f32(simulation_timer_time));
 
         }
+        ui.add(
+            egui::Slider::new(&mut tools.step_durations.speed, PLAYBACK_SPEED_RANGE)
+                .logarithmic(true)
+                .text("Playback speed")
+                .suffix("x"),
+        );
+        ui.collapsing("Step durations", |ui| {
+            ui.label("How long each kind of step is shown, relative to the simulation time");
+            ui.add(egui::Slider::new(&mut tools.step_durations.hull, STEP_DURATION_RANGE).text("Hull edges"));
+            ui.add(
+                egui::Slider::new(&mut tools.step_durations.temporary, STEP_DURATION_RANGE).text("Intermediate lines"),
+            );
+            ui.add(egui::Slider::new(&mut tools.step_durations.other, STEP_DURATION_RANGE).text("Comments"));
+        });
 
-        ui.add(egui::Slider::new(&mut point_data.2, 1.00..=1000.0).text("Point radius"));
+        ui.add(egui::Slider::new(&mut point_data.2, MIN_POINT_RADIUS..=MAX_POINT_RADIUS).text("Point radius"));
 
         ui.separator();
 
@@ -489,124 +2283,335 @@ fn ui(
                 ("Circle (Area)", DistributionType::CircleArea),
                 ("Circle (Perimeter)", DistributionType::CirclePerimeter),
                 ("Square (Area)", DistributionType::SquareArea),
+                ("Clusters", DistributionType::Clusters),
+                ("Ellipse (Area)", DistributionType::EllipseArea),
+                ("Ellipse (Perimeter)", DistributionType::EllipsePerimeter),
+                ("Ring (Area)", DistributionType::Ring),
+                ("Parametric curve", DistributionType::Parametric),
+                ("Poisson disk (Area)", DistributionType::PoissonDisk),
+                ("Halton sequence (Square)", DistributionType::Halton),
+                ("Sobol sequence (Square)", DistributionType::Sobol),
+                ("Grid (Lattice)", DistributionType::Grid),
             ],
         );
 
-        if ui.button("Generate World").clicked() {
-            despawn_entities(&mut commands, &point_query);
-            despawn_entities(&mut commands, &convex_hull_query);
-            despawn_entities(&mut commands, &gizmo_query);
-            despawn_entities(&mut commands, &text_query);
-            point_data.0.clear();
-            drawing_history.0.clear();
+        if distribution.0 == DistributionType::Fibonacci {
+            ui.checkbox(&mut distribution.1.fit_fibonacci, "Fit the spiral to the world size");
+        }
 
-            if point_data.1.is_empty() && point_data.3 > 0 {
-                (0..point_data.3).for_each(|i| match distribution.0 {
-                    DistributionType::Fibonacci => {
-                        let color = Color::hsl(360. * i as f32 / point_data.3 as f32, 0.95, 0.7);
-                        let (x, y) = fibonacci_circle(i+1);
-                        if x.is_nan() || y.is_nan() {
-                            return;
-                        }
-                        point_data.0.push(Vec2::new(x, y));
-                        commands.spawn((
-                            MaterialMesh2dBundle {
-                                mesh: Mesh2dHandle(meshes.add(Circle { radius: point_data.2 })),
-                                material: materials.add(color),
-                                transform: Transform::from_xyz(x, y, 0.0),
-                                ..default()
-                            },
-                            PointSingle,
-                        ));
-                    }
-                    DistributionType::CircleArea => {
-                        let (x, y) = circle_area(point_data.3);
-                        let color = Color::hsl(360. * i as f32 / point_data.3 as f32, 0.95, 0.7);
-                        point_data.0.push(Vec2::new(x, y));
-                        commands.spawn((
-                            MaterialMesh2dBundle {
-                                mesh: Mesh2dHandle(meshes.add(Circle { radius: point_data.2 })),
-                                material: materials.add(color),
-                                transform: Transform::from_xyz(x, y, 0.0),
-                                ..default()
-                            },
-                            PointSingle,
-                        ));
-                    }
-                    DistributionType::CirclePerimeter => {
-                        let (x, y) = circle_perimeter(point_data.3);
-                        let color = Color::hsl(360. * i as f32 / point_data.3 as f32, 0.95, 0.7);
-                        point_data.0.push(Vec2::new(x, y));
-                        commands.spawn((
-                            MaterialMesh2dBundle {
-                                mesh: Mesh2dHandle(meshes.add(Circle { radius: point_data.2 })),
-                                material: materials.add(color),
-                                transform: Transform::from_xyz(x, y, 0.0),
-                                ..default()
-                            },
-                            PointSingle,
-                        ));
-                    }
-                    DistributionType::SquareArea => {
-                        let (x, y) = square_area(point_data.3);
-                        let color = Color::hsl(360. * i as f32 / point_data.3 as f32, 0.95, 0.7);
-                        point_data.0.push(Vec2::new(x, y));
-                        commands.spawn((
-                            MaterialMesh2dBundle {
-                                mesh: Mesh2dHandle(meshes.add(Circle { radius: point_data.2 })),
-                                material: materials.add(color),
-                                transform: Transform::from_xyz(x, y, 0.0),
-                                ..default()
-                            },
-                            PointSingle,
-                        ));
-                    }
-                })
-            } else {
-                let lines_copy = point_data.1.clone();
-                for (index, line) in lines_copy.lines().enumerate() {
-                    let mut split = line.split(',');
-                    let x = split.next().and_then(|s| s.trim().parse::<f32>().ok());
-                    let y = split.next().and_then(|s| s.trim().parse::<f32>().ok());
-                    let color = Color::hsl(360. * index as f32 / point_data.1.len() as f32, 0.95, 0.7);
-
-                    match (x, y) {
-                        (Some(x), Some(y)) => {
-                            point_data.0.push(Vec2::new(x, y));
-
-                            commands.spawn((
-                                MaterialMesh2dBundle {
-                                    mesh: Mesh2dHandle(meshes.add(Circle { radius: point_data.2 })),
-                                    material: materials.add(color),
-                                    transform: Transform::from_xyz(x, y, 0.0),
-                                    ..default()
-                                },
-                                PointSingle,
-                            ));
-                        }
-                        _ => {
-                            eprintln!("Failed to parse line: {}, x: {:?}, y: {:?}", line, x, y);
-                        }
-                    }
-                }
+        let has_radius = match distribution.0 {
+            DistributionType::Fibonacci => distribution.1.fit_fibonacci,
+            DistributionType::Parametric => false,
+            _ => true,
+        };
+        if has_radius {
+            ui.checkbox(&mut distribution.1.scale_with_count, "Scale the world with the number of points")
+                .on_hover_text("Spreads the points over a radius of 100 √n, like the original distributions");
+            if !distribution.1.scale_with_count {
+                ui.add(
+                    egui::Slider::new(&mut distribution.1.radius, 10.0..=20_000.0)
+                        .logarithmic(true)
+                        .text("World size (radius)"),
+                );
             }
         }
+
+        if distribution.0 == DistributionType::Clusters {
+            ui.add(egui::Slider::new(&mut distribution.1.cluster_count, 1..=50).text("Number of clusters"));
+            ui.add(
+                egui::Slider::new(&mut distribution.1.cluster_spread, 0.01..=1.0)
+                    .logarithmic(true)
+                    .text("Cluster spread (relative to radius)"),
+            );
+        }
+
+        if distribution.0 == DistributionType::Grid {
+            ui.add(
+                egui::Slider::new(&mut distribution.1.grid_jitter, 0.0..=0.5)
+                    .text("Jitter (relative to spacing)"),
+            )
+            .on_hover_text("Without jitter the points form a perfect lattice, full of collinear points and equal slopes");
+        }
+
+        if distribution.0 == DistributionType::Halton {
+            ui.horizontal(|ui| {
+                ui.label("Bases");
+                ui.add(egui::DragValue::new(&mut distribution.1.halton_bases.0).clamp_range(2..=97).prefix("x: "));
+                ui.add(egui::DragValue::new(&mut distribution.1.halton_bases.1).clamp_range(2..=97).prefix("y: "));
+            })
+            .response
+            .on_hover_text("Coprime bases, like two different primes, cover the square evenly");
+            let (x_base, y_base) = distribution.1.halton_bases;
+            if !coprime(x_base, y_base) {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    "The bases share a common factor, so the points fall onto a few lines",
+                );
+            }
+        }
+
+        if distribution.0 == DistributionType::Sobol {
+            let dimensions = &mut distribution.1.sobol_dimensions;
+            let mut one_based = (dimensions.0 + 1, dimensions.1 + 1);
+            ui.horizontal(|ui| {
+                ui.label("Dimensions");
+                ui.add(egui::DragValue::new(&mut one_based.0).clamp_range(1..=SOBOL_DIMENSIONS).prefix("x: "));
+                ui.add(egui::DragValue::new(&mut one_based.1).clamp_range(1..=SOBOL_DIMENSIONS).prefix("y: "));
+            });
+            *dimensions = (one_based.0 - 1, one_based.1 - 1);
+            if dimensions.0 == dimensions.1 {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    "Both coordinates use the same dimension, so the points fall onto the diagonal",
+                );
+            }
+        }
+
+        if matches!(distribution.0, DistributionType::EllipseArea | DistributionType::EllipsePerimeter) {
+            ui.add(
+                egui::Slider::new(&mut distribution.1.aspect_ratio, 0.01..=100.0)
+                    .logarithmic(true)
+                    .text("Aspect ratio (width / height)"),
+            );
+            ui.add(
+                egui::Slider::new(&mut distribution.1.ellipse_rotation, -90.0..=90.0)
+                    .text("Rotation")
+                    .suffix("°"),
+            );
+        }
+
+        if distribution.0 == DistributionType::Ring {
+            let outer_radius = distribution.1.world_radius(point_data.3);
+            ui.add(
+                egui::Slider::new(&mut distribution.1.inner_radius, 0.0..=outer_radius)
+                    .text("Inner radius"),
+            );
+        }
+
+        if distribution.0 == DistributionType::Parametric {
+            ui.label("The curve is sampled at random t in [0, 1], e.g. `1000 * math::cos(2 * pi * t)`");
+            ui.horizontal(|ui| {
+                ui.label("x(t) =");
+                ui.text_edit_singleline(&mut distribution.1.x_expression);
+            });
+            ui.horizontal(|ui| {
+                ui.label("y(t) =");
+                ui.text_edit_singleline(&mut distribution.1.y_expression);
+            });
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Generate World").clicked() {
+                world.generate.send(GenerateWorld);
+            }
+            if ui
+                .add(egui::DragValue::new(&mut world.seed.0).clamp_range(0..=u32::MAX).prefix("seed: "))
+                .changed()
+            {
+                world.seed.1 = true;
+            }
+            ui.checkbox(&mut world.seed.1, "Keep seed")
+                .on_hover_text("Generates the same points again, so the world can be shared by its seed");
+            if ui.button("Randomize seed").clicked() {
+                world.seed.0 = WorldSeed::random_seed();
+                world.seed.1 = true;
+                world.generate.send(GenerateWorld);
+            }
+        });
+        if let [error] = world.errors.0.as_slice() {
+            ui.colored_label(egui::Color32::RED, error);
+        } else if !world.errors.0.is_empty() {
+            ui.colored_label(
+                egui::Color32::RED,
+                format!("{} errors, see the Input errors window", world.errors.0.len()),
+            );
+        }
         
+        create_combo_box(
+            ui,
+            "Point colors",
+            &mut tools.point_coloring.0,
+            &[
+                ("Rainbow by index", ColorMode::Rainbow),
+                ("Solid white", ColorMode::Solid),
+                ("By distance from centroid", ColorMode::DistanceFromCentroid),
+            ],
+        );
+
         ui.checkbox(&mut point_data.4, "Manually add points by clicking");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut tools.editing.point_drag.0, "Drag points");
+            ui.add_enabled(
+                tools.editing.point_drag.0,
+                egui::Checkbox::new(&mut tools.editing.point_drag.1, "Recompute the hull live"),
+            );
+        });
+        ui.checkbox(&mut tools.editing.point_deletion.0, "Delete points")
+            .on_hover_text("Right click a point to delete it, or drag a rectangle while holding Shift to delete all points inside");
+        ui.checkbox(&mut tools.editing.polygon_drawing.0, "Draw a polygon by clicking")
+            .on_hover_text("Connects every clicked point to the previous one, the polyline is the input of Melkman's algorithm");
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut tools.editing.grid_snap.0, "Snap to grid")
+                .on_hover_text("Points placed or dragged by hand snap to the previewed grid, which helps to build exact degenerate inputs");
+            ui.add(
+                egui::DragValue::new(&mut tools.editing.grid_snap.1)
+                    .clamp_range(1.0..=1000.0)
+                    .prefix("size: "),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Insert random point").clicked() {
+                let radius = Vec2::splat(distribution.1.world_radius(point_data.3));
+                let (min, max) = bounding_box(&point_data.0).unwrap_or((-radius, radius));
+                let rng = &mut world.seed.2;
+                let point = Vec2::new(rng.gen_range(min.x..=max.x), rng.gen_range(min.y..=max.y)).round();
+
+                let index = point_data.0.len();
+                point_data.0.push(point);
+                point_data.3 += 1;
+                let color = point_color(tools.point_coloring.0, &point_data.0, index);
+                spawn_point(&mut commands, &mut tools.display.assets, &mut tools.display.materials, index, point, point_data.2, color);
+                hull_data.4 = !hull_data.0.is_empty();
+                hull_cache.0 = None;
+            }
+
+            if ui.button("Jitter all points").clicked() && !point_data.0.is_empty() {
+                let rng = &mut world.seed.2;
+                let jitter = tools.jitter.0;
+                for point in point_data.0.iter_mut() {
+                    *point += Vec2::new(rng.gen_range(-jitter..=jitter), rng.gen_range(-jitter..=jitter));
+                }
+
+                // The polyline of the polygon drawing no longer matches the moved points
+                despawn_entities(&mut commands, &scene.points);
+                despawn_entities(&mut commands, &scene.polyline);
+                spawn_points(&mut commands, &mut tools.display.assets, &mut tools.display.materials, &point_data.0, Vec2::ZERO, point_data.2, tools.point_coloring.0);
+                hull_data.4 = !hull_data.0.is_empty();
+                hull_cache.0 = None;
+            }
+        });
+        ui.add(
+            egui::Slider::new(&mut tools.jitter.0, 0.0..=100.0)
+                .logarithmic(true)
+                .text("Jitter magnitude"),
+        );
         
-        // ui.text_edit_multiline(&mut point_data.1);
-        
-        // Removed temporarily coz not working on web build
-        // ui.code_editor(&mut point_data.1);
-        
-        if ui.button("Clear world").clicked() {
-            despawn_entities(&mut commands, &point_query);
-            despawn_entities(&mut commands, &convex_hull_query);
-            despawn_entities(&mut commands, &gizmo_query);
-            despawn_entities(&mut commands, &text_query);
-            point_data.0.clear();
-            drawing_history.0.clear();
+        #[cfg(not(target_arch = "wasm32"))]
+        if ui.button("Import points…").clicked() {
+            let file = rfd::FileDialog::new()
+                .add_filter("Points", &["csv", "tsv", "txt", "geojson", "json", "wkt"])
+                .add_filter("All files", &["*"])
+                .pick_file();
+            if let Some(path) = file {
+                match std::fs::read_to_string(&path) {
+                    Ok(text) => {
+                        // The editor is parsed when the world is generated, which also lists the failed rows
+                        let (points, errors) = parse_input(&text);
+                        let name = path.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+                        tools.point_import.0 = Some((name, points.len(), errors.len()));
+                        point_data.1 = text;
+                        world.generate.send(GenerateWorld);
+                    }
+                    Err(e) => warn!("Failed to import points: {}", e),
+                }
+            }
         }
+        if let Some((name, parsed, failed)) = &tools.point_import.0 {
+            ui.label(format!("Imported {} points from {}, {} rows failed", parsed, name, failed));
+        }
+
+        ui.label("Points (one x, y pair per line)");
+        point_editor(ui, &mut point_data.1);
+        
+        ui.horizontal(|ui| {
+            if ui.button("Clear world").clicked() {
+                scene.clear_world(&mut commands);
+                point_data.0.clear();
+                drawing_history.0.clear();
+                hull_data.0.clear();
+                hull_cache.0 = None;
+            }
+
+            if ui.button("Clear hull").clicked() {
+                scene.clear_drawing(&mut commands);
+                drawing_history.0.clear();
+                drawing_history.1 = 0;
+                hull_data.0.clear();
+                hull_cache.0 = None;
+            }
+
+            // The drawn hull stays valid, as the hull of its vertices is the same hull
+            if ui.button("Keep only hull").clicked() && !hull_data.0.is_empty() {
+                despawn_entities(&mut commands, &scene.points);
+                despawn_entities(&mut commands, &scene.polyline);
+                let hull = hull_data.0.clone();
+                spawn_points(&mut commands, &mut tools.display.assets, &mut tools.display.materials, &hull, Vec2::ZERO, point_data.2, tools.point_coloring.0);
+                point_data.3 = hull.len();
+                point_data.0 = hull;
+                hull_data.1 = 0;
+                hull_cache.0 = None;
+            }
+        });
+
+        #[cfg(not(target_arch = "wasm32"))]
+        ui.horizontal(|ui| {
+            if ui.button("Save session").clicked() {
+                let session = SessionState {
+                    points: point_data.0.clone(),
+                    distribution: distribution.0,
+                    algorithm: algorithm.0,
+                    point_radius: point_data.2,
+                    time_step: simulation_timer.1,
+                    step_durations: tools.step_durations.clone(),
+                    history: drawing_history.0.clone(),
+                    step: drawing_history.1,
+                    paused: tools.playback.0,
+                    hull: hull_data.0.clone(),
+                    hull_time: hull_data.2,
+                };
+                match session.save(SESSION_PATH) {
+                    Ok(()) => info!("Saved session to {}", SESSION_PATH),
+                    Err(e) => warn!("Failed to save session: {}", e),
+                }
+            }
+
+            if ui.button("Load session").clicked() {
+                match SessionState::load(SESSION_PATH) {
+                    Ok(session) => {
+                        scene.clear_world(&mut commands);
+                        hull_cache.0 = None;
+
+                        // The scene is drawn from scratch up to the step the session stopped at
+                        drawing_history.1 = session.step.min(session.history.len());
+                        drawing_history.0 = session.history;
+                        tools.playback.0 = session.paused;
+                        *tools.step_durations = session.step_durations;
+
+                        hull_data.1 = collinear_hull_points(&session.points, &session.hull, orientation_epsilon.0).len();
+                        hull_data.2 = session.hull_time;
+                        hull_data.3 = hull_diameter(&session.hull).2;
+                        hull_data.4 = false;
+                        hull_data.5 = OperationCounts::default();
+                        tools.recursion_tree.0.clear();
+                        hull_data.0 = session.hull;
+
+                        distribution.0 = session.distribution;
+                        algorithm.0 = session.algorithm;
+                        point_data.2 = clamp_point_radius(session.point_radius);
+                        simulation_timer.1 = session.time_step;
+                        simulation_timer
+                            .0
+                            .set_duration(Duration::from_secs_f32(session.time_step));
+
+                        point_data.3 = session.points.len();
+                        spawn_points(&mut commands, &mut tools.display.assets, &mut tools.display.materials, &session.points, Vec2::ZERO, point_data.2, tools.point_coloring.0);
+                        point_data.0 = session.points;
+                    }
+                    Err(e) => warn!("Failed to load session: {}", e),
+                }
+            }
+        });
 
         ui.separator();
 
@@ -619,19 +2624,537 @@ fn ui(
             &[
                 ("Jarvis March", AlgorithmType::JarvisMarch),
                 ("Kirk Patrick Seidel", AlgorithmType::KirkPatrickSeidel),
+                ("Monotone Chain", AlgorithmType::MonotoneChain),
+                ("Quickhull", AlgorithmType::Quickhull),
+                ("Chan", AlgorithmType::Chan),
+                ("Divide and Conquer", AlgorithmType::DivideAndConquer),
+                ("Randomized Incremental", AlgorithmType::RandomizedIncremental),
+                ("Melkman", AlgorithmType::Melkman),
+                ("Approximate (Bentley-Faust-Preparata)", AlgorithmType::Approximate),
             ],
         );
 
+        if algorithm.0 == AlgorithmType::Melkman {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                "Melkman's algorithm expects the points in order to form a simple polyline, e.g. drawn as a polygon",
+            );
+        }
+
+        if algorithm.0 == AlgorithmType::KirkPatrickSeidel {
+            let response = ui
+                .checkbox(&mut tools.recursion_tree.2, "Show the recursion")
+                .on_hover_text("Records the subproblems of the next run into a tree in a side panel");
+            if response.changed() && !tools.recursion_tree.2 {
+                tools.recursion_tree.0.clear();
+            }
+        }
+
+        if algorithm.0 == AlgorithmType::Approximate {
+            ui.add(
+                egui::Slider::new(&mut tools.approximate_hull.0, 1..=1000)
+                    .logarithmic(true)
+                    .text("Strips"),
+            )
+            .on_hover_text("More strips approximate the hull better, at the cost of more candidate points");
+        }
+
+        ui.add(
+            egui::Slider::new(&mut orientation_epsilon.0, 0.0..=1e-2)
+                .logarithmic(true)
+                .text("Collinearity tolerance"),
+        );
+
+        let filter_checkbox = egui::Checkbox::new(&mut tools.akl_toussaint.0, "Akl-Toussaint pre-filter");
+        ui.add_enabled(algorithm.0 != AlgorithmType::Melkman, filter_checkbox)
+            .on_hover_text("Discards the points inside the quadrilateral of the extreme points before running")
+            .on_disabled_hover_text("Melkman's algorithm needs the whole polyline, so it runs without the filter");
+        let filters = tools.akl_toussaint.filters(algorithm.0);
+        if let (true, Some((discarded, speedup))) = (filters, tools.akl_toussaint.1) {
+            ui.label(format!("Discarded {} points, {:.2}x faster", discarded, speedup));
+        }
+
+        #[cfg(feature = "geo")]
+        ui.checkbox(&mut tools.geo_validation.0, "Validate against geo::ConvexHull")
+            .on_hover_text("Compares every generated hull with the one of the geo crate");
+
+        ui.checkbox(&mut tools.hull_overlays.0, "Draw the minimum enclosing circle");
+        ui.checkbox(&mut tools.hull_overlays.1, "Draw the diameter of the hull");
+        ui.checkbox(&mut tools.hull_overlays.2, "Fill the hull");
+
         if ui.add(egui::Button::new("Generate Mesh")).clicked() {
+            let key = hull_input_key(
+                &point_data.0,
+                algorithm.0,
+                orientation_epsilon.0,
+                &tools.hull_overlays,
+                filters,
+                tools.approximate_hull.0,
+            );
+            if hull_cache.0 == Some(key) && !hull_data.0.is_empty() {
+                // Nothing changed since the last run, so its hull and history are replayed as they are
+                drawing_history.1 = 0;
+                scene.clear_drawing(&mut commands);
+            } else {
+                drawing_history.1 = 0;
+                drawing_history.0.clear();
+                scene.clear_drawing(&mut commands);
+                let points = if filters {
+                    akl_toussaint(&point_data.0, &mut drawing_history.0, orientation_epsilon.0)
+                } else {
+                    point_data.0.clone()
+                };
+                let discarded = point_data.0.len() - points.len();
+
+                let mut events = Vec::new();
+                let (hull, operations) = count_operations(|| match algorithm.0 {
+                    AlgorithmType::JarvisMarch => jarvis_march(points, &mut drawing_history.0, orientation_epsilon.0, None),
+                    AlgorithmType::KirkPatrickSeidel => {
+                        // Recording the events only pays off while they are shown
+                        let events = tools.recursion_tree.2.then_some(&mut events);
+                        kirk_patrick_seidel(points, &mut drawing_history.0, orientation_epsilon.0, events)
+                    }
+                    AlgorithmType::MonotoneChain => monotone_chain(points, &mut drawing_history.0, orientation_epsilon.0, None),
+                    AlgorithmType::Quickhull => quickhull(points, &mut drawing_history.0, orientation_epsilon.0, None),
+                    AlgorithmType::Chan => chan(points, &mut drawing_history.0, orientation_epsilon.0, None),
+                    AlgorithmType::DivideAndConquer => {
+                        divide_and_conquer(points, &mut drawing_history.0, orientation_epsilon.0, None)
+                    }
+                    AlgorithmType::RandomizedIncremental => randomized_incremental(
+                        points,
+                        &mut drawing_history.0,
+                        orientation_epsilon.0,
+                        &mut world.seed.2,
+                        None,
+                    ),
+                    AlgorithmType::Melkman => melkman(points, &mut drawing_history.0, orientation_epsilon.0, None),
+                    AlgorithmType::Approximate => approximate_hull(
+                        points,
+                        tools.approximate_hull.0,
+                        &mut drawing_history.0,
+                        orientation_epsilon.0,
+                        None,
+                    ),
+                });
+                hull_data.5 = operations;
+                tools.recursion_tree.0 = recursion_tree(&events);
+
+                // The time is taken from a separate run, as the drawing history and the counting would dominate it
+                let start = Instant::now();
+                let points = if filters {
+                    akl_toussaint(&point_data.0, &mut Vec::new(), orientation_epsilon.0)
+                } else {
+                    point_data.0.clone()
+                };
+                std::hint::black_box(compute_hull(points, algorithm.0, orientation_epsilon.0));
+                hull_data.2 = start.elapsed();
+
+                tools.approximate_hull.1 = (algorithm.0 == AlgorithmType::Approximate).then(|| {
+                    let exact =
+                        compute_hull(point_data.0.clone(), AlgorithmType::KirkPatrickSeidel, orientation_epsilon.0);
+                    approximation_error(&hull, &exact, orientation_epsilon.0)
+                });
+
+                // The speedup compares runs without a drawing history, which would dominate the time otherwise
+                tools.akl_toussaint.1 = filters.then(|| {
+                    let baseline = Instant::now();
+                    compute_hull(point_data.0.clone(), algorithm.0, orientation_epsilon.0);
+                    let baseline = baseline.elapsed();
+
+                    let filtered = Instant::now();
+                    let kept = akl_toussaint(&point_data.0, &mut Vec::new(), orientation_epsilon.0);
+                    compute_hull(kept, algorithm.0, orientation_epsilon.0);
+                    let filtered = filtered.elapsed();
+
+                    (discarded, baseline.as_secs_f64() / filtered.as_secs_f64().max(1e-9))
+                });
+
+                // Overlays are drawn together in the last step so that they all stay on the screen
+                let mut step = Vec::new();
+                let mut comments = Vec::new();
+
+                let collinear = collinear_hull_points(&point_data.0, &hull, orientation_epsilon.0);
+                if !collinear.is_empty() {
+                    step.extend(collinear.iter().map(|p| LineType::CollinearPoint(*p)));
+                    comments.push(format!("Found {} collinear points on the hull edges", collinear.len()));
+                }
+
+                if tools.hull_overlays.0 && !point_data.0.is_empty() {
+                    let (center, radius) = min_enclosing_circle(&point_data.0, &mut world.seed.2);
+                    step.push(LineType::Circle(center, radius));
+                    comments.push(format!("Minimum enclosing circle of radius {:.2}", radius));
+                }
+
+                let (a, b, diameter) = hull_diameter(&hull);
+                if tools.hull_overlays.1 && hull.len() >= 2 {
+                    step.push(LineType::Diameter(a, b));
+                    comments.push(format!("Diameter of the hull is {:.2}", diameter));
+                }
+
+                // The algorithms return the vertices counterclockwise, which the fan of the fill relies on
+                if tools.hull_overlays.2 && hull.len() >= 3 {
+                    step.push(LineType::FilledHull(hull.clone()));
+                }
+
+                if !step.is_empty() {
+                    step.push(LineType::TextComment(comments.join("\n")));
+                    drawing_history.0.push(step);
+                }
+
+                hull_data.0 = hull;
+                hull_data.1 = collinear.len();
+                hull_data.3 = diameter;
+                hull_data.4 = false;
+                hull_cache.0 = Some(key);
+            }
+
+            // This runs for the replayed hulls too, so enabling the validation doesn't need a new run
+            #[cfg(feature = "geo")]
+            {
+                tools.geo_validation.1 = (tools.geo_validation.0 && algorithm.0 != AlgorithmType::Approximate)
+                    .then(|| validate_hull(&point_data.0, &hull_data.0, orientation_epsilon.0));
+            }
+        }
+
+        if ui.button("Convex layers").clicked() {
             drawing_history.1 = 0;
             drawing_history.0.clear();
-            despawn_entities(&mut commands, &convex_hull_query);
-            despawn_entities(&mut commands, &gizmo_query);
-            let points = point_data.0.clone();
-            match algorithm.0 {
-                AlgorithmType::JarvisMarch => jarvis_march(points, &mut drawing_history.0),
-                AlgorithmType::KirkPatrickSeidel => kirk_patrick_seidel(points, &mut drawing_history.0),
+            scene.clear_drawing(&mut commands);
+            hull_data.0.clear();
+            hull_cache.0 = None;
+
+            let layers = convex_layers(&point_data.0, orientation_epsilon.0);
+            for (index, layer) in layers.iter().enumerate() {
+                let mut step: Vec<_> = (0..layer.len())
+                    .map(|i| LineType::LayerEdge(layer[i], layer[(i + 1) % layer.len()], index))
+                    .collect();
+                step.push(LineType::TextComment(format!("Layer {}: {} vertices", index + 1, layer.len())));
+                drawing_history.0.push(step);
+            }
+            drawing_history.0.push(vec![LineType::TextComment(format!(
+                "Peeled {} convex layers",
+                layers.len()
+            ))]);
+        }
+
+        if ui.button("Compare both").clicked() {
+            drawing_history.1 = 0;
+            drawing_history.0.clear();
+            scene.clear_drawing(&mut commands);
+            hull_data.0.clear();
+            hull_cache.0 = None;
+            tools.recursion_tree.0.clear();
+
+            // Draw Kirk Patrick Seidel on a copy of the points placed to the right of the originals
+            let (min_x, max_x) = point_data
+                .0
+                .iter()
+                .fold((f32::MAX, f32::MIN), |(min, max), p| (min.min(p.x), max.max(p.x)));
+            let offset = if point_data.0.is_empty() {
+                0.0
+            } else {
+                (max_x - min_x) * 1.25 + 4.0 * point_data.2
+            };
+            scene.comparison_history.2 = offset;
+
+            let copies = spawn_points(&mut commands, &mut tools.display.assets, &mut tools.display.materials, &point_data.0, Vec2::new(offset, 0.0), point_data.2, tools.point_coloring.0);
+            for entity in copies {
+                commands.entity(entity).insert(Comparison);
+            }
+
+            let (points, comparison_points) = if tools.akl_toussaint.0 {
+                (
+                    akl_toussaint(&point_data.0, &mut drawing_history.0, orientation_epsilon.0),
+                    akl_toussaint(&point_data.0, &mut scene.comparison_history.0, orientation_epsilon.0),
+                )
+            } else {
+                (point_data.0.clone(), point_data.0.clone())
+            };
+            jarvis_march(points, &mut drawing_history.0, orientation_epsilon.0, None);
+            kirk_patrick_seidel(comparison_points, &mut scene.comparison_history.0, orientation_epsilon.0, None);
+        }
+        ui.checkbox(&mut tools.display.split_screen.0, "Split screen")
+            .on_hover_text("Show the comparison side by side, Jarvis March on the left and Kirk Patrick Seidel on the right");
+
+        if ui.button("Fit view").on_hover_text("Frame all the points").clicked() {
+            tools.display.fit_view.send(FitView);
+        }
+        ui.checkbox(&mut tools.display.follow_step.0, "Follow the current step")
+            .on_hover_text("Pans and zooms the camera to the geometry of the step being shown");
+        ui.checkbox(&mut tools.display.world_grid.0, "Show the grid, the axes and the scale bar");
+        ui.checkbox(&mut tools.display.point_labels.0, "Label the points with their index");
+        ui.checkbox(&mut tools.display.point_labels.1, "Show the coordinates of the hovered point");
+
+        egui::CollapsingHeader::new("Line styles").show(ui, |ui| {
+            let before = tools.display.assets.line_styles;
+            let styles = &mut tools.display.assets.line_styles;
+            for (name, style) in [
+                ("Hull edges", &mut styles.hull),
+                ("Temporary lines", &mut styles.temporary),
+                ("Vertical lines", &mut styles.vertical_line),
+            ] {
+                ui.horizontal(|ui| {
+                    ui.add(egui::Slider::new(&mut style.width, 0.5..=10.0).suffix(" px").text(name));
+                    ui.checkbox(&mut style.dashed, "Dashed");
+                });
+            }
+            // The drawn steps keep the styles they were drawn with, so both scenes are rebuilt
+            if tools.display.assets.line_styles != before {
+                *scene.rendered_steps = RenderedSteps::default();
+            }
+        });
+
+        if !hull_data.0.is_empty() {
+            ui.label(format!("Computed hull in {:.1} ms", hull_data.2.as_secs_f64() * 1000.0));
+            ui.label(format!("Collinear points on hull edges: {}", hull_data.1));
+            ui.label(format!("Hull diameter: {:.2}", hull_data.3));
+            #[cfg(feature = "operation-counts")]
+            egui::CollapsingHeader::new("Operations").show(ui, |ui| {
+                let operations = hull_data.5;
+                egui::Grid::new("operations").striped(true).show(ui, |ui| {
+                    for (name, count) in [
+                        ("Orientation tests", operations.orientation_tests),
+                        ("Medians", operations.medians),
+                        ("Median comparisons", operations.comparisons),
+                        ("Bridge rounds", operations.bridge_rounds),
+                        ("Pruned candidates", operations.pruned_candidates),
+                    ] {
+                        ui.label(name);
+                        ui.label(count.to_string());
+                        ui.end_row();
+                    }
+                });
+            });
+            if let (AlgorithmType::Approximate, Some(error)) = (algorithm.0, tools.approximate_hull.1) {
+                ui.label(format!("Approximation error: {:.2}", error))
+                    .on_hover_text("How far the exact hull reaches outside of the approximate one");
+            }
+            #[cfg(feature = "geo")]
+            match &tools.geo_validation.1 {
+                Some(Ok(())) => {
+                    ui.colored_label(egui::Color32::GREEN, "Matches geo::ConvexHull");
+                }
+                Some(Err(mismatch)) => {
+                    ui.colored_label(egui::Color32::RED, format!("Mismatch: {}", mismatch));
+                }
+                None => {}
+            }
+            if hull_data.4 {
+                ui.colored_label(egui::Color32::YELLOW, "The points changed, generate the mesh again");
+            }
+
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut tools.hull_export.0, HullFormat::Csv, "CSV");
+                ui.radio_value(&mut tools.hull_export.0, HullFormat::Json, "JSON");
+                ui.radio_value(&mut tools.hull_export.0, HullFormat::GeoJson, "GeoJSON");
+                ui.radio_value(&mut tools.hull_export.0, HullFormat::Wkt, "WKT");
+                ui.checkbox(&mut tools.hull_export.1, "Include the input points");
+            });
+            if ui.button("Export hull").clicked() {
+                let points = tools.hull_export.1.then_some(point_data.0.as_slice());
+                let text = hull_to_text(&hull_data.0, points, tools.hull_export.0);
+
+                // The web build can't write files, so the hull is copied to the clipboard instead
+                #[cfg(target_arch = "wasm32")]
+                ui.output_mut(|output| output.copied_text = text);
+
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    let extension = tools.hull_export.0.extension();
+                    let file = rfd::FileDialog::new()
+                        .add_filter(extension, &[extension])
+                        .set_file_name(format!("hull.{}", extension))
+                        .save_file();
+                    if let Some(path) = file {
+                        match std::fs::write(&path, text) {
+                            Ok(()) => info!("Exported the hull to {}", path.display()),
+                            Err(e) => warn!("Failed to export the hull: {}", e),
+                        }
+                    }
+                }
+            }
+        }
+
+        let steps = drawing_history.0.len();
+        if steps == 0 {
+            ui.label("Animation: idle");
+        } else {
+            let step = drawing_history.1.min(steps);
+            let progress = if step == steps {
+                "done".to_string()
+            } else if tools.playback.0 {
+                format!("step {} of {}, paused", step, steps)
+            } else {
+                // The next step is drawn once the timer finishes, every step drawn after it lingers for its
+                // whole duration except the last one
+                let remaining = simulation_timer.0.remaining_secs()
+                    + drawing_history.0[step..steps - 1]
+                        .iter()
+                        .map(|next| tools.step_durations.duration(simulation_timer.1, next))
+                        .sum::<f32>();
+                format!("step {} of {}, done in {:.1} s", step, steps, remaining)
+            };
+            ui.label(format!("Animation: {}", progress));
+            ui.add(egui::ProgressBar::new(step as f32 / steps as f32).desired_height(4.0));
+            if ui
+                .add(egui::Slider::new(&mut drawing_history.1, 0..=steps).text("Step"))
+                .changed()
+            {
+                // The comparison is scrubbed along, like it plays along
+                scene.comparison_history.1 = drawing_history.1;
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("⏮ Back").on_hover_text("Pauses and goes back to the previous step").clicked() {
+                tools.playback.0 = true;
+                step_back(&mut drawing_history, &mut scene.comparison_history);
+            }
+            let label = if tools.playback.0 { "▶ Play" } else { "⏸ Pause" };
+            if ui.button(label).on_hover_text("Space").clicked() {
+                tools.playback.0 = !tools.playback.0;
+            }
+            if ui.button("⏭ Step").on_hover_text("Pauses and draws the next step").clicked() {
+                tools.playback.0 = true;
+                tools.playback.1 = true;
+            }
+        });
+
+        let milestones = milestones(&drawing_history.0);
+        if !milestones.is_empty() {
+            let current = drawing_history.1.min(steps);
+            let previous = milestones.iter().rev().find(|(index, _)| *index < current);
+            let next = milestones.iter().find(|(index, _)| *index > current);
+
+            let mut jump = None;
+            ui.horizontal(|ui| {
+                if ui.add_enabled(previous.is_some(), egui::Button::new("⏪ Previous milestone")).clicked() {
+                    jump = previous.map(|(index, _)| *index);
+                }
+                if ui.add_enabled(next.is_some(), egui::Button::new("Next milestone ⏩")).clicked() {
+                    jump = next.map(|(index, _)| *index);
+                }
+            });
+            if let Some((_, name)) = milestones.iter().rev().find(|(index, _)| *index <= current) {
+                ui.label(format!("Milestone: {}", name));
+            }
+
+            if let Some(index) = jump {
+                tools.playback.0 = true;
+                drawing_history.1 = index;
+                scene.comparison_history.1 = index;
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        match tools.gif_recording.progress() {
+            Some((captured, frames)) => {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Recording GIF: {} / {} frames", captured, frames));
+                    if ui.button("Stop recording").clicked() {
+                        tools.gif_recording.0 = None;
+                    }
+                });
+            }
+            None => {
+                if ui
+                    .add_enabled(steps > 0, egui::Button::new("Record GIF"))
+                    .on_hover_text("Plays the animation from the start and records its steps into a GIF")
+                    .clicked()
+                {
+                    tools.gif_recording.start(steps);
+                    tools.playback.0 = false;
+                    drawing_history.1 = 0;
+                    scene.comparison_history.1 = 0;
+                }
+            }
+        }
+
+        #[cfg(all(feature = "video", not(target_arch = "wasm32")))]
+        if ui
+            .add_enabled(steps > 0, egui::Button::new("Export video"))
+            .on_hover_text("Renders every step into an MP4 video with ffmpeg")
+            .clicked()
+        {
+            let colors = point_colors(tools.point_coloring.0, &point_data.0);
+            let points: Vec<_> = point_data.0.iter().copied().zip(colors).collect();
+            video::export_video(
+                drawing_history.0.clone(),
+                points,
+                point_data.2,
+                |step| tools.step_durations.duration(simulation_timer.1, step),
+                format!("animation_{}.mp4", timestamp()),
+            );
+        }
+
+        if ui.button("Export SVG").clicked() {
+            let frame = current_frame(&drawing_history.0, drawing_history.1);
+            let colors = point_colors(tools.point_coloring.0, &point_data.0);
+            let points: Vec<_> = point_data.0.iter().copied().zip(colors).collect();
+            let svg = frame_to_svg(&frame, &points, point_data.2);
+
+            // The web build can't write files, so the document is copied to the clipboard instead
+            #[cfg(target_arch = "wasm32")]
+            ui.output_mut(|output| output.copied_text = svg);
+
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                let path = format!("frame_{}.svg", drawing_history.1);
+                match std::fs::write(&path, svg) {
+                    Ok(()) => info!("Exported frame to {}", path),
+                    Err(e) => warn!("Failed to export frame: {}", e),
+                }
+            }
+        }
+
+        ui.checkbox(&mut tools.hull_query.0, "Query point-in-hull");
+        if tools.hull_query.0 {
+            match tools.hull_query.1 {
+                Some(true) => ui.label("The queried point is inside the hull"),
+                Some(false) => ui.label("The queried point is outside the hull"),
+                None => ui.label("Click anywhere to check whether the point is inside the hull"),
             };
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_radius_is_clamped_to_the_minimum() {
+        for radius in [0.0, -5.0, f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert_eq!(clamp_point_radius(radius), MIN_POINT_RADIUS, "{}", radius);
+        }
+        assert_eq!(clamp_point_radius(MIN_POINT_RADIUS / 2.0), MIN_POINT_RADIUS);
+        assert_eq!(clamp_point_radius(25.0), 25.0);
+    }
+
+    #[test]
+    fn time_step_is_clamped_to_the_inspector_range() {
+        for time_step in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert_eq!(clamp_time_step(time_step), DEFAULT_TIME_STEP, "{}", time_step);
+        }
+        assert_eq!(clamp_time_step(-1.0), 0.0);
+        assert_eq!(clamp_time_step(1e30), MAX_TIME_STEP);
+        assert_eq!(clamp_time_step(2.5), 2.5);
+    }
+
+    #[test]
+    fn systems_have_no_conflicting_parameters() {
+        // Bevy only checks the parameters of a system when it is initialized, and panics on conflicting accesses
+        fn initialize<M>(system: impl IntoSystem<(), (), M>) {
+            IntoSystem::into_system(system).initialize(&mut World::new());
+        }
+
+        initialize(ui);
+        initialize(mouse_position_system);
+        initialize(generate_world_system);
+        initialize(graphics_drawing);
+        initialize(keyboard_input_system);
+        initialize(point_color_system);
+        initialize(point_editing::point_drag_system);
+        initialize(point_editing::point_deletion_system);
+    }
+}
@@ -0,0 +1,499 @@
+//! Contains the parsing of points from text.
+//!
+//! The points are given one per line as `x, y` pairs, which is also how they are typed
+//! in the inspector and read by the command line interface. Besides commas the coordinates can be
+//! separated by semicolons, tabs or spaces, so CSV, TSV and whitespace separated files parse alike.
+//! The pairs may be wrapped in parentheses, brackets or quotes, and a header row naming the columns is skipped.
+//! The points can also be given as a [GeoJSON](https://geojson.org) document or as
+//! [WKT](https://en.wikipedia.org/wiki/Well-known_text_representation_of_geometry) geometries, see [parse_input].
+
+use bevy::prelude::*;
+use serde_json::Value;
+
+/// Largest absolute value of a parsed coordinate.
+///
+/// Coordinates beyond it break the camera and the rendering long before they are a problem for the
+/// algorithms, so such lines are rejected.
+pub const MAX_COORDINATE: f32 = 1e7;
+
+/// Characters that separate the coordinates of a line, or wrap them like in `(1, 2)`, `[1; 2]` or `"1","2"`
+const SEPARATORS: [char; 8] = [',', ';', '(', ')', '[', ']', '"', '\''];
+
+/// Parses the points from the text, one `x, y` pair per line.
+///
+/// Only the first two fields of a line are read, the rest are ignored. Empty lines are skipped, and so is the first
+/// line if it fails to parse but contains letters, as the header of a spreadsheet does. Lines that fail to parse,
+/// have a non-finite coordinate or a coordinate beyond [MAX_COORDINATE] are skipped as well, and returned along the
+/// points as one error message per line.
+pub fn parse_points(text: &str) -> (Vec<Vec2>, Vec<String>) {
+    let mut points = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut first = true;
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let header = std::mem::take(&mut first) && line.contains(|c: char| c.is_alphabetic());
+
+        let mut split = line
+            .split(|c: char| SEPARATORS.contains(&c) || c.is_whitespace())
+            .filter(|field| !field.is_empty());
+        let x = split.next().and_then(|s| s.trim().parse::<f32>().ok());
+        let y = split.next().and_then(|s| s.trim().parse::<f32>().ok());
+
+        let error = match (x, y) {
+            _ if header && (x.is_none() || y.is_none()) => continue,
+            (Some(x), Some(y)) => match checked_point(x, y) {
+                Ok(point) => {
+                    points.push(point);
+                    continue;
+                }
+                Err(error) => error,
+            },
+            _ => "failed to parse",
+        };
+        errors.push(format!("Line {}: `{}` {}", number + 1, line.trim(), error));
+    }
+
+    (points, errors)
+}
+
+/// Keywords of the WKT geometries, `LINESTRING`, `MULTILINESTRING` and `MULTIPOLYGON` are only recognized to be
+/// rejected
+const WKT_GEOMETRIES: [&str; 7] = [
+    "POINT",
+    "MULTIPOINT",
+    "LINESTRING",
+    "MULTILINESTRING",
+    "POLYGON",
+    "MULTIPOLYGON",
+    "GEOMETRYCOLLECTION",
+];
+
+/// Parses the points from the text, with [parse_geojson] when it is a JSON object, with [parse_wkt] when
+/// it starts with a WKT geometry and with [parse_points] otherwise
+pub fn parse_input(text: &str) -> (Vec<Vec2>, Vec<String>) {
+    let text = text.trim_start();
+    if text.starts_with('{') {
+        parse_geojson(text)
+    } else if is_wkt(text) {
+        parse_wkt(text)
+    } else {
+        parse_points(text)
+    }
+}
+
+/// Returns the leading letters of the text, which are the keyword of a WKT geometry
+fn wkt_keyword(text: &str) -> &str {
+    let end = text.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(text.len());
+    &text[..end]
+}
+
+/// Returns whether the text starts with a WKT geometry, whose keyword is followed by its positions or by a
+/// `Z`, `M` or `EMPTY` tag, which tells it apart from a header like `point, x, y`
+fn is_wkt(text: &str) -> bool {
+    let keyword = wkt_keyword(text);
+    WKT_GEOMETRIES.contains(&keyword.to_ascii_uppercase().as_str())
+        && text[keyword.len()..]
+            .trim_start()
+            .starts_with(['(', 'Z', 'z', 'M', 'm', 'E', 'e'])
+}
+
+/// Parses the points of WKT `POINT` and `MULTIPOINT` geometries, any number of which follow each other. The
+/// vertices of the rings of a `POLYGON` are read as points as well, without the closing vertex that repeats the
+/// first one, so that the hulls exported as [HullFormat::Wkt](crate::export::HullFormat::Wkt) can be read back.
+///
+/// The geometries may be separated by whitespace, commas or semicolons, and the keywords are case insensitive.
+/// Only the first two coordinates of a position are read, so `Z` and `M` geometries are flattened. Other geometries
+/// and invalid positions are skipped and returned as one error message each, text that isn't a geometry stops the
+/// parsing. The members of a `GEOMETRYCOLLECTION` are read like geometries of their own.
+pub fn parse_wkt(text: &str) -> (Vec<Vec2>, Vec<String>) {
+    let mut points = Vec::new();
+    let mut errors = Vec::new();
+    read_wkt_geometries(text, &mut 0, &mut points, &mut errors);
+    (points, errors)
+}
+
+/// Reads the points of the geometries following each other in the text for [parse_wkt], numbering them from the
+/// given number on
+fn read_wkt_geometries(text: &str, number: &mut usize, points: &mut Vec<Vec2>, errors: &mut Vec<String>) {
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        *number += 1;
+        let keyword = wkt_keyword(rest).to_ascii_uppercase();
+        if !WKT_GEOMETRIES.contains(&keyword.as_str()) {
+            let line = rest.lines().next().unwrap_or_default();
+            errors.push(format!("Geometry {}: `{}` is not a WKT geometry", number, line.trim()));
+            break;
+        }
+        rest = rest[keyword.len()..].trim_start();
+
+        // The dimensions of `POINT Z (1 2 3)` don't matter, while `POINT EMPTY` has no position at all
+        let tag = wkt_keyword(rest).to_ascii_uppercase();
+        rest = rest[tag.len()..].trim_start();
+        let body = if tag == "EMPTY" {
+            ""
+        } else {
+            let Some(end) = closing_parenthesis(rest) else {
+                errors.push(format!("Geometry {}: {} has unbalanced parentheses", number, keyword));
+                break;
+            };
+            let body = &rest[1..end];
+            rest = &rest[end + 1..];
+            body
+        };
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',' || c == ';');
+
+        let positions: Vec<&str> = match keyword.as_str() {
+            _ if body.trim().is_empty() => Vec::new(),
+            "POINT" => vec![body],
+            // The positions of a MULTIPOINT are written both with and without their own parentheses
+            "MULTIPOINT" => body
+                .split(',')
+                .map(|position| position.trim_matches(|c: char| c.is_whitespace() || c == '(' || c == ')'))
+                .collect(),
+            "POLYGON" => body
+                .split(')')
+                .map(|ring| ring.trim_matches(|c: char| c.is_whitespace() || c == ',' || c == '('))
+                .filter(|ring| !ring.is_empty())
+                .flat_map(|ring| {
+                    let positions: Vec<&str> = ring.split(',').collect();
+                    positions[..positions.len().saturating_sub(1)].to_vec()
+                })
+                .collect(),
+            "GEOMETRYCOLLECTION" => {
+                read_wkt_geometries(body, number, points, errors);
+                continue;
+            }
+            _ => {
+                errors.push(format!("Geometry {} is a {}, only points and polygons are supported", number, keyword));
+                continue;
+            }
+        };
+
+        for position in positions {
+            let mut coordinates = position.split_whitespace().map(|c| c.parse::<f32>().ok());
+            let error = match (coordinates.next().flatten(), coordinates.next().flatten()) {
+                (Some(x), Some(y)) => match checked_point(x, y) {
+                    Ok(point) => {
+                        points.push(point);
+                        continue;
+                    }
+                    Err(error) => error,
+                },
+                _ => "failed to parse",
+            };
+            errors.push(format!("Geometry {}: `{}` {}", number, position.trim(), error));
+        }
+    }
+}
+
+/// Returns the index of the parenthesis closing the one the text starts with
+fn closing_parenthesis(text: &str) -> Option<usize> {
+    if !text.starts_with('(') {
+        return None;
+    }
+    let mut depth = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 1 => return Some(index),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses the points of a GeoJSON document, which is a FeatureCollection, a single Feature or a bare geometry.
+///
+/// The `Point` and `MultiPoint` geometries are read, their longitude and latitude being the `x` and `y`
+/// coordinates. The vertices of the rings of a `Polygon` are read as points as well, without the closing vertex
+/// that repeats the first one, so that the hulls exported as [HullFormat::GeoJson](crate::export::HullFormat::GeoJson)
+/// can be read back. Other geometries and invalid positions are skipped and returned as one error message each.
+pub fn parse_geojson(text: &str) -> (Vec<Vec2>, Vec<String>) {
+    let mut points = Vec::new();
+    let mut errors = Vec::new();
+
+    let document: Value = match serde_json::from_str(text) {
+        Ok(document) => document,
+        Err(e) => return (points, vec![format!("Invalid GeoJSON: {}", e)]),
+    };
+    let geometries: Vec<(String, &Value)> = match document["type"].as_str() {
+        Some("FeatureCollection") => document["features"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(|(index, feature)| (format!("Feature {}", index + 1), &feature["geometry"]))
+            .collect(),
+        Some("Feature") => vec![("Feature".to_string(), &document["geometry"])],
+        _ => vec![("Geometry".to_string(), &document)],
+    };
+
+    for (name, geometry) in geometries {
+        let coordinates = &geometry["coordinates"];
+        let positions: Vec<&Value> = match geometry["type"].as_str() {
+            Some("Point") => vec![coordinates],
+            Some("MultiPoint") => coordinates.as_array().into_iter().flatten().collect(),
+            Some("Polygon") => coordinates
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_array)
+                .flat_map(|ring| &ring[..ring.len().saturating_sub(1)])
+                .collect(),
+            Some(other) => {
+                errors.push(format!("{} has a {} geometry, only points and polygons are supported", name, other));
+                continue;
+            }
+            None => {
+                errors.push(format!("{} has no geometry", name));
+                continue;
+            }
+        };
+
+        for position in positions {
+            let error = match (position[0].as_f64(), position[1].as_f64()) {
+                (Some(x), Some(y)) => match checked_point(x as f32, y as f32) {
+                    Ok(point) => {
+                        points.push(point);
+                        continue;
+                    }
+                    Err(error) => error,
+                },
+                _ => "failed to parse",
+            };
+            errors.push(format!("{}: `{}` {}", name, position, error));
+        }
+    }
+
+    (points, errors)
+}
+
+/// Returns the point with the given coordinates, unless one of them is non-finite or beyond [MAX_COORDINATE]
+pub(crate) fn checked_point(x: f32, y: f32) -> Result<Vec2, &'static str> {
+    if !x.is_finite() || !y.is_finite() {
+        Err("has a non-finite coordinate")
+    } else if x.abs() > MAX_COORDINATE || y.abs() > MAX_COORDINATE {
+        Err("has a coordinate beyond the maximum")
+    } else {
+        Ok(Vec2::new(x, y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{hull_to_text, HullFormat};
+
+    #[test]
+    fn non_finite_and_huge_coordinates_are_reported() {
+        let (points, errors) = parse_points("1, 2\nNaN, 3\n4, inf\n1e30, 5\n6, 7");
+
+        assert_eq!(points, vec![Vec2::new(1.0, 2.0), Vec2::new(6.0, 7.0)]);
+        assert_eq!(
+            errors,
+            vec![
+                "Line 2: `NaN, 3` has a non-finite coordinate",
+                "Line 3: `4, inf` has a non-finite coordinate",
+                "Line 4: `1e30, 5` has a coordinate beyond the maximum",
+            ]
+        );
+    }
+
+    #[test]
+    fn wkt_points_and_multipoints() {
+        let cases = [
+            ("POINT (1 2)", vec![Vec2::new(1.0, 2.0)]),
+            ("point(1 2)", vec![Vec2::new(1.0, 2.0)]),
+            ("MULTIPOINT ((1 2), (3 4))", vec![Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0)]),
+            ("MULTIPOINT (1 2, 3 4)", vec![Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0)]),
+            // The third and fourth coordinates are dropped
+            ("POINT Z (1 2 3)", vec![Vec2::new(1.0, 2.0)]),
+            ("MULTIPOINT M ((1 2 9), (3 4 9))", vec![Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0)]),
+            ("POINT ZM (1 2 3 4)", vec![Vec2::new(1.0, 2.0)]),
+            ("POINT EMPTY", vec![]),
+            ("MULTIPOINT EMPTY, POINT (5 6)", vec![Vec2::new(5.0, 6.0)]),
+            (
+                "POINT (1 2), POINT (3 4); MULTIPOINT (5 6)\nPOINT(7 8)",
+                vec![Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0), Vec2::new(5.0, 6.0), Vec2::new(7.0, 8.0)],
+            ),
+        ];
+
+        for (text, expected) in cases {
+            assert_eq!(parse_wkt(text), (expected.clone(), vec![]), "{}", text);
+            assert_eq!(parse_input(text), (expected, vec![]), "{}", text);
+        }
+    }
+
+    #[test]
+    fn wkt_errors_are_reported() {
+        let (points, errors) = parse_wkt("LINESTRING (0 0, 1 1), POINT (1 2), MULTIPOLYGON (((0 0, 1 0, 0 1, 0 0)))");
+        assert_eq!(points, vec![Vec2::new(1.0, 2.0)]);
+        assert_eq!(
+            errors,
+            vec![
+                "Geometry 1 is a LINESTRING, only points and polygons are supported",
+                "Geometry 3 is a MULTIPOLYGON, only points and polygons are supported",
+            ]
+        );
+
+        let (points, errors) = parse_wkt("POINT (1 2), MULTIPOINT ((3 4), (5 6)");
+        assert_eq!(points, vec![Vec2::new(1.0, 2.0)]);
+        assert_eq!(errors, vec!["Geometry 2: MULTIPOINT has unbalanced parentheses"]);
+
+        let (points, errors) = parse_wkt("MULTIPOINT (1 2, a b, 1e30 0), CIRCLE (0 0)");
+        assert_eq!(points, vec![Vec2::new(1.0, 2.0)]);
+        assert_eq!(
+            errors,
+            vec![
+                "Geometry 1: `a b` failed to parse",
+                "Geometry 1: `1e30 0` has a coordinate beyond the maximum",
+                "Geometry 2: `CIRCLE (0 0)` is not a WKT geometry",
+            ]
+        );
+    }
+
+    #[test]
+    fn wkt_polygons_are_read() {
+        let text = "POLYGON ((0 0, 4 0, 0 4, 0 0), (1 1, 2 1, 1 2, 1 1)), POLYGON EMPTY";
+        let (points, errors) = parse_wkt(text);
+        assert_eq!(
+            points,
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(4.0, 0.0),
+                Vec2::new(0.0, 4.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(2.0, 1.0),
+                Vec2::new(1.0, 2.0),
+            ]
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        // The members of a collection are numbered like the geometries around it
+        let (points, errors) = parse_wkt("GEOMETRYCOLLECTION (POINT (1 2), LINESTRING (0 0, 1 1)), POINT (a 4)");
+        assert_eq!(points, vec![Vec2::new(1.0, 2.0)]);
+        assert_eq!(
+            errors,
+            vec![
+                "Geometry 3 is a LINESTRING, only points and polygons are supported",
+                "Geometry 4: `a 4` failed to parse",
+            ]
+        );
+    }
+
+    #[test]
+    fn wkt_polygon_export_is_read_back() {
+        let hull = [Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0)];
+        let points = [Vec2::new(1.0, 1.0)];
+
+        let text = hull_to_text(&hull, None, HullFormat::Wkt);
+        assert_eq!(parse_input(&text), (hull.to_vec(), vec![]));
+
+        let text = hull_to_text(&hull, Some(&points), HullFormat::Wkt);
+        assert_eq!(parse_input(&text), ([&hull[..], &points[..]].concat(), vec![]));
+    }
+
+    #[test]
+    fn geojson_points_are_read() {
+        let collection = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [1, 2]}},
+            {"type": "Feature", "properties": {}, "geometry": {"type": "MultiPoint", "coordinates": [[3, 4], [5, 6]]}}
+        ]}"#;
+        let feature = r#"{"type": "Feature", "geometry": {"type": "Point", "coordinates": [1, 2, 30]}}"#;
+        let geometry = r#"{"type": "MultiPoint", "coordinates": [[1, 2], [3.5, -4]]}"#;
+
+        assert_eq!(
+            parse_geojson(collection),
+            (vec![Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0), Vec2::new(5.0, 6.0)], vec![])
+        );
+        assert_eq!(parse_geojson(feature), (vec![Vec2::new(1.0, 2.0)], vec![]));
+        assert_eq!(parse_input(geometry), (vec![Vec2::new(1.0, 2.0), Vec2::new(3.5, -4.0)], vec![]));
+    }
+
+    #[test]
+    fn geojson_polygon_export_is_read_back() {
+        let hull = [Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0), Vec2::new(0.0, 2.0)];
+        let points = [Vec2::new(1.0, 1.0)];
+
+        let text = hull_to_text(&hull, None, HullFormat::GeoJson);
+        assert_eq!(parse_input(&text), (hull.to_vec(), vec![]));
+
+        let text = hull_to_text(&hull, Some(&points), HullFormat::GeoJson);
+        assert_eq!(parse_input(&text), ([&hull[..], &points[..]].concat(), vec![]));
+    }
+
+    #[test]
+    fn geojson_errors_are_reported() {
+        let collection = r#"{"type": "FeatureCollection", "features": [
+            {"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[0, 0], [1, 1]]}},
+            {"type": "Feature", "geometry": null},
+            {"type": "Feature", "geometry": {"type": "MultiPoint", "coordinates": [[1, 2], ["a", 3], [1e30, 0]]}}
+        ]}"#;
+        let (points, errors) = parse_geojson(collection);
+        assert_eq!(points, vec![Vec2::new(1.0, 2.0)]);
+        assert_eq!(
+            errors,
+            vec![
+                "Feature 1 has a LineString geometry, only points and polygons are supported",
+                "Feature 2 has no geometry",
+                "Feature 3: `[\"a\",3]` failed to parse",
+                "Feature 3: `[1e30,0]` has a coordinate beyond the maximum",
+            ]
+        );
+
+        let (points, errors) = parse_geojson(r#"{"type": "Feature", "properties": {}}"#);
+        assert!(points.is_empty());
+        assert_eq!(errors, vec!["Feature has no geometry"]);
+
+        let (points, errors) = parse_geojson(r#"{"type": "Point", "coordinates": [1, 2]"#);
+        assert!(points.is_empty());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Invalid GeoJSON: "), "{}", errors[0]);
+    }
+
+    #[test]
+    fn separators_and_wrappers_are_accepted() {
+        let expected = vec![Vec2::new(1.0, 2.0), Vec2::new(-3.5, 4.0)];
+        let cases = [
+            "1, 2\n-3.5, 4\n",
+            "1,2,red\n-3.5,4,blue\n",
+            "1;2\n-3.5;4\n",
+            "1\t2\n-3.5\t4\n",
+            "  1   2\n\n-3.5 4  \n",
+            "(1, 2)\n(-3.5, 4)\n",
+            "[1; 2]\n[-3.5, 4]\n",
+            "\"1\",\"2\"\n'-3.5','4'\n",
+        ];
+
+        for text in cases {
+            assert_eq!(parse_points(text), (expected.clone(), vec![]), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn only_a_first_line_with_letters_is_a_header() {
+        let expected = vec![Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0)];
+        assert_eq!(parse_points("x, y\n1, 2\n3, 4"), (expected.clone(), vec![]));
+        assert_eq!(parse_points("\n\"x\"\t\"y\"\n1\t2\n3\t4"), (expected, vec![]));
+
+        // A first line with letters that parses is data, like an exponent or an infinity
+        assert_eq!(parse_points("1e3, 2\n3, 4"), (vec![Vec2::new(1000.0, 2.0), Vec2::new(3.0, 4.0)], vec![]));
+        assert_eq!(
+            parse_points("inf, 2\n3, 4"),
+            (vec![Vec2::new(3.0, 4.0)], vec!["Line 1: `inf, 2` has a non-finite coordinate".to_string()])
+        );
+
+        // Only the first line can be a header
+        assert_eq!(
+            parse_points("1, 2\nx, y\n3"),
+            (
+                vec![Vec2::new(1.0, 2.0)],
+                vec!["Line 2: `x, y` failed to parse".to_string(), "Line 3: `3` failed to parse".to_string()]
+            )
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Contains the point and coordinate types the algorithms are generic over.
+//!
+//! The simulation renders with [Vec2], which has `f32` coordinates. The algorithms also work with
+//! [DVec2] for `f64` coordinates, the drawing history being converted to [Vec2] with [Point::to_vec2].
+
+use bevy::math::{DVec2, Vec2};
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A floating point type used for the coordinates of a [Point], implemented for `f32` and `f64`
+pub trait Scalar:
+    Copy
+    + PartialOrd
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    /// Smallest finite value
+    const MIN: Self;
+    /// Largest finite value
+    const MAX: Self;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn from_f32(value: f32) -> Self;
+    fn to_f32(self) -> f32;
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+
+    /// Returns the bit representation of the value, with `-0.0` folded into `0.0` so that it is consistent with `==`
+    fn key(self) -> u64;
+}
+
+impl Scalar for f32 {
+    const ZERO: Self = 0.0;
+    const MIN: Self = f32::MIN;
+    const MAX: Self = f32::MAX;
+
+    fn abs(self) -> Self {
+        self.abs()
+    }
+
+    fn sqrt(self) -> Self {
+        self.sqrt()
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn key(self) -> u64 {
+        (self + 0.0).to_bits() as u64
+    }
+}
+
+impl Scalar for f64 {
+    const ZERO: Self = 0.0;
+    const MIN: Self = f64::MIN;
+    const MAX: Self = f64::MAX;
+
+    fn abs(self) -> Self {
+        self.abs()
+    }
+
+    fn sqrt(self) -> Self {
+        self.sqrt()
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value as f64
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn key(self) -> u64 {
+        (self + 0.0).to_bits()
+    }
+}
+
+/// A point in the plane, the input and output of the algorithms
+pub trait Point: Copy + PartialEq + Display {
+    /// Type of the coordinates
+    type Scalar: Scalar;
+
+    fn new(x: Self::Scalar, y: Self::Scalar) -> Self;
+    fn x(&self) -> Self::Scalar;
+    fn y(&self) -> Self::Scalar;
+
+    /// Converts the point to the `f32` coordinates used for rendering
+    fn to_vec2(&self) -> Vec2;
+
+    /// Returns the dot product of the vectors from the origin to the points
+    fn dot(&self, other: &Self) -> Self::Scalar {
+        self.x() * other.x() + self.y() * other.y()
+    }
+
+    /// Returns the vector from `other` to the point
+    fn minus(&self, other: &Self) -> Self {
+        Self::new(self.x() - other.x(), self.y() - other.y())
+    }
+
+    /// Returns the euclidean distance between the points
+    fn distance_to(&self, other: &Self) -> Self::Scalar {
+        let difference = self.minus(other);
+        difference.dot(&difference).sqrt()
+    }
+}
+
+impl Point for Vec2 {
+    type Scalar = f32;
+
+    fn new(x: f32, y: f32) -> Self {
+        Vec2::new(x, y)
+    }
+
+    fn x(&self) -> f32 {
+        self.x
+    }
+
+    fn y(&self) -> f32 {
+        self.y
+    }
+
+    fn to_vec2(&self) -> Vec2 {
+        *self
+    }
+}
+
+impl Point for DVec2 {
+    type Scalar = f64;
+
+    fn new(x: f64, y: f64) -> Self {
+        DVec2::new(x, y)
+    }
+
+    fn x(&self) -> f64 {
+        self.x
+    }
+
+    fn y(&self) -> f64 {
+        self.y
+    }
+
+    fn to_vec2(&self) -> Vec2 {
+        self.as_vec2()
+    }
+}
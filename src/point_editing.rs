@@ -0,0 +1,220 @@
+//! Contains the tools to edit the points with the mouse after they were generated.
+//!
+//! A point is grabbed by pressing the left button over it, which disables the panning of the camera until the
+//! button is released, and follows the cursor from then on. Pressing anywhere else still pans the camera.
+//! Points are deleted by right clicking them, or by dragging a rectangle around them while holding Shift.
+
+use bevy::prelude::*;
+use web_time::Instant;
+
+use convex_hull_simulation::algorithms::{
+    akl_toussaint, collinear_hull_points, compute_hull, hull_diameter, insert_point, AlgorithmType,
+};
+
+use super::{
+    clamp_point_radius, despawn_entities, hull_step, nearest_point, AklToussaint, Comparison, EditingTools,
+    HullSettings, PointSingle, PolylineEdge, RenderedSteps, UserInput, WorldState, HOVER_DISTANCE,
+};
+
+/// Resource to store the state of dragging the points.
+///
+/// It contains data in the following order: dragging is enabled | recompute the hull while dragging | index of the dragged point | the drag already added its step to the [DrawingHistory]
+#[derive(Resource, Default)]
+pub struct PointDrag(pub bool, pub bool, pub Option<usize>, pub bool);
+
+/// Resource to store the state of deleting the points.
+///
+/// It contains data in the following order: deleting is enabled | corner where the selection rectangle started
+#[derive(Resource, Default)]
+pub struct PointDeletion(pub bool, pub Option<Vec2>);
+
+/// Moves the grabbed point along with the cursor, snapping it to the [GridSnap] like a manually added point.
+///
+/// While the hull is recomputed live, the whole drag shares a single step of the [DrawingHistory], which is
+/// replaced every time the point moves. Otherwise the hull is only marked as outdated.
+///
+/// Moving a point that isn't a vertex can only grow the hull, so it is inserted into the current hull with
+/// [insert_point]. Only moving a vertex runs the whole algorithm again, after the [AklToussaint] pre-filter.
+pub fn point_drag_system(
+    input: UserInput,
+    state: WorldState,
+    settings: HullSettings,
+    editing: EditingTools,
+    mut points: Query<(&PointSingle, &mut Transform), Without<Comparison>>,
+    akl_toussaint_filter: Res<AklToussaint>,
+    mut rendered_steps: ResMut<RenderedSteps>,
+) {
+    let WorldState { mut point_data, mut hull_data, mut hull_cache, mut drawing_history } = state;
+    let HullSettings { algorithm, orientation_epsilon } = settings;
+    let EditingTools { mut point_drag, grid_snap, point_deletion, .. } = editing;
+    if !point_drag.0 || !input.mouse_button_input.pressed(MouseButton::Left) {
+        point_drag.2 = None;
+        point_drag.3 = false;
+        return;
+    }
+
+    let Some((cursor, scale)) = input.world_cursor() else {
+        return;
+    };
+
+    let Some(index) = point_drag.2.filter(|index| *index < point_data.0.len()) else {
+        // The press that starts a selection rectangle doesn't grab a point
+        let pressed = input.mouse_button_input.just_pressed(MouseButton::Left);
+        if pressed && !input.egui_wants_focus.0 && point_deletion.1.is_none() {
+            let reach = clamp_point_radius(point_data.2) + HOVER_DISTANCE * scale;
+            point_drag.2 = nearest_point(&point_data.0, cursor)
+                .filter(|(_, distance)| *distance <= reach)
+                .map(|(index, _)| index);
+        }
+        return;
+    };
+
+    let position = if grid_snap.0 && grid_snap.1 > 0.0 {
+        (cursor / grid_snap.1).round() * grid_snap.1
+    } else {
+        cursor
+    };
+    let previous = point_data.0[index];
+    if previous == position {
+        return;
+    }
+
+    point_data.0[index] = position;
+    for (point, mut transform) in points.iter_mut() {
+        if point.0 == index {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+        }
+    }
+    hull_cache.0 = None;
+
+    if hull_data.0.is_empty() {
+        return;
+    }
+    if !point_drag.1 {
+        hull_data.4 = true;
+        return;
+    }
+
+    // The approximate hull and the one of Melkman's algorithm aren't the exact hull insert_point keeps
+    let exact = !matches!(algorithm.0, AlgorithmType::Approximate | AlgorithmType::Melkman);
+    let start = Instant::now();
+    let hull = if exact && !hull_data.4 && !hull_data.0.contains(&previous) {
+        let mut hull = hull_data.0.clone();
+        insert_point(&mut hull, position, orientation_epsilon.0);
+        hull
+    } else if akl_toussaint_filter.filters(algorithm.0) {
+        let kept = akl_toussaint(&point_data.0, &mut Vec::new(), orientation_epsilon.0);
+        compute_hull(kept, algorithm.0, orientation_epsilon.0)
+    } else {
+        compute_hull(point_data.0.clone(), algorithm.0, orientation_epsilon.0)
+    };
+    hull_data.2 = start.elapsed();
+    hull_data.1 = collinear_hull_points(&point_data.0, &hull, orientation_epsilon.0).len();
+    hull_data.3 = hull_diameter(&hull).2;
+    hull_data.4 = false;
+
+    let step = hull_step(&hull, format!("Moved point {} to {}", index, position));
+    hull_data.0 = hull;
+    if point_drag.3 && !drawing_history.0.is_empty() {
+        *drawing_history.0.last_mut().unwrap() = step;
+    } else {
+        drawing_history.0.push(step);
+        point_drag.3 = true;
+    }
+    drawing_history.1 = drawing_history.0.len();
+    // The replaced step starts with clearing the screen, so drawing it again is enough
+    rendered_steps.0 = rendered_steps.0.min(drawing_history.0.len() - 1);
+}
+
+/// Deletes the right clicked point, or all points inside the selection rectangle once the left button is released.
+///
+/// The indices of the remaining [PointSingle]s, including their copies in the comparison, are shifted to match
+/// [PointData] again, so the points don't have to be spawned anew. The hull is only marked as outdated.
+pub fn point_deletion_system(
+    mut commands: Commands,
+    input: UserInput,
+    state: WorldState,
+    editing: EditingTools,
+    mut gizmos: Gizmos,
+    mut points: Query<(Entity, &mut PointSingle)>,
+    polyline: Query<Entity, With<PolylineEdge>>,
+) {
+    let WorldState { mut point_data, mut hull_data, mut hull_cache, .. } = state;
+    let EditingTools { mut point_deletion, point_drag, .. } = editing;
+    let UserInput { mouse_button_input, keyboard_input, egui_wants_focus, .. } = &input;
+    if !point_deletion.0 {
+        point_deletion.1 = None;
+        return;
+    }
+
+    let Some((cursor, scale)) = input.world_cursor() else {
+        return;
+    };
+
+    let removed: Vec<usize> = if let Some(corner) = point_deletion.1 {
+        let (min, max) = (corner.min(cursor), corner.max(cursor));
+        if mouse_button_input.pressed(MouseButton::Left) {
+            gizmos.rect_2d((min + max) / 2.0, 0.0, max - min, Color::rgb(1.0, 0.3, 0.3));
+            return;
+        }
+        point_deletion.1 = None;
+        (0..point_data.0.len())
+            .filter(|index| {
+                let point = point_data.0[*index];
+                point.cmpge(min).all() && point.cmple(max).all()
+            })
+            .collect()
+    } else if egui_wants_focus.0 || point_drag.2.is_some() {
+        return;
+    } else if mouse_button_input.just_pressed(MouseButton::Left)
+        && keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+    {
+        point_deletion.1 = Some(cursor);
+        return;
+    } else if mouse_button_input.just_pressed(MouseButton::Right) {
+        let reach = clamp_point_radius(point_data.2) + HOVER_DISTANCE * scale;
+        nearest_point(&point_data.0, cursor)
+            .filter(|(_, distance)| *distance <= reach)
+            .map(|(index, _)| index)
+            .into_iter()
+            .collect()
+    } else {
+        return;
+    };
+    if removed.is_empty() {
+        return;
+    }
+
+    // Maps the old indices to the new ones, the removed points have none
+    let mut remaining = 0;
+    let new_indices: Vec<Option<usize>> = (0..point_data.0.len())
+        .map(|index| {
+            if removed.binary_search(&index).is_ok() {
+                return None;
+            }
+            remaining += 1;
+            Some(remaining - 1)
+        })
+        .collect();
+
+    point_data.0 = point_data
+        .0
+        .iter()
+        .zip(&new_indices)
+        .filter(|(_, new_index)| new_index.is_some())
+        .map(|(point, _)| *point)
+        .collect();
+    point_data.3 = point_data.0.len();
+    for (entity, mut point) in points.iter_mut() {
+        match new_indices.get(point.0).copied().flatten() {
+            Some(new_index) => point.0 = new_index,
+            None => commands.entity(entity).despawn(),
+        }
+    }
+
+    // The polyline of the polygon drawing no longer matches the remaining points
+    despawn_entities(&mut commands, &polyline);
+    hull_data.4 = !hull_data.0.is_empty();
+    hull_cache.0 = None;
+}
@@ -0,0 +1,69 @@
+//! Contains the multiline editor of the points, which is split into pages of [PAGE_LINES] lines.
+//!
+//! egui lays out the whole text of a [TextEdit](egui::TextEdit) every frame, which made long inputs freeze
+//! the web build. Only the current page is handed to the text edit, and its edits are spliced back into
+//! the full text, so typing stays responsive however many points were pasted or imported.
+
+use bevy_egui::egui;
+
+/// Number of lines shown on one page of the editor
+const PAGE_LINES: usize = 200;
+
+/// Shows the page of the text that is currently edited, along with the controls to switch pages.
+pub fn point_editor(ui: &mut egui::Ui, text: &mut String) {
+    let id = egui::Id::new("point_editor");
+    let page_id = id.with("page");
+    let lines = text.matches('\n').count() + 1;
+    let pages = lines.div_ceil(PAGE_LINES);
+    let mut page = ui.ctx().data(|data| data.get_temp::<usize>(page_id)).unwrap_or(0).min(pages - 1);
+
+    if pages > 1 {
+        ui.horizontal(|ui| {
+            if ui.add_enabled(page > 0, egui::Button::new("◀")).clicked() {
+                page -= 1;
+            }
+            ui.label(format!(
+                "Lines {}–{} of {}",
+                page * PAGE_LINES + 1,
+                ((page + 1) * PAGE_LINES).min(lines),
+                lines
+            ));
+            if ui.add_enabled(page + 1 < pages, egui::Button::new("▶")).clicked() {
+                page += 1;
+            }
+        });
+    }
+    ui.ctx().data_mut(|data| data.insert_temp(page_id, page));
+
+    let (start, end) = page_range(text, page);
+    let mut page_text = text[start..end].to_string();
+    let response = egui::ScrollArea::vertical()
+        .id_source("point_editor_scroll")
+        .max_height(150.0)
+        .show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut page_text)
+                    .id(id)
+                    .desired_rows(6)
+                    .desired_width(f32::INFINITY)
+                    .font(egui::TextStyle::Monospace)
+                    .hint_text("x, y"),
+            )
+        })
+        .inner;
+
+    if response.changed() {
+        text.replace_range(start..end, &page_text);
+    }
+}
+
+/// Returns the byte range of the lines on the page, without the line break after its last line
+fn page_range(text: &str, page: usize) -> (usize, usize) {
+    let line_start = |line: usize| match line {
+        0 => Some(0),
+        line => text.match_indices('\n').nth(line - 1).map(|(index, _)| index + 1),
+    };
+    let start = line_start(page * PAGE_LINES).unwrap_or(text.len());
+    let end = line_start((page + 1) * PAGE_LINES).map_or(text.len(), |next| next - 1);
+    (start, end)
+}
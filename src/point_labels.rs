@@ -0,0 +1,112 @@
+//! Contains the labels with the indices of the points and the tooltip with the coordinates of the hovered point.
+//!
+//! Both help to find specific points of degenerate inputs, whose indices match the rows of the pasted or
+//! imported text.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui::{egui, EguiContexts};
+
+use super::{clamp_point_radius, despawn_entities, ComparisonCamera, EguiWantsFocus, PointData, HIGHLIGHT_DEPTH};
+
+/// Font size of the labels in world units, which are pixels at the default zoom
+const LABEL_SIZE: f32 = 12.0;
+
+/// Distance in pixels from the cursor within which a point is hovered, on top of its radius
+pub const HOVER_DISTANCE: f32 = 6.0;
+
+/// Resource to store which helpers are shown for the points.
+///
+/// It contains data in the following order: label the points with their index | show the coordinates of the hovered point
+#[derive(Resource, Default)]
+pub struct PointLabels(pub bool, pub bool);
+
+/// Component to identify the labels spawned by [point_label_system].
+#[derive(Component)]
+pub struct PointLabel;
+
+/// Keeps a label with the index next to every point while the labels are enabled.
+///
+/// The labels are respawned whenever the points or their radius change.
+pub fn point_label_system(
+    mut commands: Commands,
+    point_data: Res<PointData>,
+    point_labels: Res<PointLabels>,
+    labels: Query<Entity, With<PointLabel>>,
+    mut labeled: Local<Option<(Vec<Vec2>, f32)>>,
+) {
+    if !point_data.is_changed() && !point_labels.is_changed() {
+        return;
+    }
+    // The inspector borrows the points mutably every frame, so they are still compared before respawning the labels
+    let wanted = point_labels.0.then_some((&point_data.0, point_data.2));
+    if labeled.as_ref().map(|(points, radius)| (points, *radius)) == wanted {
+        return;
+    }
+
+    despawn_entities(&mut commands, &labels);
+    if let Some((points, radius)) = wanted {
+        let radius = clamp_point_radius(radius);
+        for (index, point) in points.iter().enumerate() {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        index.to_string(),
+                        TextStyle {
+                            font_size: LABEL_SIZE,
+                            ..default()
+                        },
+                    ),
+                    text_anchor: bevy::sprite::Anchor::BottomLeft,
+                    transform: Transform::from_xyz(point.x + radius, point.y + radius, HIGHLIGHT_DEPTH),
+                    ..default()
+                },
+                PointLabel,
+            ));
+        }
+    }
+    *labeled = wanted.map(|(points, radius)| (points.clone(), radius));
+}
+
+/// Shows a tooltip with the index and the exact coordinates of the point under the cursor, if the tooltips are enabled.
+pub fn point_tooltip_system(
+    mut contexts: EguiContexts,
+    point_data: Res<PointData>,
+    point_labels: Res<PointLabels>,
+    egui_wants_focus: Res<EguiWantsFocus>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&GlobalTransform, &Camera, &OrthographicProjection), Without<ComparisonCamera>>,
+) {
+    if !point_labels.1 || egui_wants_focus.0 {
+        return;
+    }
+
+    let (camera_transform, camera, projection) = camera_query.single();
+    let Some(cursor) = window
+        .single()
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+    else {
+        return;
+    };
+
+    // The distance is given on the screen, so it grows with the zoom
+    let reach = clamp_point_radius(point_data.2) + HOVER_DISTANCE * projection.scale;
+    let hovered = nearest_point(&point_data.0, cursor).filter(|(_, distance)| *distance <= reach);
+    if let Some((index, _)) = hovered {
+        let point = point_data.0[index];
+        egui::show_tooltip_at_pointer(contexts.ctx_mut(), egui::Id::new("point_tooltip"), |ui| {
+            ui.label(format!("Point {}", index));
+            ui.label(format!("x = {}", point.x));
+            ui.label(format!("y = {}", point.y));
+        });
+    }
+}
+
+/// Returns the index of the point nearest to `position` along with its distance, if there are any points
+pub fn nearest_point(points: &[Vec2], position: Vec2) -> Option<(usize, f32)> {
+    points
+        .iter()
+        .enumerate()
+        .map(|(index, point)| (index, point.distance(position)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
@@ -0,0 +1,120 @@
+//! Contains the side panel showing the recursion of Kirk Patrick Seidel as a tree.
+//!
+//! The tree is rebuilt with [recursion_tree](convex_hull_simulation::algorithms::recursion_tree) from the [HullEvent](convex_hull_simulation::algorithms::HullEvent)s
+//! of the last run. Every node is a subproblem of the CONNECT step, hovering it shades the region between its
+//! endpoints and its bridge in the world.
+
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_egui::{egui, EguiContexts};
+
+use convex_hull_simulation::algorithms::RecursionNode;
+
+use super::{despawn_entities, triangle_mesh, REGION_DEPTH};
+
+/// Resource to store the recursion of the last run of Kirk Patrick Seidel.
+///
+/// The events of the runs are only recorded while the tree is shown, as recording them slows the algorithm down.
+///
+/// It contains data in the following order: the upper and the lower hull trees, empty for the other algorithms | the corners of the highlighted region | the tree is shown
+#[derive(Resource, Default)]
+pub struct RecursionTree(pub Vec<RecursionNode>, pub Option<[Vec2; 4]>, pub bool);
+
+/// Component to identify the region highlighted for the hovered node of the [RecursionTree].
+#[derive(Component)]
+pub struct RecursionHighlight;
+
+/// Shows the [RecursionTree] in a side panel, highlighting the region of the hovered node.
+pub fn recursion_panel(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut tree: ResMut<RecursionTree>,
+    highlights: Query<Entity, With<RecursionHighlight>>,
+    mut material: Local<Option<Handle<ColorMaterial>>>,
+) {
+    let mut hovered = None;
+    if !tree.0.is_empty() {
+        egui::SidePanel::right("recursion_tree").show(contexts.ctx_mut(), |ui| {
+            ui.heading("KPS recursion");
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for node in &tree.0 {
+                    show_node(ui, node, &mut hovered);
+                }
+            });
+        });
+    }
+
+    let region = hovered.map(region);
+    if region == tree.1 {
+        return;
+    }
+    tree.1 = region;
+
+    despawn_entities(&mut commands, &highlights);
+    let Some([min, left, right, max]) = region else {
+        return;
+    };
+    let material = material
+        .get_or_insert_with(|| materials.add(Color::rgba(1.0, 0.8, 0.2, 0.35)))
+        .clone();
+    for (a, b, c) in [(min, left, right), (min, right, max)] {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(triangle_mesh(a, b, c, REGION_DEPTH))),
+                material: material.clone(),
+                ..default()
+            },
+            RecursionHighlight,
+        ));
+    }
+}
+
+/// Shows the node along with its children, remembering it in `hovered` if the pointer is over it
+fn show_node<'a>(ui: &mut egui::Ui, node: &'a RecursionNode, hovered: &mut Option<&'a RecursionNode>) {
+    let bridge = match node.bridge {
+        Some((left, right)) => format!("bridge {} to {}", format_point(left), format_point(right)),
+        None => "given up".to_string(),
+    };
+    let label = if node.depth == 0 {
+        let hull = if node.upper { "Upper hull" } else { "Lower hull" };
+        format!("{}: {} points, {}", hull, node.points, bridge)
+    } else {
+        format!("Depth {}: {} points, {}", node.depth, node.points, bridge)
+    };
+
+    let response = if node.children.is_empty() {
+        ui.label(label)
+    } else {
+        // The endpoints tell the nodes apart, as the labels of siblings can be the same
+        let collapsing = egui::CollapsingHeader::new(label)
+            .id_source((node.upper, node.depth, format_point(node.min), format_point(node.max)))
+            .default_open(node.depth == 0)
+            .show(ui, |ui| {
+                for child in &node.children {
+                    show_node(ui, child, hovered);
+                }
+            });
+        collapsing.header_response
+    };
+
+    if response.hovered() {
+        *hovered = Some(node);
+    }
+}
+
+/// Returns the corners of the region of the node, running from `min` over the bridge to `max`.
+///
+/// The region of a node given up on is the segment between its endpoints, which shades nothing.
+fn region(node: &RecursionNode) -> [Vec2; 4] {
+    let (left, right) = node.bridge.unwrap_or((node.min, node.max));
+    [node.min, left, right, node.max]
+}
+
+/// Returns the point with one decimal, which keeps the labels of the tree short
+fn format_point(point: Vec2) -> String {
+    format!("({:.1}, {:.1})", point.x, point.y)
+}
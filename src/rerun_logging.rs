@@ -0,0 +1,166 @@
+//! Contains the streaming of the drawing histories to the [Rerun](https://rerun.io) viewer, enabled by the `rerun` feature.
+//!
+//! Every run of "Generate Mesh" is logged as its own recording, which spawns the viewer if it isn't running yet.
+//! The steps are logged on the `step` timeline, each one holding the frame the simulation shows after it, see
+//! [current_frame]. The y axis is flipped, as Rerun draws it downwards.
+
+use bevy::prelude::*;
+use rerun::{components::LineStrip2D, RecordingStream, RecordingStreamBuilder, RecordingStreamResult};
+
+use convex_hull_simulation::{
+    algorithms::{DrawingHistory, LineType},
+    export::{current_frame, highlight_color, layer_color, HALO_RADIUS},
+};
+
+use super::{HullCache, PointData};
+
+/// Number of segments the circles are logged with
+const CIRCLE_SEGMENTS: usize = 64;
+
+/// Logs the drawing history to a new recording whenever it is replaced by a run of an algorithm.
+///
+/// The runs are told apart by their [HullCache] key, so replaying a cached run doesn't log it again.
+pub fn rerun_system(
+    hull_cache: Res<HullCache>,
+    drawing_history: Res<DrawingHistory>,
+    point_data: Res<PointData>,
+    mut logged: Local<Option<u64>>,
+) {
+    let Some(key) = hull_cache.0 else {
+        return;
+    };
+    if *logged == Some(key) {
+        return;
+    }
+    *logged = Some(key);
+
+    let result = RecordingStreamBuilder::new("convex_hull_simulation")
+        .spawn()
+        .and_then(|recording| log_history(&recording, &point_data.0, point_data.2, &drawing_history.0));
+    if let Err(e) = result {
+        warn!("Failed to log the run to Rerun: {}", e);
+    }
+}
+
+/// Logs the points and every step of the history to the recording
+fn log_history(
+    recording: &RecordingStream,
+    points: &[Vec2],
+    point_radius: f32,
+    history: &[Vec<LineType>],
+) -> RecordingStreamResult<()> {
+    recording.set_time_sequence("step", 0);
+    recording.log(
+        "points",
+        &rerun::Points2D::new(points.iter().map(|point| flip(*point))).with_radii([point_radius]),
+    )?;
+
+    let (bottom, top) = points
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(bottom, top), point| (bottom.min(point.y), top.max(point.y)));
+
+    for index in 1..=history.len() {
+        recording.set_time_sequence("step", index as i64);
+
+        let mut hull = Vec::new();
+        let mut hull_colors = Vec::new();
+        let mut temporary = Vec::new();
+        let mut vertical_lines = Vec::new();
+        let mut overlays = Vec::new();
+        let mut highlights = Vec::new();
+        let mut halos = Vec::new();
+        let mut halo_colors = Vec::new();
+        let mut comments = Vec::new();
+        for line in current_frame(history, index) {
+            match line {
+                LineType::PartOfHull(a, b) => {
+                    hull.push(segment(*a, *b));
+                    hull_colors.push(rerun::Color::from_rgb(255, 255, 255));
+                }
+                LineType::LayerEdge(a, b, layer) => {
+                    let [red, green, blue, _] = layer_color(*layer).as_rgba_u8();
+                    hull.push(segment(*a, *b));
+                    hull_colors.push(rerun::Color::from_rgb(red, green, blue));
+                }
+                LineType::Temporary(a, b) | LineType::Tangent(a, b) => temporary.push(segment(*a, *b)),
+                LineType::VerticalLine(x, _) => {
+                    vertical_lines.push(segment(Vec2::new(*x, bottom), Vec2::new(*x, top)))
+                }
+                LineType::Diameter(a, b) | LineType::ClosingEdge(a, b) => overlays.push(segment(*a, *b)),
+                LineType::Region(a, b, c) => overlays.push([*a, *b, *c, *a].into_iter().map(flip).collect()),
+                LineType::Polygon(corners, _) | LineType::FilledHull(corners) => {
+                    overlays.push(corners.iter().chain(corners.first()).map(|corner| flip(*corner)).collect())
+                }
+                LineType::Circle(center, radius) => overlays.push(
+                    (0..=CIRCLE_SEGMENTS)
+                        .map(|i| {
+                            let angle = std::f32::consts::TAU * i as f32 / CIRCLE_SEGMENTS as f32;
+                            flip(*center + Vec2::from_angle(angle) * *radius)
+                        })
+                        .collect(),
+                ),
+                LineType::Arc(center, radius, start, sweep, _) => overlays.push(
+                    std::iter::once(flip(*center))
+                        .chain((0..=CIRCLE_SEGMENTS).map(|i| {
+                            let angle = start + sweep * i as f32 / CIRCLE_SEGMENTS as f32;
+                            flip(*center + Vec2::from_angle(angle) * *radius)
+                        }))
+                        .chain(std::iter::once(flip(*center)))
+                        .collect(),
+                ),
+                LineType::CollinearPoint(point) | LineType::HullVertex(point) | LineType::DiscardedPoint(point) => {
+                    highlights.push(flip(*point))
+                }
+                LineType::HighlightPoint(point, kind) => {
+                    let [red, green, blue, _] = highlight_color(*kind).as_rgba_u8();
+                    halos.push(flip(*point));
+                    halo_colors.push(rerun::Color::from_rgb(red, green, blue));
+                }
+                LineType::TextComment(comment) => comments.push(comment.as_str()),
+                LineType::ClearScreen | LineType::Milestone(_) => {}
+            }
+        }
+
+        // Every category is logged at every step, so the ones the step doesn't have are emptied
+        recording.log("hull", &rerun::LineStrips2D::new(hull).with_colors(hull_colors))?;
+        recording.log(
+            "temporary",
+            &rerun::LineStrips2D::new(temporary).with_colors([rerun::Color::from_rgb(0x70, 0x70, 0x70)]),
+        )?;
+        recording.log(
+            "vertical_lines",
+            &rerun::LineStrips2D::new(vertical_lines).with_colors([rerun::Color::from_rgb(255, 0, 0)]),
+        )?;
+        recording.log(
+            "overlays",
+            &rerun::LineStrips2D::new(overlays).with_colors([rerun::Color::from_rgb(0x33, 0xff, 0x66)]),
+        )?;
+        recording.log(
+            "highlights",
+            &rerun::Points2D::new(highlights)
+                .with_radii([point_radius * 1.5])
+                .with_colors([rerun::Color::from_rgb(0xff, 0x66, 0xb3)]),
+        )?;
+        recording.log(
+            "halos",
+            &rerun::Points2D::new(halos)
+                .with_radii([point_radius * HALO_RADIUS])
+                .with_colors(halo_colors),
+        )?;
+        if !comments.is_empty() {
+            recording.log("comment", &rerun::TextLog::new(comments.join("\n")))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the segment between the two points as a line strip
+fn segment(a: Vec2, b: Vec2) -> LineStrip2D {
+    [flip(a), flip(b)].into_iter().collect()
+}
+
+/// Flips the y axis of the point, as the 2D views of Rerun point it downwards
+fn flip(point: Vec2) -> [f32; 2] {
+    [point.x, -point.y]
+}
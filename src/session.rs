@@ -0,0 +1,107 @@
+//! Contains the saving and loading of simulation sessions.
+//!
+//! A session is stored as a JSON file holding the points of the world along with
+//! the settings that were used to create and animate them. The drawing history and the step it
+//! stopped at are stored as well, so a loaded session resumes the animation where it was saved.
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use convex_hull_simulation::algorithms::{AlgorithmType, LineType};
+use convex_hull_simulation::distributions::DistributionType;
+use convex_hull_simulation::parsing::MAX_COORDINATE;
+
+use super::{
+    StepDurations, MAX_POINT_RADIUS, MAX_TIME_STEP, MIN_POINT_RADIUS, PLAYBACK_SPEED_RANGE, STEP_DURATION_RANGE,
+};
+
+/// Path of the file the session is saved to and loaded from
+pub const SESSION_PATH: &str = "session.json";
+
+/// A snapshot of the simulation that can be written to and read from a JSON file
+#[derive(Serialize, Deserialize)]
+pub struct SessionState {
+    /// The points of the world
+    pub points: Vec<Vec2>,
+    /// The selected distribution type
+    pub distribution: DistributionType,
+    /// The selected algorithm type
+    pub algorithm: AlgorithmType,
+    /// Radius of the drawn points
+    pub point_radius: f32,
+    /// Time step of the simulation in seconds
+    pub time_step: f32,
+    /// How long the kinds of steps are shown, relative to the time step
+    #[serde(default)]
+    pub step_durations: StepDurations,
+    /// The steps drawn by the last run algorithm
+    #[serde(default)]
+    pub history: Vec<Vec<LineType>>,
+    /// Number of steps of the history that were drawn
+    #[serde(default)]
+    pub step: usize,
+    /// Whether the animation was paused
+    #[serde(default)]
+    pub paused: bool,
+    /// The hull vertices found by the last run algorithm
+    #[serde(default)]
+    pub hull: Vec<Vec2>,
+    /// Time taken to compute the hull
+    #[serde(default)]
+    pub hull_time: Duration,
+}
+
+impl SessionState {
+    /// Serializes the session to JSON and writes it to `path`
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    /// Reads the JSON file at `path` and deserializes it into a session.
+    ///
+    /// Sessions with numbers the inspector doesn't allow are rejected, as the file could have been edited by hand.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let session: Self = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        session.validate()?;
+        Ok(session)
+    }
+
+    /// Returns an error naming the first number that is out of the range the inspector allows
+    fn validate(&self) -> Result<(), String> {
+        check_range("point_radius", self.point_radius, MIN_POINT_RADIUS..=MAX_POINT_RADIUS)?;
+        check_range("time_step", self.time_step, 0.0..=MAX_TIME_STEP)?;
+        check_range("step_durations.speed", self.step_durations.speed, PLAYBACK_SPEED_RANGE)?;
+        check_range("step_durations.hull", self.step_durations.hull, STEP_DURATION_RANGE)?;
+        check_range("step_durations.temporary", self.step_durations.temporary, STEP_DURATION_RANGE)?;
+        check_range("step_durations.other", self.step_durations.other, STEP_DURATION_RANGE)?;
+
+        let coordinates = -MAX_COORDINATE..=MAX_COORDINATE;
+        for (name, points) in [("points", &self.points), ("hull", &self.hull)] {
+            for point in points {
+                check_range(name, point.x, coordinates.clone())?;
+                check_range(name, point.y, coordinates.clone())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns an error naming the field unless the value lies within the range, which also rejects NaN
+fn check_range(name: &str, value: f32, range: RangeInclusive<f32>) -> Result<(), String> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "`{}` is {}, but has to lie between {} and {}",
+            name,
+            value,
+            range.start(),
+            range.end()
+        ))
+    }
+}
@@ -0,0 +1,78 @@
+//! Contains the persistence of the inspector settings across restarts.
+//!
+//! The settings are stored as TOML in a file in the working directory on native builds, and as JSON in the
+//! `localStorage` of the browser on the web. Unlike a session they don't include the points.
+
+use serde::{Deserialize, Serialize};
+
+use convex_hull_simulation::algorithms::AlgorithmType;
+use convex_hull_simulation::distributions::DistributionType;
+
+/// Path of the file the settings are stored in on native builds, relative to the working directory
+#[cfg(not(target_arch = "wasm32"))]
+const SETTINGS_PATH: &str = "settings.toml";
+
+/// Key of the `localStorage` entry the settings are stored in on the web
+#[cfg(target_arch = "wasm32")]
+const SETTINGS_KEY: &str = "convex_hull_simulation_settings";
+
+/// The settings of the inspector that are remembered between launches
+#[derive(Serialize, Deserialize, PartialEq, Clone, Copy)]
+pub struct AppSettings {
+    /// Radius of the drawn points
+    pub point_radius: f32,
+    /// Number of points generated from the distribution
+    pub point_count: usize,
+    /// Time step of the simulation in seconds
+    pub time_step: f32,
+    /// The selected distribution type
+    pub distribution: DistributionType,
+    /// The selected algorithm type
+    pub algorithm: AlgorithmType,
+    /// Whether points are added by clicking
+    #[serde(default)]
+    pub manual_points: bool,
+}
+
+impl AppSettings {
+    /// Reads the stored settings, if there are any and they can be parsed
+    pub fn load() -> Option<Self> {
+        read_settings()
+    }
+
+    /// Serializes the settings and stores them
+    pub fn save(&self) -> Result<(), String> {
+        write_settings(self)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_settings() -> Option<AppSettings> {
+    toml::from_str(&std::fs::read_to_string(SETTINGS_PATH).ok()?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_settings(settings: &AppSettings) -> Result<(), String> {
+    let toml = toml::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(SETTINGS_PATH, toml).map_err(|e| e.to_string())
+}
+
+/// Returns the `localStorage` of the browser, if it is available
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_settings() -> Option<AppSettings> {
+    serde_json::from_str(&local_storage()?.get_item(SETTINGS_KEY).ok()??).ok()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_settings(settings: &AppSettings) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    local_storage()
+        .ok_or("localStorage is not available")?
+        .set_item(SETTINGS_KEY, &json)
+        .map_err(|e| format!("{:?}", e))
+}
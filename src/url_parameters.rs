@@ -0,0 +1,109 @@
+//! Contains the configuration of the web build through the query parameters of its URL.
+//!
+//! A link like `?algo=kps&dist=circle&n=5000` opens the simulation with the given settings, so demos can be
+//! shared. The parameters are:
+//! - `algo`: the algorithm, named like in `hull_cli`
+//! - `dist`: the distribution, one of `fibonacci`, `circle`, `perimeter`, `square`, `clusters`, `ellipse`, `ellipse-perimeter`, `ring`, `parametric`, `poisson`, `halton`, `sobol` or `grid`
+//! - `n`: the number of points
+//! - `radius`: the radius of the drawn points
+//! - `dt`: the time step of the simulation in seconds
+//! - `seed`: the seed of the random points, which is kept so the linked world can be generated again
+//!
+//! They take precedence over the stored [AppSettings](super::AppSettings). Unknown parameters and invalid values are
+//! skipped with a warning. The numbers are clamped to the ranges the inspector allows, so a link can't ask for more
+//! points than the tab can handle.
+
+use bevy::prelude::*;
+
+use convex_hull_simulation::algorithms::AlgorithmType;
+use convex_hull_simulation::distributions::DistributionType;
+
+use super::{MAX_POINT_COUNT, MAX_POINT_RADIUS, MAX_TIME_STEP, MIN_POINT_RADIUS};
+
+/// The settings given in the query parameters, the ones that aren't given are `None`
+#[derive(Default)]
+pub struct UrlParameters {
+    pub algorithm: Option<AlgorithmType>,
+    pub distribution: Option<DistributionType>,
+    pub point_count: Option<usize>,
+    pub point_radius: Option<f32>,
+    pub time_step: Option<f32>,
+    pub seed: Option<u64>,
+}
+
+impl UrlParameters {
+    /// Reads the query parameters of the page, warning about the ones that can't be used
+    pub fn read() -> Self {
+        let search = web_sys::window()
+            .and_then(|window| window.location().search().ok())
+            .unwrap_or_default();
+        let (parameters, warnings) = Self::parse(&search);
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+        parameters
+    }
+
+    /// Returns whether none of the parameters are given
+    pub fn is_empty(&self) -> bool {
+        self.algorithm.is_none()
+            && self.distribution.is_none()
+            && self.point_count.is_none()
+            && self.point_radius.is_none()
+            && self.time_step.is_none()
+            && self.seed.is_none()
+    }
+
+    /// Parses the query string, with or without its leading `?`, along with a message for every skipped parameter
+    fn parse(query: &str) -> (Self, Vec<String>) {
+        let mut parameters = Self::default();
+        let mut warnings = Vec::new();
+
+        for pair in query.trim_start_matches('?').split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let valid = match key {
+                "algo" => {
+                    parameters.algorithm = value.parse().ok();
+                    parameters.algorithm.is_some()
+                }
+                "dist" => {
+                    parameters.distribution = value.parse().ok();
+                    parameters.distribution.is_some()
+                }
+                "n" => {
+                    parameters.point_count = value.parse().ok().map(|n: usize| n.min(MAX_POINT_COUNT));
+                    parameters.point_count.is_some()
+                }
+                "radius" => {
+                    parameters.point_radius = value
+                        .parse()
+                        .ok()
+                        .filter(|radius: &f32| radius.is_finite())
+                        .map(|radius| radius.clamp(MIN_POINT_RADIUS, MAX_POINT_RADIUS));
+                    parameters.point_radius.is_some()
+                }
+                "dt" => {
+                    parameters.time_step = value
+                        .parse()
+                        .ok()
+                        .filter(|dt: &f32| dt.is_finite())
+                        .map(|dt| dt.clamp(0.0, MAX_TIME_STEP));
+                    parameters.time_step.is_some()
+                }
+                "seed" => {
+                    parameters.seed = value.parse().ok().map(|seed: u64| seed.min(u32::MAX as u64));
+                    parameters.seed.is_some()
+                }
+                _ => {
+                    warnings.push(format!("Ignoring the unknown query parameter `{}`", key));
+                    continue;
+                }
+            };
+            if !valid {
+                warnings.push(format!("Ignoring the invalid value `{}` of the query parameter `{}`", value, key));
+            }
+        }
+
+        (parameters, warnings)
+    }
+}
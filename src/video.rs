@@ -0,0 +1,104 @@
+//! Contains the export of a whole run of an algorithm as an MP4 video, enabled by the `video` feature.
+//!
+//! The frames don't come from the window, instead every step is exported with [frame_to_svg] and rasterized
+//! with [resvg] at a fixed resolution. This keeps the video independent of the window size and of the
+//! simulation time, the frames are piped into [ffmpeg](https://ffmpeg.org), which has to be installed.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use bevy::prelude::*;
+use resvg::{tiny_skia, usvg};
+
+use convex_hull_simulation::{
+    algorithms::LineType,
+    export::{current_frame, frame_to_svg},
+};
+
+/// Width of the exported video in pixels
+const VIDEO_WIDTH: u32 = 1280;
+
+/// Height of the exported video in pixels
+const VIDEO_HEIGHT: u32 = 720;
+
+/// Frames per second of the exported video
+const VIDEO_FPS: u32 = 30;
+
+/// Exports every step of the history, each one lasting the given number of seconds, as an MP4 video at `path`.
+///
+/// Only the durations are taken right away, the steps are serialized, rasterized and encoded one at a time on
+/// a separate thread, so neither the simulation waits for them nor are all the frames kept in memory.
+pub fn export_video(
+    history: Vec<Vec<LineType>>,
+    points: Vec<(Vec2, Color)>,
+    point_radius: f32,
+    duration: impl Fn(&[LineType]) -> f32,
+    path: String,
+) {
+    let durations: Vec<f32> = history.iter().map(|step| duration(step)).collect();
+
+    std::thread::spawn(move || match encode_video(&path, &history, &durations, &points, point_radius) {
+        Ok(()) => info!("Exported the video to {}", path),
+        Err(e) => warn!("Failed to export the video: {}", e),
+    });
+}
+
+/// Exports the steps to SVG, rasterizes them and pipes them into ffmpeg, every frame repeated for the duration
+/// of its step in seconds
+fn encode_video(
+    path: &str,
+    history: &[Vec<LineType>],
+    durations: &[f32],
+    points: &[(Vec2, Color)],
+    point_radius: f32,
+) -> Result<(), String> {
+    let mut ffmpeg = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pixel_format", "rgba"])
+        .args(["-video_size", &format!("{}x{}", VIDEO_WIDTH, VIDEO_HEIGHT)])
+        .args(["-framerate", &VIDEO_FPS.to_string(), "-i", "-"])
+        .args(["-c:v", "libx264", "-pix_fmt", "yuv420p", path])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start ffmpeg: {}", e))?;
+
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+
+    let mut stdin = ffmpeg.stdin.take().unwrap();
+    for (index, duration) in (1..=history.len()).zip(durations) {
+        let svg = frame_to_svg(&current_frame(history, index), points, point_radius);
+        let pixmap = rasterize(&svg, &options)?;
+        let repeats = ((duration * VIDEO_FPS as f32).round() as usize).max(1);
+        for _ in 0..repeats {
+            stdin.write_all(pixmap.data()).map_err(|e| e.to_string())?;
+        }
+    }
+    // Closing the input lets ffmpeg finish the file
+    drop(stdin);
+
+    let status = ffmpeg.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("ffmpeg exited with {}", status))
+    }
+}
+
+/// Renders the SVG into a frame of the video, scaled to fit and centered
+fn rasterize(svg: &str, options: &usvg::Options) -> Result<tiny_skia::Pixmap, String> {
+    let tree = usvg::Tree::from_str(svg, options).map_err(|e| e.to_string())?;
+    let mut pixmap = tiny_skia::Pixmap::new(VIDEO_WIDTH, VIDEO_HEIGHT).unwrap();
+    // The bars around the view box match the background of the SVG
+    pixmap.fill(tiny_skia::Color::from_rgba8(0x66, 0x66, 0x66, 0xff));
+
+    let size = tree.size();
+    let scale = (VIDEO_WIDTH as f32 / size.width()).min(VIDEO_HEIGHT as f32 / size.height());
+    let transform = tiny_skia::Transform::from_translate(
+        (VIDEO_WIDTH as f32 - size.width() * scale) / 2.0,
+        (VIDEO_HEIGHT as f32 - size.height() * scale) / 2.0,
+    )
+    .pre_scale(scale, scale);
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    Ok(pixmap)
+}
@@ -0,0 +1,35 @@
+//! Contains the entry point for computing hulls from JavaScript in the web build.
+//!
+//! The points are exchanged as JSON arrays of `[x, y]` pairs, so the hull can be computed without
+//! driving the simulation.
+
+use bevy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::algorithms::{self, AlgorithmType, DEFAULT_ORIENTATION_EPSILON};
+use crate::parsing::checked_point;
+
+/// Computes the convex hull of a JSON array of `[x, y]` pairs and returns its vertices in the same format.
+///
+/// The algorithm is one of `jarvis`, `kps`, `monotone`, `quickhull`, `chan`, `divide`, `incremental`, `melkman` or `approximate`.
+/// Invalid JSON, an unknown algorithm or a point with a non-finite coordinate or one beyond
+/// [MAX_COORDINATE](crate::parsing::MAX_COORDINATE) is thrown as an error.
+#[wasm_bindgen]
+pub fn compute_hull(points_json: &str, algorithm: &str) -> Result<String, JsError> {
+    let algorithm: AlgorithmType = algorithm.parse().map_err(|error: String| JsError::new(&error))?;
+
+    let points: Vec<[f32; 2]> = serde_json::from_str(points_json)?;
+    let points = points
+        .into_iter()
+        .enumerate()
+        .map(|(index, [x, y])| {
+            checked_point(x, y).map_err(|error| JsError::new(&format!("Point {}: `[{}, {}]` {}", index + 1, x, y, error)))
+        })
+        .collect::<Result<Vec<Vec2>, JsError>>()?;
+
+    let hull: Vec<[f32; 2]> = algorithms::compute_hull(points, algorithm, DEFAULT_ORIENTATION_EPSILON)
+        .into_iter()
+        .map(|point: Vec2| point.to_array())
+        .collect();
+    Ok(serde_json::to_string(&hull)?)
+}
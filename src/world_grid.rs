@@ -0,0 +1,181 @@
+//! Contains the background grid with labeled axes and the scale bar, which follow the zoom of the camera.
+//!
+//! Everything is painted with egui in screen space behind its windows, so the grid doesn't spawn any entities
+//! and its lines and labels keep their size at every zoom. The spacing of the grid is the smallest
+//! "nice" number, i.e. 1, 2 or 5 times a power of ten, that keeps the lines [GRID_SPACING] pixels apart.
+//! The grid the manually placed points snap to is previewed the same way, along with where the cursor snaps to.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_egui::{egui, EguiContexts};
+
+use super::{ComparisonCamera, EguiWantsFocus, GridSnap};
+
+/// Smallest distance in pixels between two lines of the grid
+const GRID_SPACING: f32 = 80.0;
+
+/// Font size of the labels of the grid and of the scale bar
+const GRID_LABEL_SIZE: f32 = 12.0;
+
+/// Smallest distance in pixels between two lines of the snapping grid, below which only the snapped cursor is shown
+const SNAP_PREVIEW_SPACING: f32 = 6.0;
+
+/// Resource to store whether the grid, the axes and the scale bar are shown.
+#[derive(Resource)]
+pub struct WorldGrid(pub bool);
+
+/// Paints the grid, the axes and the scale bar for the current view of the main camera.
+pub fn world_grid_system(
+    mut contexts: EguiContexts,
+    world_grid: Res<WorldGrid>,
+    camera_query: Query<(&GlobalTransform, &Camera, &OrthographicProjection), Without<ComparisonCamera>>,
+) {
+    if !world_grid.0 {
+        return;
+    }
+
+    let (camera_transform, camera, projection) = camera_query.single();
+    // The main camera keeps the left of the window when it's split, so its viewport always starts at the origin
+    let Some(size) = camera.logical_viewport_size() else {
+        return;
+    };
+
+    let spacing = nice_spacing(GRID_SPACING * projection.scale);
+    let decimals = (-spacing.log10().floor()).max(0.0) as usize;
+    let painter = contexts.ctx_mut().layer_painter(egui::LayerId::background());
+    let screen = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(size.x, size.y));
+    let font = egui::FontId::proportional(GRID_LABEL_SIZE);
+    let label = egui::Color32::from_white_alpha(140);
+
+    let [vertical, horizontal] = grid_lines(&painter, (camera_transform, camera), spacing, |i| {
+        egui::Stroke::new(1.0, egui::Color32::from_white_alpha(if i == 0 { 90 } else { 20 }))
+    });
+
+    // The labels stick to the axes, or to the edge of the screen when the axes are out of view
+    let origin = to_screen((camera_transform, camera), Vec2::ZERO).unwrap_or(screen.center());
+    let label_x = origin.x.clamp(screen.left() + 4.0, screen.right() - 40.0);
+    let label_y = origin.y.clamp(screen.top() + 4.0, screen.bottom() - 16.0);
+    for (i, x) in vertical {
+        let text = format!("{:.*}", decimals, i as f32 * spacing);
+        let anchor = egui::pos2(x + 2.0, label_y);
+        painter.text(anchor, egui::Align2::LEFT_TOP, text, font.clone(), label);
+    }
+    for (i, y) in horizontal.into_iter().filter(|(i, _)| *i != 0) {
+        let text = format!("{:.*}", decimals, i as f32 * spacing);
+        let anchor = egui::pos2(label_x + 2.0, y);
+        painter.text(anchor, egui::Align2::LEFT_BOTTOM, text, font.clone(), label);
+    }
+
+    // The scale bar is one grid spacing long, centered at the bottom between the comments of both histories
+    let length = spacing / projection.scale;
+    let bar_y = screen.bottom() - 48.0;
+    let (left, right) = (screen.center().x - length / 2.0, screen.center().x + length / 2.0);
+    let stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+    painter.line_segment([egui::pos2(left, bar_y), egui::pos2(right, bar_y)], stroke);
+    for x in [left, right] {
+        painter.line_segment([egui::pos2(x, bar_y - 4.0), egui::pos2(x, bar_y + 4.0)], stroke);
+    }
+    painter.text(
+        egui::pos2(screen.center().x, bar_y - 6.0),
+        egui::Align2::CENTER_BOTTOM,
+        format!("{:.*}", decimals, spacing),
+        font,
+        egui::Color32::WHITE,
+    );
+}
+
+/// Previews the grid of the [GridSnap] while it's enabled, marking the position the cursor would snap to.
+pub fn snap_preview_system(
+    mut contexts: EguiContexts,
+    grid_snap: Res<GridSnap>,
+    egui_wants_focus: Res<EguiWantsFocus>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&GlobalTransform, &Camera, &OrthographicProjection), Without<ComparisonCamera>>,
+) {
+    if !grid_snap.0 || grid_snap.1 <= 0.0 {
+        return;
+    }
+
+    let (camera_transform, camera, projection) = camera_query.single();
+    let spacing = grid_snap.1;
+    let painter = contexts.ctx_mut().layer_painter(egui::LayerId::background());
+
+    if spacing / projection.scale >= SNAP_PREVIEW_SPACING {
+        let stroke = egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(80, 200, 255, 40));
+        grid_lines(&painter, (camera_transform, camera), spacing, |_| stroke);
+    }
+
+    if egui_wants_focus.0 {
+        return;
+    }
+    let snapped = window
+        .single()
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+        .and_then(|cursor| to_screen((camera_transform, camera), (cursor / spacing).round() * spacing));
+    if let Some(snapped) = snapped {
+        painter.circle_stroke(snapped, 5.0, egui::Stroke::new(1.5, egui::Color32::from_rgb(80, 200, 255)));
+    }
+}
+
+/// Paints the lines of a grid with the given spacing over the viewport of the camera, the stroke of every line
+/// being chosen from its index counted from the axis.
+///
+/// Returns the indices along with the screen positions of the vertical and of the horizontal lines, so they can
+/// be labeled.
+fn grid_lines(
+    painter: &egui::Painter,
+    (camera_transform, camera): (&GlobalTransform, &Camera),
+    spacing: f32,
+    stroke: impl Fn(i64) -> egui::Stroke,
+) -> [Vec<(i64, f32)>; 2] {
+    let Some(size) = camera.logical_viewport_size() else {
+        return [Vec::new(), Vec::new()];
+    };
+    let (Some(top_left), Some(bottom_right)) = (
+        camera.viewport_to_world_2d(camera_transform, Vec2::ZERO),
+        camera.viewport_to_world_2d(camera_transform, size),
+    ) else {
+        return [Vec::new(), Vec::new()];
+    };
+    let screen = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(size.x, size.y));
+
+    let (min, max) = (top_left.min(bottom_right), top_left.max(bottom_right));
+    let mut vertical = Vec::new();
+    for i in (min.x / spacing).ceil() as i64..=(max.x / spacing).floor() as i64 {
+        if let Some(position) = to_screen((camera_transform, camera), Vec2::new(i as f32 * spacing, 0.0)) {
+            painter.line_segment(
+                [egui::pos2(position.x, screen.top()), egui::pos2(position.x, screen.bottom())],
+                stroke(i),
+            );
+            vertical.push((i, position.x));
+        }
+    }
+    let mut horizontal = Vec::new();
+    for i in (min.y / spacing).ceil() as i64..=(max.y / spacing).floor() as i64 {
+        if let Some(position) = to_screen((camera_transform, camera), Vec2::new(0.0, i as f32 * spacing)) {
+            painter.line_segment(
+                [egui::pos2(screen.left(), position.y), egui::pos2(screen.right(), position.y)],
+                stroke(i),
+            );
+            horizontal.push((i, position.y));
+        }
+    }
+    [vertical, horizontal]
+}
+
+/// Returns the screen position of the world position in the viewport of the camera
+fn to_screen((camera_transform, camera): (&GlobalTransform, &Camera), world: Vec2) -> Option<egui::Pos2> {
+    camera
+        .world_to_viewport(camera_transform, world.extend(0.0))
+        .map(|screen| egui::pos2(screen.x, screen.y))
+}
+
+/// Returns the smallest number of the form 1, 2 or 5 times a power of ten that is at least `minimum`
+fn nice_spacing(minimum: f32) -> f32 {
+    let power = 10f32.powf(minimum.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|factor| factor * power)
+        .find(|spacing| *spacing >= minimum)
+        .unwrap_or(10.0 * power)
+}